@@ -0,0 +1,1015 @@
+/* automatically generated by rust-bindgen 0.57.0 */
+
+#[allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]
+pub mod m {
+    #[allow(unused_imports)]
+    use self::super::m;
+    pub use super::super::v1_2_x::m::*;
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct MumbleAPI {
+        #[doc = " Frees the given pointer."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param pointer The pointer to free"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub freeMemory: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                pointer: *const ::std::os::raw::c_void,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets the connection ID of the server the user is currently active on (the user's audio output is directed at)."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param[out] connection A pointer to the memory location the ID should be written to"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then it is valid to access the"]
+        #[doc = " \tvalue of the provided pointer"]
+        pub getActiveServerConnection: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: *mut m::ConnectionT,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Checks whether the given connection has finished initializing yet."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param[out] A pointer to the boolean variable that'll hold the info whether the server has finished synchronization yet"]
+        #[doc = " \tafter this function has executed successfully."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub isConnectionSynchronized: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                synchronized: *mut bool,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the information about the local user."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param[out] userID A pointer to the memory the user's ID shall be written to"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getLocalUserID: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: *mut m::UserIdT,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the information about the given user's name."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param userID The user's ID whose name should be obtained"]
+        #[doc = " @param[out] userName A pointer to where the pointer to the allocated string (C-encoded) should be written to. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getUserName: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: m::UserIdT,
+                userName: *mut *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the information about the given channel's name."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param channelID The channel's ID whose name should be obtained"]
+        #[doc = " @param[out] channelName A pointer to where the pointer to the allocated string (C-ecoded) should be written to. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getChannelName: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                channelID: m::ChannelIdT,
+                channelName: *mut *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets an array of all users that are currently connected to the provided server. Passing a nullptr as any of the out-parameter"]
+        #[doc = " will prevent that property to be set/allocated. If you are only interested in the user count you can thus pass nullptr as the"]
+        #[doc = " users parameter and save time on allocating + freeing the channels-array while still getting the size out."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param[out] users A pointer to where the pointer of the allocated array shall be written. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @param[out] userCount A pointer to where the size of the allocated user-array shall be written to"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getAllUsers: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                users: *mut *mut m::UserIdT,
+                userCount: *mut usize,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets an array of all channels on the provided server. Passing a nullptr as any of the out-parameter will prevent"]
+        #[doc = " that property to be set/allocated. If you are only interested in the channel count you can thus pass nullptr as the"]
+        #[doc = " channels parameter and save time on allocating + freeing the channels-array while still getting the size out."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param[out] channels A pointer to where the pointer of the allocated array shall be written. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @param[out] channelCount A pointer to where the size of the allocated channel-array shall be written to"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getAllChannels: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                channels: *mut *mut m::ChannelIdT,
+                channelCount: *mut usize,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets the ID of the channel the given user is currently connected to."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param userID The ID of the user to search for"]
+        #[doc = " @param[out] A pointer to where the ID of the channel shall be written"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getChannelOfUser: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: m::UserIdT,
+                channel: *mut m::ChannelIdT,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets an array of all users in the specified channel."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param channelID The ID of the channel whose users shall be retrieved"]
+        #[doc = " @param[out] userList A pointer to where the pointer of the allocated array shall be written. The allocated memory has"]
+        #[doc = " \tto be freed by a call to freeMemory by the plugin eventually. The memory will only be allocated if this function"]
+        #[doc = " \treturns STATUS_OK."]
+        #[doc = " @param[out] userCount A pointer to where the size of the allocated user-array shall be written to"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getUsersInChannel: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                channelID: m::ChannelIdT,
+                userList: *mut *mut m::UserIdT,
+                userCount: *mut usize,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets the current transmission mode of the local user."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param[out] transmissionMode A pointer to where the transmission mode shall be written."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getLocalUserTransmissionMode: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                transmissionMode: *mut m::TransmissionModeT,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Checks whether the given user is currently locally muted."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param userID The ID of the user to check for"]
+        #[doc = " @param[out] muted A pointer to where the local mute state of that user shall be written"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub isUserLocallyMuted: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: m::UserIdT,
+                muted: *mut bool,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Checks whether the local user is currently muted."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param[out] muted A pointer to where the mute state of the local user shall be written"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub isLocalUserMuted:
+            unsafe extern "C" fn(callerID: m::PluginId, muted: *mut bool) -> m::ErrorT,
+        #[doc = " Checks whether the local user is currently deafened."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param[out] deafened A pointer to where the deaf state of the local user shall be written"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub isLocalUserDeafened:
+            unsafe extern "C" fn(callerID: m::PluginId, deafened: *mut bool) -> m::ErrorT,
+        #[doc = " Gets the hash of the given user (can be used to recognize users between restarts)"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param userID The ID of the user to search for"]
+        #[doc = " @param[out] hash A pointer to where the pointer to the allocated string (C-encoded) should be written to. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getUserHash: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: m::UserIdT,
+                hash: *mut *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets the hash of the server for the given connection (can be used to recognize servers between restarts)"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection"]
+        #[doc = " @param[out] hash A pointer to where the pointer to the allocated string (C-encoded) should be written to. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getServerHash: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                hash: *mut *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets the comment of the given user. Note that a user might have a comment configured that hasn't been synchronized"]
+        #[doc = " to this client yet. In this case this function will return EC_UNSYNCHRONIZED_BLOB. As of now there is now way"]
+        #[doc = " to request the synchronization to happen via the Plugin-API."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection"]
+        #[doc = " @param userID the ID of the user whose comment should be obtained"]
+        #[doc = " @param[out] comment A pointer to where the pointer to the allocated string (C-encoded) should be written to. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getUserComment: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: m::UserIdT,
+                comment: *mut *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Gets the description of the given channel. Note that a channel might have a description configured that hasn't been synchronized"]
+        #[doc = " to this client yet. In this case this function will return EC_UNSYNCHRONIZED_BLOB. As of now there is now way"]
+        #[doc = " to request the synchronization to happen via the Plugin-API."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection"]
+        #[doc = " @param channelID the ID of the channel whose comment should be obtained"]
+        #[doc = " @param[out] description A pointer to where the pointer to the allocated string (C-encoded) should be written to. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub getChannelDescription: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                channelID: m::ChannelIdT,
+                description: *mut *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Requests Mumble to set the local user's transmission mode to the specified one. If you only need to temporarily set"]
+        #[doc = " the transmission mode to continous, use requestMicrophoneActivationOverwrite instead as this saves you the work of"]
+        #[doc = " restoring the previous state afterwards."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param transmissionMode The requested transmission mode"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub requestLocalUserTransmissionMode: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                transmissionMode: m::TransmissionModeT,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Requests Mumble to move the given user into the given channel"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param userID The ID of the user that shall be moved"]
+        #[doc = " @param channelID The ID of the channel to move the user to"]
+        #[doc = " @param password The password of the target channel (UTF-8 encoded as a C-string). Pass NULL if the target channel does not require a"]
+        #[doc = " \tpassword for entering"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub requestUserMove: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: m::UserIdT,
+                channelID: m::ChannelIdT,
+                password: *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Requests Mumble to overwrite the microphone activation so that the microphone is always on (same as if the user had chosen"]
+        #[doc = " the continous transmission mode). If a plugin requests this overwrite, it is responsible for deactivating the overwrite again"]
+        #[doc = " once it is no longer required"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param activate Whether to activate the overwrite (false deactivates an existing overwrite)"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub requestMicrophoneActivationOvewrite:
+            unsafe extern "C" fn(callerID: m::PluginId, activate: bool) -> m::ErrorT,
+        #[doc = " Requests Mumble to set the local mute state of the given client. Note that this only affects the **local** mute state"]
+        #[doc = " opposed to a server-mute (client is globally muted by the server) or the client's own mute-state (client has muted its"]
+        #[doc = " microphone and thus isn't transmitting any audio)."]
+        #[doc = " Furthermore it must be noted that muting the local user with this function does not work (it doesn't make sense). If"]
+        #[doc = " you try to do so, this function will fail. In order to make this work, this function will also fail if the server"]
+        #[doc = " has not finished synchronizing with the client yet."]
+        #[doc = " For muting the local user, use requestLocalUserMute instead."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function."]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param userID The ID of the user that shall be muted"]
+        #[doc = " @param muted Whether to locally mute the given client (opposed to unmuting it)"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub requestLocalMute: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userID: m::UserIdT,
+                muted: bool,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Requests Mumble to set the mute state of the local user. In the UI this is referred to as \"self-mute\"."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function."]
+        #[doc = " @param muted Whether to locally mute the local user (opposed to unmuting it)"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub requestLocalUserMute:
+            unsafe extern "C" fn(callerID: m::PluginId, muted: bool) -> m::ErrorT,
+        #[doc = " Requests Mumble to set the deaf state of the local user. In the UI this is referred to as \"self-deaf\"."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function."]
+        #[doc = " @param deafened Whether to locally deafen the local user (opposed to undeafening it)"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub requestLocalUserDeaf:
+            unsafe extern "C" fn(callerID: m::PluginId, deafened: bool) -> m::ErrorT,
+        #[doc = " Sets the comment of the local user"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection"]
+        #[doc = " @param comment The new comment to use (C-encoded). A subset of HTML formatting is supported."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer"]
+        #[doc = " \tmay be accessed"]
+        pub requestSetLocalUserComment: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                comment: *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the information about a user with the specified name, if such a user exists. The search is case-sensitive."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param userName The respective user's name"]
+        #[doc = " @param[out] userID A pointer to the memory the user's ID shall be written to"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer may"]
+        #[doc = " \tbe accessed."]
+        pub findUserByName: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                userName: *const ::std::os::raw::c_char,
+                userID: *mut m::UserIdT,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the information about a channel with the specified name, if such a channel exists. The search is case-sensitive."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to use as a context"]
+        #[doc = " @param channelName The respective channel's name"]
+        #[doc = " @param[out] channelID A pointer to the memory the channel's ID shall be written to"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer may"]
+        #[doc = " \tbe accessed."]
+        pub findChannelByName: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                channelName: *const ::std::os::raw::c_char,
+                channelID: *mut m::ChannelIdT,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the current value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is a bool!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param[out] outValue A pointer to the memory the setting's value shall be written to."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer may"]
+        #[doc = " \tbe accessed."]
+        pub getMumbleSetting_bool: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                outValue: *mut bool,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the current value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is an int!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param[out] outValue A pointer to the memory the setting's value shall be written to."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer may"]
+        #[doc = " \tbe accessed."]
+        pub getMumbleSetting_int: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                outValue: *mut ::std::os::raw::c_int,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the current value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is a double!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param[out] outValue A pointer to the memory the setting's value shall be written to."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer may"]
+        #[doc = " \tbe accessed."]
+        pub getMumbleSetting_double: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                outValue: *mut f64,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Fills in the current value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is a String!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param[out] outValue The memory address to which the pointer to the setting's value (the String) will be written. The"]
+        #[doc = " \tallocated memory has to be freed by a call to freeMemory by the plugin eventually. The memory will only be"]
+        #[doc = " \tallocated if this function returns STATUS_OK."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned. Only then the passed pointer may"]
+        #[doc = " \tbe accessed."]
+        pub getMumbleSetting_string: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                outValue: *mut *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Sets the value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is a bool!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param value The value that should be set for the given setting"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub setMumbleSetting_bool: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                value: bool,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Sets the value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is an int!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param value The value that should be set for the given setting"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub setMumbleSetting_int: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                value: ::std::os::raw::c_int,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Sets the value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is a double!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param value The value that should be set for the given setting"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub setMumbleSetting_double: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                value: f64,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Sets the value of the setting with the given key. Note that this function can only be used for settings whose value"]
+        #[doc = " is a string!"]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param key The key to the desired setting"]
+        #[doc = " @param value The value that should be set for the given setting"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub setMumbleSetting_string: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                key: m::SettingsKeyT,
+                value: *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Sends the provided data to the provided client(s). This kind of data can only be received by another plugin active"]
+        #[doc = " on that client. The sent data can be seen by any active plugin on the receiving client. Therefore the sent data"]
+        #[doc = " must not contain sensitive information or anything else that shouldn't be known by others."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param connection The ID of the server-connection to send the data through (the server the given users are on)"]
+        #[doc = " @param users An array of user IDs to send the data to"]
+        #[doc = " @param userCount The size of the provided user-array"]
+        #[doc = " @param data The data array that shall be sent. This can be an arbitrary sequence of bytes."]
+        #[doc = " @param dataLength The length of the data array"]
+        #[doc = " @param dataID The ID of the sent data. This has to be used by the receiving plugin(s) to figure out what to do with"]
+        #[doc = " \tthe data. This has to be a C-encoded String."]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub sendData: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                connection: m::ConnectionT,
+                users: *const m::UserIdT,
+                userCount: usize,
+                data: *const u8,
+                dataLength: usize,
+                dataID: *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Logs the given message (typically to Mumble's console). All passed strings have to be UTF-8 encoded."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param message The message to log"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub log: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                message: *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+        #[doc = " Plays the provided sample. It uses libsndfile as a backend so the respective file format needs to be supported by it"]
+        #[doc = " in order for this to work out (see http://www.mega-nerd.com/libsndfile/)."]
+        #[doc = ""]
+        #[doc = " @param callerID The ID of the plugin calling this function"]
+        #[doc = " @param samplePath The path to the sample that shall be played (UTF-8 encoded)"]
+        #[doc = " @returns The error code. If everything went well, STATUS_OK will be returned."]
+        pub playSample: ::std::option::Option<
+            unsafe extern "C" fn(
+                callerID: m::PluginId,
+                samplePath: *const ::std::os::raw::c_char,
+            ) -> m::ErrorT,
+        >,
+    }
+    #[test]
+    fn bindgen_test_layout_MumbleAPI() {
+        assert_eq!(
+            ::std::mem::size_of::<MumbleAPI>(),
+            304usize,
+            concat!("Size of: ", stringify!(MumbleAPI))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<MumbleAPI>(),
+            8usize,
+            concat!("Alignment of ", stringify!(MumbleAPI))
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).freeMemory as *const _ as usize },
+            0usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(freeMemory)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).getActiveServerConnection as *const _ as usize
+            },
+            8usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getActiveServerConnection)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).isConnectionSynchronized as *const _ as usize
+            },
+            16usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(isConnectionSynchronized)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getLocalUserID as *const _ as usize },
+            24usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getLocalUserID)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getUserName as *const _ as usize },
+            32usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getUserName)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getChannelName as *const _ as usize },
+            40usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getChannelName)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getAllUsers as *const _ as usize },
+            48usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getAllUsers)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getAllChannels as *const _ as usize },
+            56usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getAllChannels)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getChannelOfUser as *const _ as usize },
+            64usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getChannelOfUser)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getUsersInChannel as *const _ as usize },
+            72usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getUsersInChannel)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).getLocalUserTransmissionMode as *const _
+                    as usize
+            },
+            80usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getLocalUserTransmissionMode)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).isUserLocallyMuted as *const _ as usize
+            },
+            88usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(isUserLocallyMuted)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).isLocalUserMuted as *const _ as usize },
+            96usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(isLocalUserMuted)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).isLocalUserDeafened as *const _ as usize
+            },
+            104usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(isLocalUserDeafened)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getUserHash as *const _ as usize },
+            112usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getUserHash)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getServerHash as *const _ as usize },
+            120usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getServerHash)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).getUserComment as *const _ as usize },
+            128usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getUserComment)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).getChannelDescription as *const _ as usize
+            },
+            136usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getChannelDescription)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).requestLocalUserTransmissionMode as *const _
+                    as usize
+            },
+            144usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(requestLocalUserTransmissionMode)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).requestUserMove as *const _ as usize },
+            152usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(requestUserMove)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).requestMicrophoneActivationOvewrite
+                    as *const _ as usize
+            },
+            160usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(requestMicrophoneActivationOvewrite)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).requestLocalMute as *const _ as usize },
+            168usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(requestLocalMute)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).requestLocalUserMute as *const _ as usize
+            },
+            176usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(requestLocalUserMute)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).requestLocalUserDeaf as *const _ as usize
+            },
+            184usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(requestLocalUserDeaf)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).requestSetLocalUserComment as *const _
+                    as usize
+            },
+            192usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(requestSetLocalUserComment)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).findUserByName as *const _ as usize },
+            200usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(findUserByName)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).findChannelByName as *const _ as usize },
+            208usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(findChannelByName)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).getMumbleSetting_bool as *const _ as usize
+            },
+            216usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getMumbleSetting_bool)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).getMumbleSetting_int as *const _ as usize
+            },
+            224usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getMumbleSetting_int)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).getMumbleSetting_double as *const _ as usize
+            },
+            232usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getMumbleSetting_double)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).getMumbleSetting_string as *const _ as usize
+            },
+            240usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(getMumbleSetting_string)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).setMumbleSetting_bool as *const _ as usize
+            },
+            248usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(setMumbleSetting_bool)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).setMumbleSetting_int as *const _ as usize
+            },
+            256usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(setMumbleSetting_int)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).setMumbleSetting_double as *const _ as usize
+            },
+            264usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(setMumbleSetting_double)
+            )
+        );
+        assert_eq!(
+            unsafe {
+                &(*(::std::ptr::null::<MumbleAPI>())).setMumbleSetting_string as *const _ as usize
+            },
+            272usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(setMumbleSetting_string)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).sendData as *const _ as usize },
+            280usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(sendData)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).log as *const _ as usize },
+            288usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(log)
+            )
+        );
+        assert_eq!(
+            unsafe { &(*(::std::ptr::null::<MumbleAPI>())).playSample as *const _ as usize },
+            296usize,
+            concat!(
+                "Offset of field: ",
+                stringify!(MumbleAPI),
+                "::",
+                stringify!(playSample)
+            )
+        );
+    }
+}
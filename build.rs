@@ -1,21 +1,49 @@
-#![feature(nll)]
-
 #[macro_use]
 extern crate const_format;
 extern crate bindgen;
 
-use heck;
-use regex;
+#[path = "build/codegen.rs"]
+mod codegen;
+
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+const MUMBLE_NAME_ROOT: &str = "mumble";
+const MUMBLE_WRAPPER_NAME: &str = concatcp!(MUMBLE_NAME_ROOT, "-wrapper.h");
+/// Vendored plugin API headers, one directory per API version
+const MUMBLE_HEADERS_DIR: &str = "headers";
+/// Checked-in bindings used unless regeneration is requested (or fails)
+const MUMBLE_PREGENERATED_DIR: &str = "bindings";
+/// API versions with vendored headers, oldest first; `1.0.x` is read from
+/// `headers/1.0.x/*_v_1_0_x.h`. The newest provides the types shared by every version.
+const MUMBLE_API_VERSIONS: &[&str] = &["1.0.x", "1.2.x"];
 
-const MUMBLE_NAME_ROOT: &'static str = "mumble";
-const MUMBLE_WRAPPER_NAME: &'static str = concatcp!(MUMBLE_NAME_ROOT, "-wrapper.h");
-const MUMBLE_BINDINGS_NAME: &'static str = concatcp!(MUMBLE_NAME_ROOT, ".rs");
-const MUMBLE_WRAPPER_SRC: &'static str = "src/";
-const MUMBLE_WRAPPER: &'static str = concatcp!(MUMBLE_WRAPPER_SRC, MUMBLE_WRAPPER_NAME);
-const MUMBLE_BINDINGS: &'static str = MUMBLE_BINDINGS_NAME;
+/// Bindgen newtypes that identify something; they get `Hash` and `Ord` for use as map keys
+const MUMBLE_ID_NEWTYPES: &[&str] = &["ConnectionT", "UserIdT", "ChannelIdT", "PluginId"];
+/// Functions whose `MumbleAPI` wrappers are written by hand in `src/lib.rs`, because their
+/// semantics go beyond the mechanical mapping (infallible queries, optional arguments,
+/// "not found" as `None`, version-dependent arguments, or a name that predates generation)
+const MUMBLE_HAND_WRITTEN_WRAPPERS: &[&str] = &[
+    "freeMemory",
+    "getActiveServerConnection",
+    "isConnectionSynchronized",
+    "isUserLocallyMuted",
+    "requestUserMove",
+    "requestMicrophoneActivationOvewrite",
+    "findUserByName",
+    "findChannelByName",
+    "sendData",
+    "playSample",
+];
+/// Generated `impl MumbleAPI` block, included by `src/lib.rs`
+const MUMBLE_WRAPPERS_NAME: &str = "mumble_api.rs";
+/// Generated tests of those wrappers, included by `src/api_tests.rs`
+const MUMBLE_WRAPPER_TESTS_NAME: &str = "mumble_api_tests.rs";
+
+const REGENERATE_ENV: &str = "MUMBLE_SYS_REGENERATE_BINDINGS";
+const UPDATE_PREGENERATED_ENV: &str = "MUMBLE_SYS_UPDATE_PREGENERATED";
 
 #[derive(Debug)]
 struct CustomCallbacks {
@@ -33,9 +61,6 @@ impl CustomCallbacks {
         if original_item_name == "root" {
             return Some("m".into());
         }
-        let is_mumble_function_prefix = regex::RegexBuilder::new(r"^mumble_[a-z].+$")
-            .build()
-            .unwrap();
         match original_item_name {
             "Version" => return None,
             "MumbleStringWrapper" => return None,
@@ -44,60 +69,62 @@ impl CustomCallbacks {
             x if x.starts_with("Mumble_") && x.chars().filter(|x| *x == '_').count() == 1 => {
                 return Some(x["Mumble_".len()..].into())
             }
+            // Exported plugin functions keep their C names, e.g. `mumble_getName`
             x if x.starts_with("mumble_")
                 && !x.ends_with("_t")
-                && is_mumble_function_prefix.is_match(&x) =>
+                && x["mumble_".len()..].starts_with(|c: char| c.is_ascii_lowercase())
+                && x.len() > "mumble_".len() + 1 =>
             {
                 return Some(x.into())
             }
 
             _ => {}
         }
-        let strip_mumble_prefix = regex::RegexBuilder::new(r"^[Mm]umble_(.+)$")
-            .build()
-            .unwrap();
-
-        let name: String = strip_mumble_prefix
-            .replace(original_item_name, r"$1")
-            .into();
+        let name = original_item_name
+            .strip_prefix("mumble_")
+            .or_else(|| original_item_name.strip_prefix("Mumble_"))
+            .filter(|rest| !rest.is_empty())
+            .unwrap_or(original_item_name);
 
         // methods should become snake case; types should become pascal case
         let name: String = if !name.ends_with("_t") {
-            heck::SnekCase::to_snek_case(name.as_str()).into()
+            heck::SnekCase::to_snek_case(name)
         } else {
             let name = name.replace("id_", "Id_");
-
-            let unsnake = regex::RegexBuilder::new(r"(?:^|_)([a-z])").build().unwrap();
-
-            let name = if name.starts_with(|c: char| c.is_alphabetic() && c.is_lowercase()) {
-                unsnake
-                    .replace_all(&name, |cap: &regex::Captures| {
-                        cap.get(1).map(|m| m.as_str().to_uppercase()).unwrap()
-                    })
-                    .into()
+            if name.starts_with(|c: char| c.is_alphabetic() && c.is_lowercase()) {
+                unsnake(&name)
             } else {
                 name
-            };
-
-            name
+            }
         };
         Some(name)
     }
 }
 
+/// Uppercases each lowercase letter that starts the name or follows an underscore, dropping
+/// that underscore: `userId_t` becomes `UserIdT`.
+fn unsnake(name: &str) -> String {
+    let mut unsnaked = String::with_capacity(name.len());
+    for (index, part) in name.split('_').enumerate() {
+        match part.chars().next() {
+            Some(first) if first.is_ascii_lowercase() => {
+                unsnaked.push(first.to_ascii_uppercase());
+                unsnaked.push_str(&part[1..]);
+            }
+            _ => {
+                if index > 0 {
+                    unsnaked.push('_');
+                }
+                unsnaked.push_str(part);
+            }
+        }
+    }
+    unsnaked
+}
+
 impl bindgen::callbacks::ParseCallbacks for CustomCallbacks {
     fn item_name(&self, original_item_name: &str) -> Option<String> {
-        let new_name = self.item_name_handler(original_item_name);
-
-        println!(
-            "GEN NAME: {} = {}",
-            original_item_name,
-            match &new_name {
-                Some(x) => x.as_str(),
-                None => original_item_name,
-            }
-        );
-        new_name
+        self.item_name_handler(original_item_name)
     }
 
     fn include_file(&self, filename: &str) {
@@ -105,73 +132,57 @@ impl bindgen::callbacks::ParseCallbacks for CustomCallbacks {
     }
 }
 
-fn main() {
-    // println!("cargo:rerun-if-changed={}", MUMBLE_WRAPPER);
-    env_logger::builder()
-        .format(|buf, record| {
-            use std::io::Write;
-            writeln!(buf, "{}: {:#?}", record.level(), record.args())
-        })
-        .init();
+fn version_suffix(api_version: &str) -> String {
+    api_version.replace('.', "_")
+}
 
-    // let out_file = if cfg!(feature = "idebuild") {
-    let out_dir = env::current_dir().unwrap();
-    let out_file = out_dir.join(MUMBLE_WRAPPER_SRC).join(MUMBLE_BINDINGS);
-    // } else {
-    //     let out_dir = std::path::PathBuf::from(env::var("OUT_DIR").unwrap());
-    //     let out_file = out_dir.join(MUMBLE_BINDINGS);
-    //     out_file
-    // };
-
-    let should_build = !out_file.exists() || true; //TODO: Remove || true
-    if should_build {
-        let mumble_sources_symlink = env::current_dir().unwrap().join("mumble_sources");
-        let mumble_home = if mumble_sources_symlink.exists() {
-            mumble_sources_symlink
-                .canonicalize()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string()
-        } else {
-            let home_path = env::var("MUMBLE_HOME")
-                .expect("Must have a MUMBLE_HOME environment variable set if no mumble_sources symlink is present")
-                .trim_end_matches("/")
-                .to_string();
-            if !PathBuf::from(&home_path).exists() {
-                panic!(
-                    "No mumble_sources directory in repo root, and MUMBLE_HOME env var referred to non-existent location"
-                )
-            }
-            home_path
-        };
+fn bindings_file_name(api_version: &str) -> String {
+    format!("{}_{}.rs", MUMBLE_NAME_ROOT, version_suffix(api_version))
+}
 
-        // TODO: This should be done with procedural macros instead
-        let regex_replace_with_nonnull = regex::RegexBuilder::new(
-            r"\bpub ([^\s:]+): (?:(?:::std::)?option::)?Option<\s*(.+?,)\s*>,\n",
-        )
-        .dot_matches_new_line(true)
-        .build()
-        .unwrap();
-        let replace_fn_ptrs_nonnull = |s: &str| -> String {
-            regex_replace_with_nonnull
-                .replace_all(s, "pub ${1}: $2\n")
-                .to_string()
-        };
+/// Runs bindgen over the vendored headers of `api_version`. With `shared_from`, only the API
+/// struct is generated, written against the types of that (newer) version's bindings.
+/// Returns None if libclang is unavailable or the headers fail to parse.
+fn generate_bindings(
+    manifest_dir: &Path,
+    api_version: &str,
+    shared_from: Option<&str>,
+) -> Option<String> {
+    let header_dir = manifest_dir.join(MUMBLE_HEADERS_DIR).join(api_version);
+    let suffix = version_suffix(api_version);
+    let components_name = format!("PluginComponents_v_{}.h", suffix);
+    let wrapper = format!(
+        "#include <string.h>\n#include \"{}\"\n#include \"MumblePlugin_v_{}.h\"\n",
+        components_name, suffix
+    );
 
-        let bindings = bindgen::Builder::default()
-            .clang_args(&["-x", "c++", "-std=c++20"])
-            .clang_arg(format!("-I{}/plugins", mumble_home))
-            // .layout_tests(false)
-            .rust_target(bindgen::RustTarget::Nightly)
-            .enable_cxx_namespaces()
-            .default_enum_style(bindgen::EnumVariation::Rust {
-                non_exhaustive: false,
-            })
-            .default_alias_style(bindgen::AliasVariation::NewTypeDeref)
-            .type_alias("mumble_error_t")
-            .derive_eq(true)
-            .size_t_is_usize(true)
+    let rust_target = if env::var_os("CARGO_FEATURE_NIGHTLY").is_some() {
+        bindgen::RustTarget::Nightly
+    } else {
+        bindgen::LATEST_STABLE_RUST
+    };
+    let builder = bindgen::Builder::default()
+        .clang_args(&["-x", "c++", "-std=c++20"])
+        .clang_arg(format!("-I{}", header_dir.display()))
+        // .layout_tests(false)
+        .rust_target(rust_target)
+        .enable_cxx_namespaces()
+        .default_enum_style(bindgen::EnumVariation::Rust {
+            non_exhaustive: false,
+        })
+        .default_alias_style(bindgen::AliasVariation::NewTypeDeref)
+        .type_alias("mumble_error_t")
+        .derive_eq(true)
+        .size_t_is_usize(true);
+    let builder = match shared_from {
+        Some(newer) => builder
+            .whitelist_type("MumbleAPI_v.*")
+            .blacklist_type("Version|MumbleStringWrapper|Mumble_.*|mumble_.*_t")
+            .module_raw_line(
+                "root",
+                format!("pub use super::super::v{}::m::*;", version_suffix(newer)),
+            ),
+        None => builder
             // <allow-list>
             .whitelist_var("MUMBLE_PLUGIN_API_VERSION")
             .whitelist_type("MumbleAPI_v.*")
@@ -213,28 +224,108 @@ fn main() {
             .whitelist_function("mumble_onKeyEvent")
             // Plugin updates
             .whitelist_function("mumble_hasUpdate")
-            .whitelist_function("mumble_getUpdateDownloadURL")
-            // </allow-list>
-            .detect_include_paths(true)
-            // PluginComponents has references to std::string which apparently explode these days
-            // We replace the ifdefs to get it to parse
-            .header_contents(
-                "PluginComponents_v_1_0_x.h",
-                &std::fs::read_to_string(
-                    PathBuf::from(&mumble_home)
-                        .join("plugins")
-                        .join("PluginComponents_v_1_0_x.h"),
-                )
+            .whitelist_function("mumble_getUpdateDownloadURL"),
+        // </allow-list>
+    };
+    let builder = builder
+        .detect_include_paths(true)
+        // PluginComponents has references to std::string which apparently explode these days
+        // We replace the ifdefs to get it to parse
+        .header_contents(
+            &components_name,
+            &fs::read_to_string(header_dir.join(&components_name))
                 .expect("PluginComponents file must exist")
                 .replace("#ifdef __cplusplus", "#ifdef __never"),
-            )
-            .header(MUMBLE_WRAPPER)
-            .parse_callbacks(Box::new(CustomCallbacks::new()))
-            .generate()
-            .expect("Unable to generate bindings");
-
-        fs::write(&out_file, replace_fn_ptrs_nonnull(&bindings.to_string()))
-            .expect("Couldn't write bindings!");
-        println!("Wrote bindings to {:?}", &out_file);
+        )
+        .header_contents(MUMBLE_WRAPPER_NAME, &wrapper)
+        .parse_callbacks(Box::new(CustomCallbacks::new()));
+
+    // bindgen panics rather than erroring when libclang can't be loaded
+    match panic::catch_unwind(AssertUnwindSafe(|| builder.generate())) {
+        Ok(Ok(bindings)) => Some(bindings.to_string()),
+        Ok(Err(())) => {
+            println!(
+                "cargo:warning=Failed to generate bindings for plugin API {}; using pre-generated bindings",
+                api_version
+            );
+            None
+        }
+        Err(_) => {
+            println!(
+                "cargo:warning=libclang is unavailable; using pre-generated bindings for plugin API {}",
+                api_version
+            );
+            None
+        }
+    }
+}
+
+fn main() {
+    env_logger::builder()
+        .format(|buf, record| {
+            use std::io::Write;
+            writeln!(buf, "{}: {}", record.level(), record.args())
+        })
+        .init();
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", MUMBLE_HEADERS_DIR);
+    println!("cargo:rerun-if-changed={}", MUMBLE_PREGENERATED_DIR);
+    println!("cargo:rerun-if-env-changed={}", REGENERATE_ENV);
+    println!("cargo:rerun-if-env-changed={}", UPDATE_PREGENERATED_ENV);
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let regenerate = env::var_os("CARGO_FEATURE_REGENERATE_BINDINGS").is_some()
+        || env::var_os(REGENERATE_ENV).is_some();
+
+    let newest = MUMBLE_API_VERSIONS.last().cloned();
+    let mut parsed = Vec::new();
+    for api_version in MUMBLE_API_VERSIONS {
+        let file_name = bindings_file_name(api_version);
+        let out_file = out_dir.join(&file_name);
+        let pregenerated = manifest_dir.join(MUMBLE_PREGENERATED_DIR).join(&file_name);
+
+        let generated = if regenerate {
+            let shared_from = newest.filter(|newest| newest != api_version);
+            generate_bindings(&manifest_dir, api_version, shared_from)
+        } else {
+            None
+        };
+        let bindings = match generated {
+            Some(bindings) => {
+                if env::var_os(UPDATE_PREGENERATED_ENV).is_some() {
+                    fs::write(&pregenerated, &bindings)
+                        .expect("Couldn't update pre-generated bindings!");
+                }
+                bindings
+            }
+            None => fs::read_to_string(&pregenerated).unwrap_or_else(|e| {
+                panic!(
+                    "Couldn't read pre-generated bindings from {:?}: {}",
+                    pregenerated, e
+                )
+            }),
+        };
+
+        let bindings = codegen::postprocess(&bindings, MUMBLE_ID_NEWTYPES)
+            .unwrap_or_else(|e| panic!("Couldn't parse bindings for {}: {}", api_version, e));
+        fs::write(&out_file, codegen::render(&bindings)).expect("Couldn't write bindings!");
+        parsed.push((*api_version, bindings));
+    }
+
+    let tables: Vec<codegen::FunctionTable> = parsed
+        .iter()
+        .map(|(version, bindings)| codegen::FunctionTable { version, bindings })
+        .collect();
+    let (wrappers, tests, skipped) = codegen::api_wrappers(&tables, MUMBLE_HAND_WRITTEN_WRAPPERS);
+    for reason in skipped {
+        println!(
+            "cargo:warning=No MumbleAPI wrapper generated for {}; add one by hand",
+            reason
+        );
     }
+    fs::write(out_dir.join(MUMBLE_WRAPPERS_NAME), wrappers).expect("Couldn't write wrappers!");
+    fs::write(out_dir.join(MUMBLE_WRAPPER_TESTS_NAME), tests)
+        .expect("Couldn't write wrapper tests!");
 }
@@ -0,0 +1,1012 @@
+//! Typed post-processing of bindgen's output, and generation of the safe `MumbleAPI`
+//! wrappers from the function tables of every supported API version.
+
+use heck::SnekCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::BTreeSet;
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    Attribute, BareFnArg, Expr, ExprLit, Field, File, GenericArgument, Item, ItemStruct, Lit, Meta,
+    PathArguments, ReturnType, Token, Type, TypeBareFn, TypePtr,
+};
+
+const HEADER: &str = "// Generated by build.rs (see build/codegen.rs); do not edit\n\n";
+
+/// Parses the bindings and applies every transformation below.
+pub fn postprocess(source: &str, id_newtypes: &[&str]) -> syn::Result<File> {
+    let mut file = syn::parse_file(source)?;
+    NonNullFunctions.visit_file_mut(&mut file);
+    DocCleanup.visit_file_mut(&mut file);
+    NullFreeOffsets.visit_file_mut(&mut file);
+    if let Some(items) = module_items_mut(&mut file) {
+        derive_id_traits(items, id_newtypes);
+        drop_function_comparisons(items);
+        add_enum_conversions(items);
+    }
+    Ok(file)
+}
+
+pub fn render(file: &File) -> String {
+    format!("{}{}", HEADER, prettyplease::unparse(file))
+}
+
+fn module_items_mut(file: &mut File) -> Option<&mut Vec<Item>> {
+    file.items.iter_mut().find_map(|item| match item {
+        Item::Mod(module) if module.ident == "m" => module.content.as_mut().map(|(_, items)| items),
+        _ => None,
+    })
+}
+
+fn module_items(file: &File) -> &[Item] {
+    file.items
+        .iter()
+        .find_map(|item| match item {
+            Item::Mod(module) if module.ident == "m" => {
+                module.content.as_ref().map(|(_, items)| items.as_slice())
+            }
+            _ => None,
+        })
+        .unwrap_or(&[])
+}
+
+/// Mumble always fills in every function, so `Option<fn>` fields become bare function pointers.
+struct NonNullFunctions;
+
+impl VisitMut for NonNullFunctions {
+    fn visit_field_mut(&mut self, field: &mut Field) {
+        if let Some(function) = option_inner(&field.ty).cloned() {
+            field.ty = function;
+        }
+        visit_mut::visit_field_mut(self, field);
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) => &path.path,
+        _ => return None,
+    };
+    let last = path.segments.last()?;
+    let args = match &last.arguments {
+        PathArguments::AngleBracketed(args) if last.ident == "Option" => args,
+        _ => return None,
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(inner @ Type::BareFn(_))) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// bindgen's layout tests take field offsets through a null pointer, which current
+/// compilers reject; they are rewritten to measure an uninitialized value instead.
+struct NullFreeOffsets;
+
+impl VisitMut for NullFreeOffsets {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Some((ty, member)) = null_offset(expr) {
+            *expr = syn::parse_quote! {{
+                let uninit = ::std::mem::MaybeUninit::<#ty>::uninit();
+                let base = uninit.as_ptr();
+                ::std::ptr::addr_of!((*base).#member) as usize - base as usize
+            }};
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    /// The offsets are arguments of `assert_eq!`, which syn leaves as tokens
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        if mac.path.is_ident("assert_eq") {
+            let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+            if let Ok(mut args) = mac.parse_body_with(parser) {
+                args.iter_mut().for_each(|arg| self.visit_expr_mut(arg));
+                mac.tokens = args.to_token_stream();
+            }
+        }
+        visit_mut::visit_macro_mut(self, mac);
+    }
+}
+
+/// Matches `&(*(::std::ptr::null::<T>())).member as *const _ as usize`
+fn null_offset(expr: &Expr) -> Option<(Type, syn::Member)> {
+    fn unparen(expr: &Expr) -> &Expr {
+        match expr {
+            Expr::Paren(paren) => unparen(&paren.expr),
+            _ => expr,
+        }
+    }
+    let field = match expr {
+        Expr::Cast(outer) => match unparen(&outer.expr) {
+            Expr::Cast(inner) => match unparen(&inner.expr) {
+                Expr::Reference(reference) => match unparen(&reference.expr) {
+                    Expr::Field(field) => field,
+                    _ => return None,
+                },
+                _ => return None,
+            },
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let call = match unparen(&field.base) {
+        Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Deref(_)) => match unparen(&unary.expr)
+        {
+            Expr::Call(call) if call.args.is_empty() => call,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let last = match &*call.func {
+        Expr::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) if last.ident == "null" => match args.args.first() {
+            Some(GenericArgument::Type(ty)) => Some((ty.clone(), field.member.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Rewrites Doxygen comments as rustdoc markdown: `@param` lists become an `# Arguments`
+/// section, tab-indented continuations are joined rather than rendered as code blocks,
+/// and `@code` becomes a fenced block.
+struct DocCleanup;
+
+impl DocCleanup {
+    fn clean(&self, attrs: &mut Vec<Attribute>) {
+        let lines: Vec<String> = attrs.iter().filter_map(doc_line).collect();
+        if lines.is_empty() {
+            return;
+        }
+        let position = attrs
+            .iter()
+            .position(|attr| doc_line(attr).is_some())
+            .unwrap();
+        attrs.retain(|attr| doc_line(attr).is_none());
+        let cleaned = clean_doc(&lines).into_iter().map(|line| {
+            let line = if line.is_empty() {
+                line
+            } else {
+                format!(" {}", line)
+            };
+            syn::parse_quote!(#[doc = #line])
+        });
+        attrs.splice(position..position, cleaned);
+    }
+}
+
+impl VisitMut for DocCleanup {
+    fn visit_field_mut(&mut self, node: &mut Field) {
+        self.clean(&mut node.attrs);
+        visit_mut::visit_field_mut(self, node);
+    }
+
+    fn visit_variant_mut(&mut self, node: &mut syn::Variant) {
+        self.clean(&mut node.attrs);
+        visit_mut::visit_variant_mut(self, node);
+    }
+
+    fn visit_item_struct_mut(&mut self, node: &mut ItemStruct) {
+        self.clean(&mut node.attrs);
+        visit_mut::visit_item_struct_mut(self, node);
+    }
+
+    fn visit_item_enum_mut(&mut self, node: &mut syn::ItemEnum) {
+        self.clean(&mut node.attrs);
+        visit_mut::visit_item_enum_mut(self, node);
+    }
+
+    fn visit_item_const_mut(&mut self, node: &mut syn::ItemConst) {
+        self.clean(&mut node.attrs);
+        visit_mut::visit_item_const_mut(self, node);
+    }
+
+    fn visit_foreign_item_fn_mut(&mut self, node: &mut syn::ForeignItemFn) {
+        self.clean(&mut node.attrs);
+        visit_mut::visit_foreign_item_fn_mut(self, node);
+    }
+}
+
+fn doc_line(attr: &Attribute) -> Option<String> {
+    match &attr.meta {
+        Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Some(lit.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn push_section(out: &mut Vec<String>, heading: &str) {
+    if out.last().map_or(false, |line| !line.is_empty()) {
+        out.push(String::new());
+    }
+    out.push(format!("# {}", heading));
+    out.push(String::new());
+}
+
+fn clean_doc(lines: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut in_code = false;
+    let mut in_arguments = false;
+    for line in lines {
+        let line = line.strip_prefix(' ').unwrap_or(line);
+        let trimmed = line.trim();
+        if in_code {
+            if trimmed == "@endcode" {
+                out.push("```".into());
+                in_code = false;
+            } else {
+                out.push(line.replace('\t', "    ").trim_end().into());
+            }
+            continue;
+        }
+        if trimmed == "@code" {
+            out.push("```c".into());
+            in_code = true;
+        } else if line.starts_with('\t') && out.last().map_or(false, |last| !last.is_empty()) {
+            let last = out.last_mut().unwrap();
+            last.push(' ');
+            last.push_str(trimmed);
+        } else if let Some((direction, rest)) = param_tag(trimmed) {
+            if !in_arguments {
+                push_section(&mut out, "Arguments");
+                in_arguments = true;
+            }
+            // Parameter names are lowerCamelCase; some entries omit the name entirely
+            let (name, description) = match rest.split_once(' ') {
+                Some((name, description)) if name.starts_with(|c: char| c.is_lowercase()) => {
+                    (Some(name), description)
+                }
+                _ => (None, rest),
+            };
+            out.push(match (name, direction) {
+                (Some(name), Some(direction)) => {
+                    format!("* `{}` ({}) - {}", name, direction, description)
+                }
+                (Some(name), None) => format!("* `{}` - {}", name, description),
+                (None, Some(direction)) => format!("* ({}) {}", direction, description),
+                (None, None) => format!("* {}", description),
+            });
+        } else if let Some(rest) = trimmed
+            .strip_prefix("@returns ")
+            .or_else(|| trimmed.strip_prefix("@return "))
+        {
+            push_section(&mut out, "Returns");
+            out.push(rest.into());
+            in_arguments = false;
+        } else {
+            out.push(line.trim_end().into());
+        }
+    }
+    while out.last().map_or(false, |line| line.is_empty()) {
+        out.pop();
+    }
+    out
+}
+
+fn param_tag(line: &str) -> Option<(Option<&'static str>, &str)> {
+    let rest = line.strip_prefix("@param")?;
+    let (direction, rest) = if let Some(rest) = rest.strip_prefix("[out]") {
+        (Some("out"), rest)
+    } else if let Some(rest) = rest.strip_prefix("[in]") {
+        (Some("in"), rest)
+    } else {
+        (None, rest)
+    };
+    rest.strip_prefix(' ').map(|rest| (direction, rest.trim()))
+}
+
+/// IDs are used as map keys throughout the crate.
+fn derive_id_traits(items: &mut [Item], id_newtypes: &[&str]) {
+    for item in items {
+        let item = match item {
+            Item::Struct(item) if id_newtypes.iter().any(|name| item.ident == name) => item,
+            _ => continue,
+        };
+        edit_derives(item, |derives| {
+            for extra in &["Hash", "PartialOrd", "Ord"] {
+                if !derives.iter().any(|path| path.is_ident(extra)) {
+                    derives.push(syn::Path::from(format_ident!("{}", extra)));
+                }
+            }
+        });
+    }
+}
+
+/// Function pointers have no meaningful equality (the same function may have several
+/// addresses), so the function tables don't get the comparisons bindgen derives.
+fn drop_function_comparisons(items: &mut [Item]) {
+    for item in items {
+        let item = match item {
+            Item::Struct(item)
+                if item
+                    .fields
+                    .iter()
+                    .any(|field| matches!(field.ty, Type::BareFn(_))) =>
+            {
+                item
+            }
+            _ => continue,
+        };
+        edit_derives(item, |derives| {
+            derives.retain(|path| !["PartialEq", "Eq", "Hash"].iter().any(|c| path.is_ident(c)));
+        });
+    }
+}
+
+fn edit_derives(item: &mut ItemStruct, edit: impl Fn(&mut Vec<syn::Path>)) {
+    for attr in item.attrs.iter_mut() {
+        if !attr.path().is_ident("derive") {
+            continue;
+        }
+        let mut derives: Vec<syn::Path> = attr
+            .parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+            .expect("bindgen derives are paths")
+            .into_iter()
+            .collect();
+        edit(&mut derives);
+        *attr = syn::parse_quote!(#[derive(#(#derives),*)]);
+    }
+}
+
+/// Adds `From` both ways between each enum and the newtype bindgen wraps it in.
+fn add_enum_conversions(items: &mut Vec<Item>) {
+    let enums: BTreeSet<String> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Enum(item) => Some(item.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+    let conversions: Vec<Item> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(item) => Some(item),
+            _ => None,
+        })
+        .filter_map(|item| {
+            let field = match &item.fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+                _ => return None,
+            };
+            let inner = match &field.ty {
+                Type::Path(path) => path.path.segments.last()?.ident.to_string(),
+                _ => return None,
+            };
+            if !enums.contains(&inner) {
+                return None;
+            }
+            let newtype = &item.ident;
+            let inner = &field.ty;
+            Some(vec![
+                syn::parse_quote! {
+                    impl From<#inner> for #newtype {
+                        fn from(value: #inner) -> Self {
+                            #newtype(value)
+                        }
+                    }
+                },
+                syn::parse_quote! {
+                    impl From<#newtype> for #inner {
+                        fn from(value: #newtype) -> Self {
+                            value.0
+                        }
+                    }
+                },
+            ])
+        })
+        .flatten()
+        .collect();
+    items.extend(conversions);
+}
+
+/// An API version's function table, as parsed from its bindings.
+pub struct FunctionTable<'a> {
+    /// e.g. `1.2.x`
+    pub version: &'a str,
+    pub bindings: &'a File,
+}
+
+impl FunctionTable<'_> {
+    fn variant(&self) -> syn::Ident {
+        format_ident!("V{}", self.version.trim_end_matches(".x").replace('.', "_"))
+    }
+
+    /// The module under `crate::mumble` holding this version's bindings
+    fn module(&self) -> syn::Ident {
+        format_ident!("v{}", self.version.replace('.', "_"))
+    }
+
+    fn functions(&self) -> Vec<&Field> {
+        module_items(self.bindings)
+            .iter()
+            .find_map(|item| match item {
+                Item::Struct(item) if item.ident == "MumbleAPI" => Some(item),
+                _ => None,
+            })
+            .map(|item| item.fields.iter().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn bare_fn(field: &Field) -> Option<&TypeBareFn> {
+    match &field.ty {
+        Type::BareFn(function) => Some(function),
+        _ => None,
+    }
+}
+
+/// Compares signatures by type only; parameter names don't affect the ABI.
+fn signature_key(function: &TypeBareFn) -> String {
+    let inputs = function
+        .inputs
+        .iter()
+        .map(|arg| arg.ty.to_token_stream().to_string());
+    let output = function.output.to_token_stream().to_string();
+    format!("{}->{}", inputs.collect::<Vec<_>>().join(","), output)
+}
+
+fn is_named(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == name),
+        _ => false,
+    }
+}
+
+fn pointee(ty: &Type) -> Option<&TypePtr> {
+    match ty {
+        Type::Ptr(ptr) => Some(ptr),
+        _ => None,
+    }
+}
+
+fn arg_name(arg: &BareFnArg) -> syn::Ident {
+    let name = arg
+        .name
+        .as_ref()
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| "arg".into());
+    format_ident!("{}", name.to_snek_case())
+}
+
+/// How a wrapper exposes one or two of the raw function's parameters.
+enum Marshal {
+    /// Passed through unchanged
+    Value(syn::Ident, Type),
+    Str(syn::Ident),
+    /// A pointer followed by its length
+    Slice(syn::Ident, Type, syn::Ident),
+    /// Written by Mumble
+    Out(syn::Ident, Type),
+    /// An allocated string that must be returned to `freeMemory`
+    OutString(syn::Ident),
+    /// An allocated array and its length; the array must be returned to `freeMemory`
+    OutArray(syn::Ident, Type, syn::Ident),
+}
+
+fn is_output(marshal: &Marshal) -> bool {
+    matches!(
+        marshal,
+        Marshal::Out(..) | Marshal::OutString(_) | Marshal::OutArray(..)
+    )
+}
+
+/// Whether the output is memory that must be returned to `freeMemory`
+fn is_host_memory(marshal: &Marshal) -> bool {
+    matches!(marshal, Marshal::OutString(_) | Marshal::OutArray(..))
+}
+
+/// Maps the raw parameters after `callerID`, or explains why they can't be wrapped.
+fn marshal(args: &[&BareFnArg]) -> Result<Vec<Marshal>, String> {
+    let mut marshalled = Vec::new();
+    let mut index = 0;
+    while index < args.len() {
+        let arg = args[index];
+        let next = args.get(index + 1).map(|next| &next.ty);
+        let name = arg_name(arg);
+        index += 1;
+        let ptr = match pointee(&arg.ty) {
+            Some(ptr) => ptr,
+            None => {
+                marshalled.push(Marshal::Value(name, arg.ty.clone()));
+                continue;
+            }
+        };
+        let inner = &*ptr.elem;
+        let next_is_len = next.map_or(false, |next| is_named(next, "usize"));
+        let next_is_len_out = next.and_then(pointee).map_or(false, |next| {
+            next.mutability.is_some() && is_named(&next.elem, "usize")
+        });
+        if ptr.const_token.is_some() {
+            if is_named(inner, "c_char") {
+                marshalled.push(Marshal::Str(name));
+            } else if next_is_len && !is_named(inner, "c_void") {
+                marshalled.push(Marshal::Slice(name, inner.clone(), arg_name(args[index])));
+                index += 1;
+            } else {
+                return Err(format!("`{}` is a pointer without a length", name));
+            }
+            continue;
+        }
+        match pointee(inner) {
+            None => marshalled.push(Marshal::Out(name, inner.clone())),
+            Some(out) if out.const_token.is_some() && is_named(&out.elem, "c_char") => {
+                marshalled.push(Marshal::OutString(name))
+            }
+            Some(out) if out.mutability.is_some() && next_is_len_out => {
+                marshalled.push(Marshal::OutArray(
+                    name,
+                    (*out.elem).clone(),
+                    arg_name(args[index]),
+                ));
+                index += 1;
+            }
+            Some(_) => return Err(format!("`{}` is an output pointer of unknown shape", name)),
+        }
+    }
+    Ok(marshalled)
+}
+
+/// The summary paragraph of a function's documentation, plus the arguments the wrapper keeps.
+fn wrapper_doc(field: &Field, marshalled: &[Marshal]) -> Vec<String> {
+    let lines: Vec<String> = field.attrs.iter().filter_map(doc_line).collect();
+    // Only the first sentence; the rest tends to describe the raw out-parameters
+    let summary = lines
+        .iter()
+        .take_while(|line| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let summary = match summary.find(". ") {
+        Some(end) => summary[..=end].to_string(),
+        None => summary,
+    };
+    let mut doc: Vec<String> = if summary.is_empty() {
+        Vec::new()
+    } else {
+        vec![summary]
+    };
+    let inputs: Vec<String> = marshalled
+        .iter()
+        .filter_map(|marshal| match marshal {
+            Marshal::Value(name, _) | Marshal::Str(name) | Marshal::Slice(name, ..) => {
+                Some(name.to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    let arguments: Vec<String> = lines
+        .iter()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("* `")?;
+            let (name, description) = rest.split_once("` - ")?;
+            let name = name.to_snek_case();
+            if inputs.contains(&name) {
+                Some(format!("* `{}` - {}", name, description))
+            } else {
+                None
+            }
+        })
+        .collect();
+    if !arguments.is_empty() {
+        doc.extend(vec![String::new(), "# Arguments".into(), String::new()]);
+        doc.extend(arguments);
+    }
+    doc
+}
+
+fn wrapper(
+    name: &syn::Ident,
+    field: &Field,
+    marshalled: &[Marshal],
+    arms: &[(syn::Ident, &str)],
+    all_versions: bool,
+    borrowed: bool,
+) -> TokenStream {
+    let copying = format_ident!("{}", name.to_string().to_snek_case());
+    let method = if borrowed {
+        format_ident!("{}_borrowed", copying)
+    } else {
+        copying.clone()
+    };
+    let mut params = Vec::new();
+    let mut setup = Vec::new();
+    let mut call_args = Vec::new();
+    let mut results = Vec::new();
+    let mut result_types = Vec::new();
+    for marshal in marshalled {
+        match marshal {
+            Marshal::Value(name, ty) => {
+                params.push(quote!(#name: #ty));
+                call_args.push(quote!(#name));
+            }
+            Marshal::Str(name) => {
+                params.push(quote!(#name: &str));
+                setup.push(quote!(let #name = CString::new(#name).expect("Must be valid cstr");));
+                call_args.push(quote!(#name.as_ptr()));
+            }
+            Marshal::Slice(name, ty, _) => {
+                params.push(quote!(#name: &[#ty]));
+                call_args.push(quote!(#name.as_ptr()));
+                call_args.push(quote!(#name.len()));
+            }
+            Marshal::Out(name, ty) => {
+                setup.push(quote!(let mut #name = MaybeUninit::uninit();));
+                call_args.push(quote!(#name.as_mut_ptr()));
+                results.push(quote!(#name.assume_init()));
+                result_types.push(quote!(#ty));
+            }
+            Marshal::OutString(name) => {
+                setup.push(quote!(let mut #name = crate::host::HostOut::new(self);));
+                call_args.push(quote!(#name.as_mut_const_ptr()));
+                if borrowed {
+                    results.push(quote!(#name.into_string()?));
+                    result_types.push(quote!(crate::host::HostString));
+                } else {
+                    results.push(quote!(#name.into_string()?.to_string()));
+                    result_types.push(quote!(String));
+                }
+            }
+            Marshal::OutArray(name, ty, count) => {
+                setup.push(quote!(let mut #name = crate::host::HostOut::new(self);));
+                setup.push(quote!(let mut #count = MaybeUninit::uninit();));
+                call_args.push(quote!(#name.as_mut_ptr()));
+                call_args.push(quote!(#count.as_mut_ptr()));
+                let slice = quote!(#name.into_slice(#count.assume_init()));
+                if borrowed {
+                    results.push(slice);
+                    result_types.push(quote!(crate::host::HostSlice<#ty>));
+                } else {
+                    results.push(quote!(Box::from(&*#slice)));
+                    result_types.push(quote!(Box<[#ty]>));
+                }
+            }
+        }
+    }
+    let (result, result_type) = match results.len() {
+        1 => (results.remove(0), result_types.remove(0)),
+        _ => (quote!((#(#results),*)), quote!((#(#result_types),*))),
+    };
+    // A borrowed string is already the `MumbleResult` to return
+    let tail = match marshalled.iter().find(|marshal| is_output(marshal)) {
+        Some(Marshal::OutString(name)) if borrowed => quote!(#name.into_string()),
+        _ => quote!(Ok(#result)),
+    };
+
+    let mut doc = wrapper_doc(field, marshalled);
+    if borrowed {
+        if !doc.is_empty() {
+            doc.push(String::new());
+        }
+        doc.push(format!(
+            "Like `{}`, but borrows the memory Mumble returned instead of copying it.",
+            copying
+        ));
+    }
+    let fallback = if all_versions {
+        None
+    } else {
+        let versions: Vec<&str> = arms.iter().map(|(_, version)| *version).collect();
+        if !doc.is_empty() {
+            doc.push(String::new());
+        }
+        doc.push(format!(
+            "Only available with plugin API {}; other versions return `EC_INVALID_API_VERSION`.",
+            versions.join(", ")
+        ));
+        Some(quote! {
+            #[allow(unreachable_patterns)]
+            _ => return Err(versions::unsupported()),
+        })
+    };
+    let doc = doc.iter().map(|line| {
+        let line = if line.is_empty() {
+            line.clone()
+        } else {
+            format!(" {}", line)
+        };
+        quote!(#[doc = #line])
+    });
+    let variants = arms.iter().map(|(variant, _)| variant);
+    quote! {
+        #(#doc)*
+        pub fn #method(&mut self, #(#params),*) -> MumbleResult<#result_type> {
+            let f = match self.api {
+                #(RawMumbleAPI::#variants(api) => api.#name,)*
+                #fallback
+            };
+            #(#setup)*
+            unsafe {
+                f(self.id, #(#call_args),*).resultify()?;
+                #tail
+            }
+        }
+    }
+}
+
+/// A function table whose entries record a mismatch when called, except `freeMemory`,
+/// which releases what the fakes allocated.
+fn unexpected_table(table: &FunctionTable) -> TokenStream {
+    let constructor = format_ident!("unexpected_{}", table.variant().to_string().to_lowercase());
+    let module = table.module();
+    let mut fakes = Vec::new();
+    let mut fields = Vec::new();
+    for field in table.functions() {
+        let name = field.ident.as_ref().expect("MumbleAPI has named fields");
+        if name == "freeMemory" {
+            fields.push(quote!(#name: fake::free_memory));
+            continue;
+        }
+        let function = match bare_fn(field) {
+            Some(function) => function,
+            None => continue,
+        };
+        let fake = format_ident!("unexpected_{}", name.to_string().to_snek_case());
+        let c_name = name.to_string();
+        let inputs = function.inputs.iter().map(|arg| &arg.ty);
+        let output = &function.output;
+        fakes.push(quote! {
+            unsafe extern "C" fn #fake(#(_: #inputs),*) #output {
+                fake::unexpected(#c_name)
+            }
+        });
+        fields.push(quote!(#name: #fake));
+    }
+    quote! {
+        pub(crate) fn #constructor() -> crate::mumble::#module::m::MumbleAPI {
+            #(#fakes)*
+            crate::mumble::#module::m::MumbleAPI {
+                #(#fields),*
+            }
+        }
+    }
+}
+
+/// Checks a generated wrapper against a fake that verifies what it receives, with every
+/// table that has the function, and that the other tables refuse the call.
+fn conformance_test(
+    name: &syn::Ident,
+    function: &TypeBareFn,
+    marshalled: &[Marshal],
+    arms: &[(syn::Ident, &str)],
+    tables: &[FunctionTable],
+    borrowed: bool,
+) -> TokenStream {
+    let method = format_ident!("{}", name.to_string().to_snek_case());
+    let method = if borrowed {
+        format_ident!("{}_borrowed", method)
+    } else {
+        method
+    };
+    let params = function.inputs.iter().map(|arg| {
+        let name = arg_name(arg);
+        let ty = &arg.ty;
+        quote!(#name: #ty)
+    });
+    let caller = arg_name(&function.inputs[0]);
+    let output = &function.output;
+    let mut checks = Vec::new();
+    let mut writes = Vec::new();
+    let mut call_args = Vec::new();
+    let mut expected = Vec::new();
+    for marshal in marshalled {
+        match marshal {
+            Marshal::Value(name, _) => {
+                let label = name.to_string();
+                checks.push(quote!(fake::check(#label, #name, fake::sample());));
+                call_args.push(quote!(fake::sample()));
+            }
+            Marshal::Str(name) => {
+                let label = name.to_string();
+                checks.push(quote!(fake::check_str(#label, #name, fake::TEXT);));
+                call_args.push(quote!(fake::TEXT));
+            }
+            Marshal::Slice(name, ty, count) => {
+                let label = name.to_string();
+                checks.push(quote! {
+                    fake::check_slice(#label, #name, #count, &fake::samples::<#ty>());
+                });
+                call_args.push(quote!(&fake::samples::<#ty>()));
+            }
+            Marshal::Out(name, ty) => {
+                writes.push(quote!(*#name = fake::sample();));
+                expected.push(quote!(fake::sample::<#ty>()));
+            }
+            Marshal::OutString(name) => {
+                writes.push(quote!(*#name = fake::alloc_string(fake::TEXT);));
+                expected.push(quote!(String::from(fake::TEXT)));
+            }
+            Marshal::OutArray(name, ty, count) => {
+                writes.push(quote! {
+                    let (array, len) = fake::alloc_array(fake::samples::<#ty>());
+                    *#name = array;
+                    *#count = len;
+                });
+                expected.push(quote!(fake::samples::<#ty>().into_boxed_slice()));
+            }
+        }
+    }
+    let expected = match expected.len() {
+        1 => expected.remove(0),
+        _ => quote!((#(#expected),*)),
+    };
+    // Borrowed results are copied to compare them with what the fake handed out
+    let copy = match marshalled.iter().find(|marshal| is_host_memory(marshal)) {
+        Some(Marshal::OutString(_)) if borrowed => quote!(.map(|string| string.to_string())),
+        Some(Marshal::OutArray(..)) if borrowed => {
+            quote!(.map(|slice| slice.to_vec().into_boxed_slice()))
+        }
+        _ => quote!(),
+    };
+
+    let calls = tables.iter().map(|table| {
+        let variant = table.variant();
+        let unexpected = format_ident!("unexpected_{}", variant.to_string().to_lowercase());
+        let version = format!("plugin API {}", table.version);
+        if arms.iter().any(|(arm, _)| *arm == variant) {
+            quote! {
+                let mut table = #unexpected();
+                table.#name = fake_function;
+                let mut api = fake::api(RawMumbleAPI::#variant(table));
+                assert_eq!(api.#method(#(#call_args),*)#copy, Ok(#expected), #version);
+                fake::set_failing(true);
+                assert_eq!(api.#method(#(#call_args),*)#copy, Err(fake::error()), #version);
+            }
+        } else {
+            quote! {
+                let mut api = fake::api(RawMumbleAPI::#variant(#unexpected()));
+                assert_eq!(
+                    api.#method(#(#call_args),*)#copy,
+                    Err(crate::versions::unsupported()),
+                    #version
+                );
+            }
+        }
+    });
+    let test_name = method.to_string();
+    quote! {
+        #[test]
+        fn #method() {
+            unsafe extern "C" fn fake_function(#(#params),*) #output {
+                fake::check("callerID", #caller, fake::PLUGIN);
+                #(#checks)*
+                if fake::failing() {
+                    return fake::error();
+                }
+                #(#writes)*
+                fake::ok()
+            }
+            #(#calls)*
+            fake::finish(#test_name);
+        }
+    }
+}
+
+/// Generates an `impl MumbleAPI` block wrapping every function of every table, except
+/// those in `hand_written`. Tables are given oldest first. Functions that can't be
+/// wrapped mechanically are skipped and reported in the returned list. Also returns a
+/// test for each wrapper, to be included by `api_tests`.
+pub fn api_wrappers(
+    tables: &[FunctionTable],
+    hand_written: &[&str],
+) -> (String, String, Vec<String>) {
+    // Newest first, so each function is wrapped with its latest signature
+    let mut names: Vec<syn::Ident> = Vec::new();
+    for table in tables.iter().rev() {
+        for field in table.functions() {
+            let name = field.ident.clone().expect("MumbleAPI has named fields");
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    let mut methods = Vec::new();
+    let mut tests = Vec::new();
+    let mut listed = Vec::new();
+    let mut skipped = Vec::new();
+    for name in names {
+        let c_name = name.to_string();
+        if hand_written.iter().any(|hand_written| name == hand_written) {
+            listed.push(quote!((#c_name, None)));
+            continue;
+        }
+        let (field, function) = tables
+            .iter()
+            .rev()
+            .flat_map(|table| table.functions())
+            .find_map(|field| match bare_fn(field) {
+                Some(function) if field.ident.as_ref() == Some(&name) => Some((field, function)),
+                _ => None,
+            })
+            .expect("every listed function is in some table");
+        let key = signature_key(function);
+        let arms: Vec<(syn::Ident, &str)> = tables
+            .iter()
+            .filter(|table| {
+                table.functions().iter().any(|field| {
+                    field.ident.as_ref() == Some(&name)
+                        && bare_fn(field).map(signature_key).as_ref() == Some(&key)
+                })
+            })
+            .map(|table| (table.variant(), table.version))
+            .collect();
+
+        let args: Vec<&BareFnArg> = function.inputs.iter().collect();
+        let returns_error = match &function.output {
+            ReturnType::Type(_, ty) => is_named(ty, "ErrorT"),
+            ReturnType::Default => false,
+        };
+        let marshalled = match args.split_first() {
+            Some((caller, rest)) if is_named(&caller.ty, "PluginId") && returns_error => {
+                marshal(rest)
+            }
+            _ => Err("it doesn't take a caller ID and return an error code".into()),
+        };
+        match marshalled {
+            Ok(marshalled) => {
+                let method = c_name.to_snek_case();
+                listed.push(quote!((#c_name, Some(#method))));
+                // Functions returning one string or array also get a variant borrowing it
+                let outputs: Vec<&Marshal> = marshalled.iter().filter(|m| is_output(m)).collect();
+                let borrowable = matches!(outputs[..], [output] if is_host_memory(output));
+                for borrowed in [false, true] {
+                    if borrowed && !borrowable {
+                        continue;
+                    }
+                    let all_versions = arms.len() == tables.len();
+                    methods.push(wrapper(
+                        &name,
+                        field,
+                        &marshalled,
+                        &arms,
+                        all_versions,
+                        borrowed,
+                    ));
+                    tests.push(conformance_test(
+                        &name,
+                        function,
+                        &marshalled,
+                        &arms,
+                        tables,
+                        borrowed,
+                    ));
+                }
+            }
+            Err(reason) => {
+                listed.push(quote!((#c_name, None)));
+                skipped.push(format!("{}: {}", name, reason));
+            }
+        }
+    }
+
+    let file: File = syn::parse_quote! {
+        impl MumbleAPI {
+            #(#methods)*
+        }
+    };
+    let unexpected = tables.iter().map(unexpected_table);
+    let test_file: File = syn::parse_quote! {
+        use super::fake;
+        use crate::types as m;
+        use crate::versions::RawMumbleAPI;
+
+        /// Every function in any table, and the method generated for it, if any
+        pub(crate) const API_FUNCTIONS: &[(&str, Option<&str>)] = &[#(#listed),*];
+
+        #(#unexpected)*
+
+        #(#tests)*
+    };
+    (render(&file), render(&test_file), skipped)
+}
@@ -0,0 +1,116 @@
+// Copyright 2021 The Mumble Developers. All rights reserved.
+// Use of this source code is governed by a BSD-style license
+// that can be found in the LICENSE file at the root of the
+// Mumble source tree or at <https://www.mumble.info/LICENSE>.
+
+/// This header file contains definitions of types and other components used in Mumble's plugin system
+
+#ifndef MUMBLE_PLUGIN_COMPONENTS_H_
+#define MUMBLE_PLUGIN_COMPONENTS_H_
+
+#include <stddef.h>
+#include <stdint.h>
+#ifndef __cplusplus
+#	include <stdbool.h>
+#endif
+
+#if defined(_MSC_VER)
+#	define PLUGIN_CALLING_CONVENTION __cdecl
+#elif defined(__MINGW32__)
+#	define PLUGIN_CALLING_CONVENTION __attribute__((cdecl))
+#else
+#	define PLUGIN_CALLING_CONVENTION
+#endif
+
+/// This enum's values represent talking states a user can be in when using Mumble.
+enum Mumble_TalkingState {
+	INVALID = -1,
+	PASSIVE = 0,
+	TALKING = 1,
+	WHISPERING = 2,
+	SHOUTING = 3,
+};
+
+/// This enum's values represent transmission modes a user might have configured. Transmission mode
+/// in this context is referring to a method that determines when a user is speaking and thus when
+/// to transmit audio packets.
+enum Mumble_TransmissionMode {
+	TM_CONTINOUS = 0,
+	TM_VOICE_ACTIVATION = 1,
+	TM_PUSH_TO_TALK = 2,
+};
+
+/// This enum's values represent the error codes that are being used by the MumbleAPI.
+/// You can get a string-representation for each error code via the errorMessage function.
+enum Mumble_ErrorCode {
+	EC_INTERNAL_ERROR = -2,
+	EC_GENERIC_ERROR = -1,
+	EC_OK = 0,
+	EC_POINTER_NOT_FOUND = 1,
+	EC_NO_ACTIVE_CONNECTION = 2,
+	EC_USER_NOT_FOUND = 3,
+	EC_CHANNEL_NOT_FOUND = 4,
+	EC_CONNECTION_NOT_FOUND = 5,
+	EC_UNKNOWN_TRANSMISSION_MODE = 6,
+	EC_AUDIO_NOT_AVAILABLE = 7,
+	EC_INVALID_SAMPLE = 8,
+	EC_INVALID_PLUGIN_ID = 9,
+	EC_INVALID_MUTE_TARGET = 10,
+	EC_CONNECTION_UNSYNCHRONIZED = 11,
+	EC_INVALID_API_VERSION = 12,
+	EC_UNSYNCHRONIZED_BLOB = 13,
+	EC_UNKNOWN_SETTINGS_KEY = 14,
+	EC_WRONG_SETTINGS_TYPE = 15,
+	EC_SETTING_WAS_REMOVED = 16,
+};
+
+/// This enum's values represent keys for specific settings inside Mumble.
+enum Mumble_SettingsKey {
+	MSK_INVALID = -1,
+	MSK_AUDIO_INPUT_VOICE_HOLD = 0,
+	MSK_AUDIO_INPUT_VAD_SILENCE_THRESHOLD = 1,
+	MSK_AUDIO_INPUT_VAD_SPEECH_THRESHOLD = 2,
+	MSK_AUDIO_OUTPUT_PA_MINIMUM_DISTANCE = 3,
+	MSK_AUDIO_OUTPUT_PA_MAXIMUM_DISTANCE = 4,
+	MSK_AUDIO_OUTPUT_PA_BLOOM = 5,
+	MSK_AUDIO_OUTPUT_PA_MINIMUM_VOLUME = 6,
+};
+
+/// A struct for representing a version of the form major.minor.patch
+struct Version {
+	int32_t major;
+	int32_t minor;
+	int32_t patch;
+};
+
+struct MumbleStringWrapper {
+	/// The pointer to the actual String data
+	const char *data;
+	/// The size of the pointed String data
+	size_t size;
+	/// Whether the wrapped String needs to be released
+	/// after its usage. Instances for which this would be
+	/// false: Static Strings
+	bool needsReleasing;
+};
+
+/// Typedef for the type of a talking state
+typedef enum Mumble_TalkingState mumble_talking_state_t;
+/// Typedef for the type of a transmission mode
+typedef enum Mumble_TransmissionMode mumble_transmission_mode_t;
+/// Typedef for the type of a version
+typedef struct Version mumble_version_t;
+/// Typedef for the type of a connection
+typedef int32_t mumble_connection_t;
+/// Typedef for the type of a user
+typedef uint32_t mumble_userid_t;
+/// Typedef for the type of a channel
+typedef int32_t mumble_channelid_t;
+/// Typedef for the type of an error (code)
+typedef enum Mumble_ErrorCode mumble_error_t;
+/// Typedef for the type of a plugin ID
+typedef uint32_t mumble_plugin_id_t;
+/// Typedef for the type of a key to a setting in Mumble
+typedef enum Mumble_SettingsKey mumble_settings_key_t;
+
+#endif // MUMBLE_PLUGIN_COMPONENTS_H_
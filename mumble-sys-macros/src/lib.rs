@@ -0,0 +1,502 @@
+//! Procedural macros for `mumble_sys`, which re-exports them.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Expr, ExprLit, ImplItem, ItemImpl, Lit, LitStr, Meta, Token};
+
+/// Every event callback Mumble may look up, by `MumblePlugin` method name.
+const CALLBACKS: &[&str] = &[
+    "on_server_connected",
+    "on_server_disconnected",
+    "on_server_synchronized",
+    "on_channel_entered",
+    "on_channel_exited",
+    "on_user_talking_state_changed",
+    "on_audio_input",
+    "on_audio_source_fetched",
+    "on_audio_output_about_to_play",
+    "on_receive_data",
+    "on_user_added",
+    "on_user_removed",
+    "on_channel_added",
+    "on_channel_removed",
+    "on_channel_renamed",
+    "on_key_event",
+];
+
+/// Callbacks Mumble makes on its main thread often enough for the crate to catch up on work
+/// that waits on time or has to call Mumble from there, which it does at the end of each.
+const MAIN_THREAD: &[&str] = &[
+    "on_server_synchronized",
+    "on_channel_entered",
+    "on_user_talking_state_changed",
+    "on_user_added",
+    "on_user_removed",
+    "on_key_event",
+];
+
+/// The callbacks each of the crate's subsystems hooks, for `uses(...)`, and whether it needs
+/// `MAIN_THREAD` as well.
+const SUBSYSTEMS: &[(&str, &[&str], bool)] = &[
+    (
+        "dsp",
+        &[
+            "on_audio_input",
+            "on_audio_source_fetched",
+            "on_audio_output_about_to_play",
+            "on_user_removed",
+            "on_server_connected",
+            "on_server_disconnected",
+            "on_server_synchronized",
+        ],
+        false,
+    ),
+    (
+        "mixer",
+        &["on_audio_input", "on_audio_output_about_to_play"],
+        true,
+    ),
+    (
+        "record",
+        &[
+            "on_audio_input",
+            "on_audio_source_fetched",
+            "on_audio_output_about_to_play",
+            "on_server_connected",
+            "on_server_disconnected",
+            "on_server_synchronized",
+        ],
+        false,
+    ),
+    (
+        "meter",
+        &[
+            "on_audio_input",
+            "on_audio_source_fetched",
+            "on_user_talking_state_changed",
+            "on_user_removed",
+            "on_server_disconnected",
+        ],
+        false,
+    ),
+    ("vad", &["on_audio_input"], true),
+    (
+        "spatial",
+        &[
+            "on_audio_source_fetched",
+            "on_user_added",
+            "on_user_removed",
+            "on_channel_entered",
+            "on_server_connected",
+            "on_server_disconnected",
+            "on_server_synchronized",
+        ],
+        false,
+    ),
+    ("hotkeys", &["on_key_event"], true),
+    (
+        "transmission",
+        &[
+            "on_channel_entered",
+            "on_server_disconnected",
+            "on_server_synchronized",
+            "on_key_event",
+        ],
+        true,
+    ),
+    (
+        "auto_move",
+        &[
+            "on_channel_entered",
+            "on_user_talking_state_changed",
+            "on_user_removed",
+            "on_server_disconnected",
+        ],
+        true,
+    ),
+    (
+        "local_user",
+        &["on_server_synchronized", "on_key_event"],
+        true,
+    ),
+    (
+        "storage",
+        &["on_server_disconnected", "on_server_synchronized"],
+        false,
+    ),
+    ("logging", &[], true),
+];
+
+/// API versions `mumble_sys::versions::SUPPORTED` has bindings for, by major and minor
+const SUPPORTED_API_VERSIONS: &[(i32, i32)] = &[(1, 0), (1, 2)];
+
+struct Args {
+    name: Option<LitStr>,
+    author: Option<LitStr>,
+    description: Option<LitStr>,
+    version: Option<(i32, i32, i32)>,
+    api_version: Option<(i32, i32, i32)>,
+    also_export: Vec<syn::Ident>,
+    uses: Vec<syn::Ident>,
+}
+
+fn string_value(value: &Expr, key: &str) -> syn::Result<LitStr> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => {
+            if lit.value().contains('\0') {
+                Err(syn::Error::new(
+                    lit.span(),
+                    format!("`{}` must not contain NUL characters", key),
+                ))
+            } else {
+                Ok(lit.clone())
+            }
+        }
+        _ => Err(syn::Error::new(
+            value.span(),
+            format!("`{}` must be a string literal", key),
+        )),
+    }
+}
+
+fn parse_version(lit: &LitStr) -> syn::Result<(i32, i32, i32)> {
+    let value = lit.value();
+    // Components are C ints on the Mumble side
+    let parts = value
+        .split('.')
+        .map(|part| part.parse::<i32>().ok().filter(|n| *n >= 0))
+        .collect::<Option<Vec<_>>>();
+    match parts.as_deref() {
+        Some([major, minor, patch]) => Ok((*major, *minor, *patch)),
+        _ => Err(syn::Error::new(
+            lit.span(),
+            "`version` must be of the form \"major.minor.patch\", e.g. \"1.2.0\"",
+        )),
+    }
+}
+
+fn parse_args(attr: TokenStream) -> syn::Result<Args> {
+    let metas = Punctuated::<Meta, Token![,]>::parse_terminated.parse(attr)?;
+    let mut args = Args {
+        name: None,
+        author: None,
+        description: None,
+        version: None,
+        api_version: None,
+        also_export: Vec::new(),
+        uses: Vec::new(),
+    };
+    for meta in metas {
+        let key = meta
+            .path()
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .unwrap_or_default();
+        match (key.as_str(), &meta) {
+            ("name", Meta::NameValue(nv)) => args.name = Some(string_value(&nv.value, "name")?),
+            ("author", Meta::NameValue(nv)) => {
+                args.author = Some(string_value(&nv.value, "author")?)
+            }
+            ("description", Meta::NameValue(nv)) => {
+                args.description = Some(string_value(&nv.value, "description")?)
+            }
+            ("version", Meta::NameValue(nv)) => {
+                args.version = Some(parse_version(&string_value(&nv.value, "version")?)?)
+            }
+            ("api_version", Meta::NameValue(nv)) => {
+                let lit = string_value(&nv.value, "api_version")?;
+                let version = parse_version(&lit)?;
+                if !SUPPORTED_API_VERSIONS.contains(&(version.0, version.1)) {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        format!(
+                            "mumble_sys has no bindings for API version {}.{}; expected one of: {}",
+                            version.0,
+                            version.1,
+                            SUPPORTED_API_VERSIONS
+                                .iter()
+                                .map(|(major, minor)| format!("{}.{}.x", major, minor))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                    ));
+                }
+                args.api_version = Some(version)
+            }
+            ("also_export", Meta::List(list)) => {
+                let names = list.parse_args_with(
+                    Punctuated::<syn::Ident, Token![,]>::parse_terminated,
+                )?;
+                for name in names {
+                    if !CALLBACKS.contains(&name.to_string().as_str()) {
+                        return Err(syn::Error::new(
+                            name.span(),
+                            format!(
+                                "`{}` is not a MumblePlugin callback; expected one of: {}",
+                                name,
+                                CALLBACKS.join(", ")
+                            ),
+                        ));
+                    }
+                    args.also_export.push(name);
+                }
+            }
+            ("uses", Meta::List(list)) => {
+                let names = list.parse_args_with(
+                    Punctuated::<syn::Ident, Token![,]>::parse_terminated,
+                )?;
+                for name in names {
+                    if !SUBSYSTEMS
+                        .iter()
+                        .any(|(subsystem, _, _)| name == subsystem)
+                    {
+                        let known: Vec<&str> =
+                            SUBSYSTEMS.iter().map(|(subsystem, _, _)| *subsystem).collect();
+                        return Err(syn::Error::new(
+                            name.span(),
+                            format!(
+                                "`{}` is not a mumble_sys subsystem; expected one of: {}",
+                                name,
+                                known.join(", ")
+                            ),
+                        ));
+                    }
+                    args.uses.push(name);
+                }
+            }
+            ("name", _)
+            | ("author", _)
+            | ("description", _)
+            | ("version", _)
+            | ("api_version", _) => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    format!("expected `{} = \"...\"`", key),
+                ))
+            }
+            ("also_export", _) => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "expected `also_export(callback, ...)`",
+                ))
+            }
+            ("uses", _) => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "expected `uses(subsystem, ...)`",
+                ))
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    meta.path().span(),
+                    "unknown argument; expected `name`, `author`, `description`, `version`, `api_version`, `also_export` or `uses`",
+                ))
+            }
+        }
+    }
+    Ok(args)
+}
+
+fn require(lit: Option<LitStr>, key: &str) -> syn::Result<LitStr> {
+    lit.ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("missing `{} = \"...\"` in #[mumble_plugin(...)]", key),
+        )
+    })
+}
+
+fn nul_terminated(lit: &LitStr) -> LitStr {
+    LitStr::new(&format!("{}\0", lit.value()), lit.span())
+}
+
+fn string_export(symbol: &str, lit: &LitStr) -> TokenStream2 {
+    let symbol = format_ident!("{}", symbol);
+    let terminated = nul_terminated(lit);
+    quote! {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn #symbol() -> ::mumble_sys::types::MumbleStringWrapper {
+            const VALUE: &str = #terminated;
+            ::mumble_sys::types::MumbleStringWrapper {
+                data: VALUE.as_ptr() as *const ::std::os::raw::c_char,
+                size: VALUE.len() - 1,
+                needsReleasing: false,
+            }
+        }
+    }
+}
+
+fn expand(args: Args, item: ItemImpl) -> syn::Result<TokenStream2> {
+    let trait_path = match &item.trait_ {
+        Some((None, path, _)) => path,
+        _ => {
+            return Err(syn::Error::new(
+                item.self_ty.span(),
+                "#[mumble_plugin] must be placed on `impl MumblePlugin for YourPlugin`",
+            ))
+        }
+    };
+    if trait_path.segments.last().map(|s| s.ident.to_string()) != Some("MumblePlugin".into()) {
+        return Err(syn::Error::new(
+            trait_path.span(),
+            "#[mumble_plugin] must be placed on `impl MumblePlugin for YourPlugin`",
+        ));
+    }
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            item.generics.span(),
+            "#[mumble_plugin] cannot export a generic plugin type",
+        ));
+    }
+    let implemented: Vec<String> = item
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(method) => Some(method.sig.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+    if !implemented.iter().any(|name| name == "shutdown") {
+        return Err(syn::Error::new(
+            item.self_ty.span(),
+            "MumblePlugin implementations must define `fn shutdown(&self)`",
+        ));
+    }
+
+    let name = require(args.name, "name")?;
+    let author = require(args.author, "author")?;
+    let description = args
+        .description
+        .unwrap_or_else(|| LitStr::new("", Span::call_site()));
+    let (major, minor, patch) = args.version.unwrap_or((0, 0, 1));
+    // Left to the trait's default unless given, which is always supported
+    let api_version = args.api_version.map(|(major, minor, patch)| {
+        quote! {
+            fn api_version() -> ::mumble_sys::types::Version {
+                ::mumble_sys::types::Version {
+                    major: #major,
+                    minor: #minor,
+                    patch: #patch,
+                }
+            }
+        }
+    });
+
+    let mut wanted: Vec<String> = args.also_export.iter().map(|i| i.to_string()).collect();
+    for used in &args.uses {
+        let (_, hooks, main_thread) = SUBSYSTEMS
+            .iter()
+            .find(|(subsystem, _, _)| used == subsystem)
+            .expect("checked while parsing");
+        wanted.extend(hooks.iter().map(|hook| hook.to_string()));
+        if *main_thread {
+            wanted.extend(MAIN_THREAD.iter().map(|hook| hook.to_string()));
+        }
+    }
+    let callbacks = CALLBACKS
+        .iter()
+        .filter(|callback| {
+            implemented.iter().any(|name| name == *callback)
+                || wanted.iter().any(|name| name == *callback)
+        })
+        .map(|callback| {
+            let callback = format_ident!("{}", callback);
+            quote! { ::mumble_sys::__export_callback!(#callback); }
+        });
+
+    let self_ty = &item.self_ty;
+    let get_name = string_export("mumble_getName", &name);
+    let get_author = string_export("mumble_getAuthor", &author);
+    let get_description = string_export("mumble_getDescription", &description);
+    Ok(quote! {
+        #item
+
+        impl ::mumble_sys::traits::MumblePluginDescriptor for #self_ty {
+            fn name() -> &'static str {
+                #name
+            }
+            fn author() -> &'static str {
+                #author
+            }
+            fn description() -> &'static str {
+                #description
+            }
+            fn version() -> ::mumble_sys::types::Version {
+                ::mumble_sys::types::Version {
+                    major: #major,
+                    minor: #minor,
+                    patch: #patch,
+                }
+            }
+            #api_version
+            fn init(
+                id: ::mumble_sys::types::PluginId,
+                api: ::mumble_sys::versions::RawMumbleAPI,
+            ) -> Result<Self, ::mumble_sys::types::ErrorT> {
+                <#self_ty as ::mumble_sys::traits::MumblePluginInit>::init(id, api)
+            }
+        }
+
+        ::mumble_sys::__export_init!(#self_ty);
+        ::mumble_sys::__export_versions!(#self_ty);
+        #get_name
+        #get_author
+        #get_description
+        #(#callbacks)*
+    })
+}
+
+/// Exports a plugin from its `impl MumblePlugin for ...` block.
+///
+/// Metadata is embedded as NUL-terminated constants. Only the callbacks the impl overrides
+/// are exported, so Mumble doesn't call into the plugin for events it ignores. The crate's
+/// subsystems (`dsp`, `mixer`, `record`, `meter`, `vad`, `spatial`, `hotkeys`,
+/// `transmission`, `auto_move`, `local_user`, `storage` and `logging`) run from callbacks
+/// too, so list the ones the plugin uses in `uses(...)` to export what they hook;
+/// `also_export(...)` exports single callbacks. Subsystems with work that waits on time, or
+/// `logging`'s console output, catch up at the end of Mumble's main-thread callbacks.
+///
+/// `api_version` must be one the crate has bindings for. The type must implement
+/// `MumblePluginInit`.
+///
+/// ```ignore
+/// #[mumble_plugin(
+///     name = "Example",
+///     author = "Someone",
+///     description = "Says hello",
+///     version = "1.0.0",
+///     api_version = "1.2.0",
+///     uses(dsp, hotkeys),
+///     also_export(on_channel_exited),
+/// )]
+/// impl MumblePlugin for Example {
+///     fn shutdown(&self) {}
+///
+///     fn on_server_synchronized(&mut self, conn: ConnectionT) {}
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn mumble_plugin(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = match syn::parse::<ItemImpl>(item) {
+        Ok(item) => item,
+        Err(_) => {
+            return syn::Error::new(
+                Span::call_site(),
+                "#[mumble_plugin] must be placed on `impl MumblePlugin for YourPlugin`",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+    parse_args(attr)
+        .and_then(|args| expand(args, item))
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
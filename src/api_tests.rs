@@ -0,0 +1,513 @@
+//! Tests of `MumbleAPI` against fake function tables. Wrappers generated by build.rs get
+//! generated tests (see `generated`); the hand-written ones are tested here. Modules built
+//! on `MumbleAPI` test against the same fakes, next to their code.
+
+use crate::types as m;
+use crate::versions::RawMumbleAPI;
+use crate::MumbleAPI;
+use std::os::raw;
+
+/// Shared by the generated and hand-written tests. The fakes run inside `extern "C"`
+/// functions, where a panic would abort the test binary, so they record mismatches
+/// instead and each test asserts there were none.
+pub(crate) mod fake {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::collections::BTreeMap;
+    use std::ffi::{CStr, CString};
+
+    pub const PLUGIN: m::PluginId = m::PluginId(99);
+    pub const TEXT: &str = "text ünïcode";
+
+    thread_local! {
+        static MISMATCHES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        static ALLOCATIONS: RefCell<BTreeMap<usize, Box<dyn FnOnce()>>> = RefCell::new(BTreeMap::new());
+        static FAILING: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// Values each fake expects to receive and hands back.
+    pub trait Sample: Sized {
+        fn sample() -> Self;
+    }
+
+    impl Sample for m::ConnectionT {
+        fn sample() -> Self {
+            m::ConnectionT(7)
+        }
+    }
+
+    impl Sample for m::UserIdT {
+        fn sample() -> Self {
+            m::UserIdT(42)
+        }
+    }
+
+    impl Sample for m::ChannelIdT {
+        fn sample() -> Self {
+            m::ChannelIdT(5)
+        }
+    }
+
+    impl Sample for m::TransmissionModeT {
+        fn sample() -> Self {
+            m::TransmissionModeT(m::TransmissionMode::TM_PUSH_TO_TALK)
+        }
+    }
+
+    impl Sample for m::SettingsKeyT {
+        fn sample() -> Self {
+            m::SettingsKeyT(m::SettingsKey::MSK_AUDIO_OUTPUT_PA_BLOOM)
+        }
+    }
+
+    impl Sample for bool {
+        fn sample() -> Self {
+            true
+        }
+    }
+
+    impl Sample for i32 {
+        fn sample() -> Self {
+            -1234
+        }
+    }
+
+    impl Sample for f32 {
+        fn sample() -> Self {
+            0.25
+        }
+    }
+
+    impl Sample for f64 {
+        fn sample() -> Self {
+            0.125
+        }
+    }
+
+    pub fn sample<T: Sample>() -> T {
+        T::sample()
+    }
+
+    pub fn samples<T: Sample>() -> Vec<T> {
+        vec![T::sample(), T::sample(), T::sample()]
+    }
+
+    pub fn mismatch(message: String) {
+        MISMATCHES.with(|mismatches| mismatches.borrow_mut().push(message));
+    }
+
+    pub fn check<T: PartialEq + std::fmt::Debug>(what: &str, actual: T, expected: T) {
+        if actual != expected {
+            mismatch(format!(
+                "{}: got {:?}, expected {:?}",
+                what, actual, expected
+            ));
+        }
+    }
+
+    pub unsafe fn check_str(what: &str, actual: *const raw::c_char, expected: &str) {
+        if actual.is_null() {
+            mismatch(format!("{}: got a null string", what));
+        } else {
+            check(what, CStr::from_ptr(actual).to_str(), Ok(expected));
+        }
+    }
+
+    pub unsafe fn check_slice<T: PartialEq + std::fmt::Debug>(
+        what: &str,
+        pointer: *const T,
+        len: usize,
+        expected: &[T],
+    ) {
+        if pointer.is_null() {
+            mismatch(format!("{}: got a null array", what));
+        } else {
+            check(what, std::slice::from_raw_parts(pointer, len), expected);
+        }
+    }
+
+    pub fn set_failing(failing: bool) {
+        FAILING.with(|cell| cell.set(failing));
+    }
+
+    pub fn failing() -> bool {
+        FAILING.with(|cell| cell.get())
+    }
+
+    /// What fakes return while `failing`
+    pub fn error() -> m::ErrorT {
+        m::ErrorT(m::ErrorCode::EC_GENERIC_ERROR)
+    }
+
+    pub fn ok() -> m::ErrorT {
+        m::ErrorT(m::ErrorCode::EC_OK)
+    }
+
+    pub fn unexpected(function: &str) -> m::ErrorT {
+        mismatch(format!("unexpected call to {}", function));
+        m::ErrorT(m::ErrorCode::EC_INTERNAL_ERROR)
+    }
+
+    /// A string Mumble allocated, to be returned through `freeMemory`.
+    pub fn alloc_string(text: &str) -> *const raw::c_char {
+        let pointer = CString::new(text).unwrap().into_raw();
+        let address = pointer as usize;
+        ALLOCATIONS.with(|allocations| {
+            allocations.borrow_mut().insert(
+                address,
+                Box::new(move || drop(unsafe { CString::from_raw(address as *mut raw::c_char) })),
+            )
+        });
+        pointer
+    }
+
+    /// An array Mumble allocated, to be returned through `freeMemory`.
+    pub fn alloc_array<T: 'static>(values: Vec<T>) -> (*mut T, usize) {
+        let len = values.len();
+        let pointer = Box::into_raw(values.into_boxed_slice()) as *mut T;
+        let address = pointer as usize;
+        ALLOCATIONS.with(|allocations| {
+            allocations.borrow_mut().insert(
+                address,
+                Box::new(move || {
+                    let slice = std::ptr::slice_from_raw_parts_mut(address as *mut T, len);
+                    drop(unsafe { Box::from_raw(slice) })
+                }),
+            )
+        });
+        (pointer, len)
+    }
+
+    pub unsafe extern "C" fn free_memory(
+        caller_id: m::PluginId,
+        pointer: *const raw::c_void,
+    ) -> m::ErrorT {
+        check("freeMemory caller", caller_id, PLUGIN);
+        let free =
+            ALLOCATIONS.with(|allocations| allocations.borrow_mut().remove(&(pointer as usize)));
+        match free {
+            Some(free) => {
+                free();
+                ok()
+            }
+            None => {
+                mismatch(format!("freeMemory of unknown pointer {:?}", pointer));
+                m::ErrorT(m::ErrorCode::EC_POINTER_NOT_FOUND)
+            }
+        }
+    }
+
+    pub fn api(raw_api: RawMumbleAPI) -> MumbleAPI {
+        set_failing(false);
+        MumbleAPI::new(PLUGIN, raw_api)
+    }
+
+    /// Asserts that no fake saw anything unexpected and every allocation was freed.
+    pub fn finish(test: &str) {
+        let mismatches = MISMATCHES.with(|mismatches| mismatches.replace(Vec::new()));
+        assert!(mismatches.is_empty(), "{}: {:#?}", test, mismatches);
+        let leaked = ALLOCATIONS.with(|allocations| allocations.borrow().len());
+        assert_eq!(
+            leaked, 0,
+            "{}: allocations not returned to freeMemory",
+            test
+        );
+    }
+}
+
+/// Generated by build.rs alongside the wrappers
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/mumble_api_tests.rs"));
+}
+
+use fake::{check, check_slice, check_str, sample, samples};
+pub(crate) use generated::{unexpected_v1_0, unexpected_v1_2};
+
+#[test]
+fn every_function_is_wrapped() {
+    // Functions build.rs leaves to hand-written wrappers, and where those live
+    macro_rules! hand_written {
+        ($($function: ident => $method: path),* $(,)?) => {
+            &[$({
+                let _ = $method;
+                stringify!($function)
+            }),*]
+        };
+    }
+    let hand_written: &[&str] = hand_written!(
+        freeMemory => crate::host::HostOut::<u8>::as_mut_ptr,
+        getActiveServerConnection => MumbleAPI::get_active_server_connection,
+        isConnectionSynchronized => MumbleAPI::is_connection_synchronized,
+        isUserLocallyMuted => MumbleAPI::get_user_locally_muted,
+        requestUserMove => MumbleAPI::request_user_move,
+        requestMicrophoneActivationOvewrite => MumbleAPI::request_microphone_activation_overwrite,
+        findUserByName => MumbleAPI::find_user_by_name,
+        findChannelByName => MumbleAPI::find_channel_by_name,
+        sendData => MumbleAPI::send_data,
+        playSample => MumbleAPI::play_sample,
+    );
+    let unwrapped: Vec<&str> = generated::API_FUNCTIONS
+        .iter()
+        .filter(|(function, method)| method.is_none() && !hand_written.contains(function))
+        .map(|(function, _)| *function)
+        .collect();
+    assert!(
+        unwrapped.is_empty(),
+        "MumbleAPI has no wrapper for {:?}",
+        unwrapped
+    );
+}
+
+#[test]
+fn get_active_server_connection() {
+    unsafe extern "C" fn fake(
+        caller_id: m::PluginId,
+        connection: *mut m::ConnectionT,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        *connection = sample();
+        fake::ok()
+    }
+    let mut table = unexpected_v1_2();
+    table.getActiveServerConnection = fake;
+    let api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(api.get_active_server_connection(), sample());
+    fake::finish("get_active_server_connection");
+}
+
+#[test]
+fn is_connection_synchronized() {
+    unsafe extern "C" fn fake(
+        caller_id: m::PluginId,
+        connection: m::ConnectionT,
+        synchronized: *mut bool,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check("connection", connection, sample());
+        *synchronized = true;
+        fake::ok()
+    }
+    let mut table = unexpected_v1_0();
+    table.isConnectionSynchronized = fake;
+    let api = fake::api(RawMumbleAPI::V1_0(table));
+    assert!(api.is_connection_synchronized(sample()));
+    fake::finish("is_connection_synchronized");
+}
+
+#[test]
+fn get_user_locally_muted() {
+    unsafe extern "C" fn fake(
+        caller_id: m::PluginId,
+        connection: m::ConnectionT,
+        user_id: m::UserIdT,
+        muted: *mut bool,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check("connection", connection, sample());
+        check("user", user_id, sample());
+        if fake::failing() {
+            return fake::error();
+        }
+        *muted = true;
+        fake::ok()
+    }
+    let mut table = unexpected_v1_2();
+    table.isUserLocallyMuted = fake;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(api.get_user_locally_muted(sample(), sample()), Ok(true));
+    fake::set_failing(true);
+    assert_eq!(
+        api.get_user_locally_muted(sample(), sample()),
+        Err(fake::error())
+    );
+    fake::finish("get_user_locally_muted");
+}
+
+#[test]
+fn request_user_move() {
+    unsafe extern "C" fn with_password(
+        caller_id: m::PluginId,
+        connection: m::ConnectionT,
+        user_id: m::UserIdT,
+        channel_id: m::ChannelIdT,
+        password: *const raw::c_char,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check("connection", connection, sample());
+        check("user", user_id, sample());
+        check("channel", channel_id, sample());
+        check_str("password", password, fake::TEXT);
+        fake::ok()
+    }
+    unsafe extern "C" fn without_password(
+        _: m::PluginId,
+        _: m::ConnectionT,
+        _: m::UserIdT,
+        _: m::ChannelIdT,
+        password: *const raw::c_char,
+    ) -> m::ErrorT {
+        check("password", password, std::ptr::null());
+        fake::ok()
+    }
+    let mut table = unexpected_v1_2();
+    table.requestUserMove = with_password;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(
+        api.request_user_move(sample(), sample(), sample(), Some(fake::TEXT)),
+        Ok(())
+    );
+    table.requestUserMove = without_password;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(
+        api.request_user_move(sample(), sample(), sample(), None),
+        Ok(())
+    );
+    fake::finish("request_user_move");
+}
+
+#[test]
+fn request_microphone_activation_overwrite() {
+    unsafe extern "C" fn fake(caller_id: m::PluginId, activate: bool) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check("activate", activate, true);
+        fake::ok()
+    }
+    let mut table = unexpected_v1_0();
+    table.requestMicrophoneActivationOvewrite = fake;
+    let mut api = fake::api(RawMumbleAPI::V1_0(table));
+    assert_eq!(api.request_microphone_activation_overwrite(true), Ok(()));
+    fake::finish("request_microphone_activation_overwrite");
+}
+
+#[test]
+fn find_user_by_name() {
+    unsafe extern "C" fn fake(
+        caller_id: m::PluginId,
+        connection: m::ConnectionT,
+        user_name: *const raw::c_char,
+        user_id: *mut m::UserIdT,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check("connection", connection, sample());
+        check_str("name", user_name, fake::TEXT);
+        if fake::failing() {
+            return m::ErrorT(m::ErrorCode::EC_USER_NOT_FOUND);
+        }
+        *user_id = sample();
+        fake::ok()
+    }
+    let mut table = unexpected_v1_2();
+    table.findUserByName = fake;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(
+        api.find_user_by_name(sample(), fake::TEXT),
+        Ok(Some(sample()))
+    );
+    fake::set_failing(true);
+    assert_eq!(api.find_user_by_name(sample(), fake::TEXT), Ok(None));
+    fake::finish("find_user_by_name");
+}
+
+#[test]
+fn find_channel_by_name() {
+    unsafe extern "C" fn fake(
+        caller_id: m::PluginId,
+        connection: m::ConnectionT,
+        channel_name: *const raw::c_char,
+        channel_id: *mut m::ChannelIdT,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check("connection", connection, sample());
+        check_str("name", channel_name, fake::TEXT);
+        if fake::failing() {
+            return m::ErrorT(m::ErrorCode::EC_CHANNEL_NOT_FOUND);
+        }
+        *channel_id = sample();
+        fake::ok()
+    }
+    let mut table = unexpected_v1_2();
+    table.findChannelByName = fake;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(
+        api.find_channel_by_name(sample(), fake::TEXT),
+        Ok(Some(sample()))
+    );
+    fake::set_failing(true);
+    assert_eq!(api.find_channel_by_name(sample(), fake::TEXT), Ok(None));
+    fake::finish("find_channel_by_name");
+}
+
+#[test]
+fn send_data() {
+    unsafe extern "C" fn fake(
+        caller_id: m::PluginId,
+        connection: m::ConnectionT,
+        users: *const m::UserIdT,
+        user_count: usize,
+        data: *const u8,
+        data_length: usize,
+        data_id: *const raw::c_char,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check("connection", connection, sample());
+        check_slice("users", users, user_count, &samples::<m::UserIdT>());
+        check_slice("data", data, data_length, fake::TEXT.as_bytes());
+        check_str("data id", data_id, "id");
+        fake::ok()
+    }
+    let mut table = unexpected_v1_2();
+    table.sendData = fake;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(
+        api.send_data(sample(), &samples::<m::UserIdT>(), fake::TEXT, "id"),
+        Ok(())
+    );
+    fake::finish("send_data");
+}
+
+#[test]
+fn play_sample() {
+    unsafe extern "C" fn fake_v1_0(
+        caller_id: m::PluginId,
+        sample_path: *const raw::c_char,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check_str("path", sample_path, fake::TEXT);
+        fake::ok()
+    }
+    unsafe extern "C" fn fake_v1_2(
+        caller_id: m::PluginId,
+        sample_path: *const raw::c_char,
+        volume: f32,
+    ) -> m::ErrorT {
+        check("caller", caller_id, fake::PLUGIN);
+        check_str("path", sample_path, fake::TEXT);
+        check("volume", volume, 0.5);
+        fake::ok()
+    }
+    unsafe extern "C" fn fake_full_volume(
+        _: m::PluginId,
+        _: *const raw::c_char,
+        volume: f32,
+    ) -> m::ErrorT {
+        check("volume", volume, 1.0);
+        fake::ok()
+    }
+    let mut table = unexpected_v1_0();
+    table.playSample = fake_v1_0;
+    let mut api = fake::api(RawMumbleAPI::V1_0(table));
+    assert_eq!(api.play_sample(fake::TEXT), Ok(()));
+    assert_eq!(
+        api.play_sample_with_volume(fake::TEXT, 0.5),
+        Err(crate::versions::unsupported())
+    );
+
+    let mut table = unexpected_v1_2();
+    table.playSample = fake_v1_2;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(api.play_sample_with_volume(fake::TEXT, 0.5), Ok(()));
+    table.playSample = fake_full_volume;
+    let mut api = fake::api(RawMumbleAPI::V1_2(table));
+    assert_eq!(api.play_sample(fake::TEXT), Ok(()));
+    fake::finish("play_sample");
+}
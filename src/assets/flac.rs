@@ -0,0 +1,19 @@
+use super::{AssetError, AssetResult};
+use crate::mixer::Clip;
+use std::io::Cursor;
+
+fn malformed(e: claxon::Error) -> AssetError {
+    AssetError::Malformed(format!("FLAC: {}", e))
+}
+
+pub(super) fn decode(bytes: &[u8]) -> AssetResult<Clip> {
+    let mut reader = claxon::FlacReader::new(Cursor::new(bytes)).map_err(malformed)?;
+    let info = reader.streaminfo();
+    let scale = 1.0 / (1u64 << (info.bits_per_sample - 1)) as f32;
+    let samples = reader
+        .samples()
+        .map(|sample| sample.map(|sample| sample as f32 * scale))
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(malformed)?;
+    Ok(Clip::new(samples, info.channels as u16, info.sample_rate))
+}
@@ -0,0 +1,301 @@
+//! Loads audio files into `mixer::Clip`s.
+//!
+//! WAV is always available; FLAC, Ogg/Vorbis and MP3 decoding sit behind the
+//! `flac`, `vorbis` and `mp3` features. Clips can be mixed by the plugin itself
+//! through `mixer::output()`, or handed to Mumble via `play_sample`.
+
+#[cfg(feature = "flac")]
+mod flac;
+#[cfg(feature = "mp3")]
+mod mp3;
+#[cfg(feature = "vorbis")]
+mod vorbis;
+mod wav;
+
+use crate::mixer::Clip;
+use crate::record::{SampleSink, WavWriter};
+use crate::types as m;
+use crate::MumbleAPI;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Flac,
+    Vorbis,
+    Mp3,
+}
+
+impl AudioFormat {
+    /// Identifies a file from its leading bytes.
+    pub fn sniff(header: &[u8]) -> Option<AudioFormat> {
+        if header.len() >= 12 && &header[..4] == b"RIFF" && &header[8..12] == b"WAVE" {
+            Some(AudioFormat::Wav)
+        } else if header.starts_with(b"fLaC") {
+            Some(AudioFormat::Flac)
+        } else if header.starts_with(b"OggS") {
+            Some(AudioFormat::Vorbis)
+        } else if header.starts_with(b"ID3")
+            || (header.len() >= 2 && header[0] == 0xFF && header[1] & 0xE0 == 0xE0)
+        {
+            Some(AudioFormat::Mp3)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AssetError {
+    Io(io::Error),
+    /// Not a recognised format, or its decoder feature is disabled
+    Unsupported(String),
+    Malformed(String),
+    Mumble(m::ErrorT),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Io(e) => write!(f, "I/O error: {}", e),
+            AssetError::Unsupported(reason) => write!(f, "Unsupported audio: {}", reason),
+            AssetError::Malformed(reason) => write!(f, "Malformed audio: {}", reason),
+            AssetError::Mumble(e) => write!(f, "Mumble error: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<io::Error> for AssetError {
+    fn from(e: io::Error) -> Self {
+        AssetError::Io(e)
+    }
+}
+
+impl From<m::ErrorT> for AssetError {
+    fn from(e: m::ErrorT) -> Self {
+        AssetError::Mumble(e)
+    }
+}
+
+pub type AssetResult<T> = Result<T, AssetError>;
+
+/// Decodes an in-memory file, detecting its format from the contents.
+pub fn decode(bytes: &[u8]) -> AssetResult<Clip> {
+    let format = AudioFormat::sniff(bytes)
+        .ok_or_else(|| AssetError::Unsupported("unrecognised file header".into()))?;
+    decode_as(format, bytes)
+}
+
+pub fn decode_as(format: AudioFormat, bytes: &[u8]) -> AssetResult<Clip> {
+    match format {
+        AudioFormat::Wav => wav::decode(bytes),
+        #[cfg(feature = "flac")]
+        AudioFormat::Flac => flac::decode(bytes),
+        #[cfg(feature = "vorbis")]
+        AudioFormat::Vorbis => vorbis::decode(bytes),
+        #[cfg(feature = "mp3")]
+        AudioFormat::Mp3 => mp3::decode(bytes),
+        #[allow(unreachable_patterns)]
+        other => Err(AssetError::Unsupported(format!(
+            "{:?} decoding is not enabled",
+            other
+        ))),
+    }
+}
+
+pub fn load(path: &Path) -> AssetResult<Clip> {
+    decode(&std::fs::read(path)?)
+}
+
+/// Decoded clips by path, converted to a fixed rate when one is given.
+pub struct ClipCache {
+    sample_rate: Option<u32>,
+    clips: Mutex<HashMap<PathBuf, Clip>>,
+}
+
+impl ClipCache {
+    pub fn new() -> Self {
+        ClipCache {
+            sample_rate: None,
+            clips: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resamples clips as they are loaded, so mixing them later needs no conversion.
+    pub fn with_sample_rate(sample_rate: u32) -> Self {
+        ClipCache {
+            sample_rate: Some(sample_rate),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the cached clip, decoding the file on first use.
+    pub fn get(&self, path: &Path) -> AssetResult<Clip> {
+        if let Some(clip) = self.clips.lock().get(path) {
+            return Ok(clip.clone());
+        }
+        // Decode without holding the lock; a racing load of the same path just wins or loses
+        let mut clip = load(path)?;
+        if let Some(sample_rate) = self.sample_rate {
+            clip = clip.resampled(sample_rate);
+        }
+        self.clips.lock().insert(path.to_owned(), clip.clone());
+        Ok(clip)
+    }
+
+    pub fn evict(&self, path: &Path) -> Option<Clip> {
+        self.clips.lock().remove(path)
+    }
+
+    pub fn clear(&self) {
+        self.clips.lock().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.clips.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clips.lock().is_empty()
+    }
+}
+
+impl Default for ClipCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Mumble reads samples asynchronously, so a file outlives its `play_sample` call; the most
+// recent few are kept (and reused for the same clip), older ones are deleted
+const TEMP_SAMPLE_LIMIT: usize = 16;
+static TEMP_SAMPLES: Mutex<Vec<(Clip, PathBuf)>> = Mutex::new(Vec::new());
+static NEXT_TEMP_SAMPLE: AtomicU64 = AtomicU64::new(0);
+
+fn create_temp_sample() -> io::Result<(File, PathBuf)> {
+    loop {
+        let path = std::env::temp_dir().join(format!(
+            "mumble-sys-{}-{}.wav",
+            std::process::id(),
+            NEXT_TEMP_SAMPLE.fetch_add(1, Ordering::Relaxed)
+        ));
+        // Never reuse a file someone else put there
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn temp_sample_path(clip: &Clip) -> AssetResult<PathBuf> {
+    let mut samples = TEMP_SAMPLES.lock();
+    if let Some((_, path)) = samples.iter().find(|(held, _)| held.shares_samples(clip)) {
+        return Ok(path.clone());
+    }
+    let (file, path) = create_temp_sample()?;
+    let written =
+        WavWriter::from_file(file, clip.channels(), clip.sample_rate(), false).and_then(|writer| {
+            let mut writer = Box::new(writer);
+            writer.write(clip.samples())?;
+            writer.finish()
+        });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&path);
+        return Err(e.into());
+    }
+    if samples.len() == TEMP_SAMPLE_LIMIT {
+        let (_, oldest) = samples.remove(0);
+        let _ = std::fs::remove_file(oldest);
+    }
+    samples.push((clip.clone(), path.clone()));
+    Ok(path)
+}
+
+/// Plays `clip` through Mumble's own sample player, by way of a temporary WAV file.
+pub fn play_sample(api: &mut MumbleAPI, clip: &Clip) -> AssetResult<()> {
+    let path = temp_sample_path(clip)?;
+    let path = path
+        .to_str()
+        .ok_or_else(|| AssetError::Unsupported("temporary directory is not valid UTF-8".into()))?;
+    api.play_sample(path)?;
+    Ok(())
+}
+
+/// Deletes the files still kept for `play_sample`; called automatically on plugin shutdown.
+pub fn remove_temp_samples() {
+    for (_, path) in TEMP_SAMPLES.lock().drain(..) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_samples_are_reused_per_clip_and_bounded() {
+        let first = Clip::new(vec![0.5; 8], 2, 8000);
+        let path = temp_sample_path(&first).unwrap();
+        assert_eq!(temp_sample_path(&first.clone()).unwrap(), path);
+        assert_eq!(load(&path).unwrap().samples().len(), 8);
+
+        // Skips over files it did not create
+        let taken = std::env::temp_dir().join(format!(
+            "mumble-sys-{}-{}.wav",
+            std::process::id(),
+            NEXT_TEMP_SAMPLE.load(Ordering::Relaxed)
+        ));
+        std::fs::write(&taken, b"not ours").unwrap();
+        let other = temp_sample_path(&Clip::new(vec![0.5; 8], 2, 8000)).unwrap();
+        assert_ne!(other, taken);
+        assert_eq!(std::fs::read(&taken).unwrap(), b"not ours");
+        std::fs::remove_file(&taken).unwrap();
+
+        let later: Vec<PathBuf> = (0..TEMP_SAMPLE_LIMIT)
+            .map(|_| temp_sample_path(&Clip::new(vec![0.0; 2], 1, 8000)).unwrap())
+            .collect();
+        assert!(!path.exists() && !other.exists());
+        assert!(later.iter().all(|path| path.exists()));
+        assert_eq!(TEMP_SAMPLES.lock().len(), TEMP_SAMPLE_LIMIT);
+
+        remove_temp_samples();
+        assert!(later.iter().all(|path| !path.exists()));
+    }
+
+    #[test]
+    fn formats_are_sniffed_and_garbage_is_rejected() {
+        assert_eq!(
+            AudioFormat::sniff(b"RIFF\0\0\0\0WAVEfmt "),
+            Some(AudioFormat::Wav)
+        );
+        assert_eq!(AudioFormat::sniff(b"RIFF\0\0\0\0AVI "), None);
+        assert_eq!(AudioFormat::sniff(b"fLaC\0"), Some(AudioFormat::Flac));
+        assert_eq!(AudioFormat::sniff(b"OggS\0"), Some(AudioFormat::Vorbis));
+        assert_eq!(AudioFormat::sniff(b"ID3\x04"), Some(AudioFormat::Mp3));
+        assert_eq!(
+            AudioFormat::sniff(&[0xFF, 0xFB, 0x90]),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(AudioFormat::sniff(&[0xFF]), None);
+        assert!(matches!(decode(b"MThd"), Err(AssetError::Unsupported(_))));
+
+        for (format, bytes) in &[
+            (AudioFormat::Flac, &b"fLaC\0\0\0\x22garbage"[..]),
+            (AudioFormat::Vorbis, &b"OggS\0\x02garbage"[..]),
+        ] {
+            match decode_as(*format, bytes) {
+                Err(AssetError::Malformed(_)) | Err(AssetError::Unsupported(_)) => {}
+                other => panic!("{:?} decoded garbage: {:?}", format, other.map(|_| ())),
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use super::{AssetError, AssetResult};
+use crate::mixer::Clip;
+use std::io::Cursor;
+
+pub(super) fn decode(bytes: &[u8]) -> AssetResult<Clip> {
+    let mut decoder = minimp3::Decoder::new(Cursor::new(bytes));
+    let mut format = None;
+    let mut samples = Vec::new();
+    loop {
+        match decoder.next_frame() {
+            Ok(frame) => {
+                let frame_format = (frame.channels as u16, frame.sample_rate as u32);
+                // A stream that changes layout part way can't be one clip; keep the first part
+                if *format.get_or_insert(frame_format) != frame_format {
+                    break;
+                }
+                samples.extend(frame.data.iter().map(|sample| *sample as f32 / 32768.0));
+            }
+            Err(minimp3::Error::Eof) | Err(minimp3::Error::InsufficientData) => break,
+            Err(minimp3::Error::SkippedData) => continue,
+            Err(e) => return Err(AssetError::Malformed(format!("MP3: {}", e))),
+        }
+    }
+    let (channels, sample_rate) =
+        format.ok_or_else(|| AssetError::Malformed("MP3: no audio frames".into()))?;
+    Ok(Clip::new(samples, channels, sample_rate))
+}
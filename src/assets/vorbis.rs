@@ -0,0 +1,24 @@
+use super::{AssetError, AssetResult};
+use crate::mixer::Clip;
+use lewton::inside_ogg::OggStreamReader;
+use std::io::Cursor;
+
+fn malformed(e: lewton::VorbisError) -> AssetError {
+    AssetError::Malformed(format!("Vorbis: {}", e))
+}
+
+pub(super) fn decode(bytes: &[u8]) -> AssetResult<Clip> {
+    let mut reader = OggStreamReader::new(Cursor::new(bytes)).map_err(malformed)?;
+    let channels = reader.ident_hdr.audio_channels as u16;
+    let sample_rate = reader.ident_hdr.audio_sample_rate;
+    if channels == 0 || sample_rate == 0 {
+        return Err(AssetError::Malformed(
+            "Vorbis: zero channels or sample rate".into(),
+        ));
+    }
+    let mut samples = Vec::new();
+    while let Some(packet) = reader.read_dec_packet_itl().map_err(malformed)? {
+        samples.extend(packet.iter().map(|sample| *sample as f32 / 32768.0));
+    }
+    Ok(Clip::new(samples, channels, sample_rate))
+}
@@ -0,0 +1,241 @@
+use super::{AssetError, AssetResult};
+use crate::mixer::Clip;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IEEE_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+struct Format {
+    tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+fn malformed(reason: &str) -> AssetError {
+    AssetError::Malformed(format!("WAV: {}", reason))
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn parse_format(chunk: &[u8]) -> AssetResult<Format> {
+    if chunk.len() < 16 {
+        return Err(malformed("fmt chunk too short"));
+    }
+    let mut tag = u16_at(chunk, 0);
+    // Extensible headers carry the real format in the first two bytes of the subformat GUID
+    if tag == FORMAT_EXTENSIBLE {
+        if chunk.len() < 26 {
+            return Err(malformed("extensible fmt chunk too short"));
+        }
+        tag = u16_at(chunk, 24);
+    }
+    Ok(Format {
+        tag,
+        channels: u16_at(chunk, 2),
+        sample_rate: u32_at(chunk, 4),
+        bits_per_sample: u16_at(chunk, 14),
+    })
+}
+
+fn convert(format: &Format, data: &[u8]) -> AssetResult<Vec<f32>> {
+    if format.bits_per_sample == 0 {
+        return Err(malformed("zero bits per sample"));
+    }
+    let bytes_per_sample = ((format.bits_per_sample + 7) / 8) as usize;
+    let samples = data.chunks_exact(bytes_per_sample);
+    Ok(match (format.tag, bytes_per_sample) {
+        (FORMAT_PCM, 1) => samples.map(|s| (s[0] as f32 - 128.0) / 128.0).collect(),
+        (FORMAT_PCM, 2) => samples
+            .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0)
+            .collect(),
+        (FORMAT_PCM, 3) => samples
+            .map(|s| i32::from_le_bytes([0, s[0], s[1], s[2]]) as f32 / 2147483648.0)
+            .collect(),
+        (FORMAT_PCM, 4) => samples
+            .map(|s| i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2147483648.0)
+            .collect(),
+        (FORMAT_IEEE_FLOAT, 4) => samples
+            .map(|s| f32::from_le_bytes([s[0], s[1], s[2], s[3]]))
+            .collect(),
+        (FORMAT_IEEE_FLOAT, 8) => samples
+            .map(|s| f64::from_le_bytes([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]]) as f32)
+            .collect(),
+        (tag, _) => {
+            return Err(AssetError::Unsupported(format!(
+                "WAV format {:#x} at {} bits per sample",
+                tag, format.bits_per_sample
+            )))
+        }
+    })
+}
+
+pub(super) fn decode(bytes: &[u8]) -> AssetResult<Clip> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(malformed("missing RIFF/WAVE header"));
+    }
+    let mut format = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let len = u32_at(bytes, offset + 4) as usize;
+        let body = offset + 8;
+        // Writers that never patched the size leave it zero or too large; take what is there
+        let end = (body + len).min(bytes.len());
+        match id {
+            b"fmt " => format = Some(parse_format(&bytes[body..end])?),
+            b"data" => {
+                let format = format.ok_or_else(|| malformed("data chunk before fmt chunk"))?;
+                if format.channels == 0 || format.sample_rate == 0 {
+                    return Err(malformed("zero channels or sample rate"));
+                }
+                let data_end = if len == 0 { bytes.len() } else { end };
+                let mut samples = convert(&format, &bytes[body..data_end])?;
+                samples
+                    .truncate(samples.len() / format.channels as usize * format.channels as usize);
+                return Ok(Clip::new(samples, format.channels, format.sample_rate));
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        offset = body + len + (len & 1);
+    }
+    Err(malformed("no data chunk"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(fmt: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        for (id, body) in [(b"fmt ", fmt), (b"data", data)] {
+            bytes.extend_from_slice(id);
+            bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(body);
+            if body.len() % 2 == 1 {
+                bytes.push(0);
+            }
+        }
+        bytes
+    }
+
+    fn fmt(tag: u16, channels: u16, sample_rate: u32, bits_per_sample: u16) -> Vec<u8> {
+        let block_align = channels * ((bits_per_sample + 7) / 8);
+        let mut chunk = Vec::new();
+        chunk.extend_from_slice(&tag.to_le_bytes());
+        chunk.extend_from_slice(&channels.to_le_bytes());
+        chunk.extend_from_slice(&sample_rate.to_le_bytes());
+        chunk.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        chunk.extend_from_slice(&block_align.to_le_bytes());
+        chunk.extend_from_slice(&bits_per_sample.to_le_bytes());
+        chunk
+    }
+
+    fn malformed_reason(bytes: &[u8]) -> String {
+        match decode(bytes) {
+            Err(AssetError::Malformed(reason)) => reason,
+            other => panic!("expected a malformed error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decodes_each_sample_format() {
+        let pcm8 = decode(&wav(&fmt(FORMAT_PCM, 1, 8000, 8), &[0, 128, 192])).unwrap();
+        assert_eq!(pcm8.samples(), &[-1.0, 0.0, 0.5]);
+        assert_eq!((pcm8.channels(), pcm8.sample_rate()), (1, 8000));
+
+        let data: Vec<u8> = [-32768i16, 16384]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let pcm16 = decode(&wav(&fmt(FORMAT_PCM, 2, 44100, 16), &data)).unwrap();
+        assert_eq!(pcm16.samples(), &[-1.0, 0.5]);
+        assert_eq!(pcm16.frames(), 1);
+
+        let pcm24 = decode(&wav(&fmt(FORMAT_PCM, 1, 48000, 24), &[0, 0, 0x40])).unwrap();
+        assert_eq!(pcm24.samples(), &[0.5]);
+
+        let data: Vec<u8> = [0.25f64, -0.75]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let float64 = decode(&wav(&fmt(FORMAT_IEEE_FLOAT, 1, 48000, 64), &data)).unwrap();
+        assert_eq!(float64.samples(), &[0.25, -0.75]);
+    }
+
+    #[test]
+    fn reads_extensible_headers() {
+        let mut chunk = fmt(FORMAT_EXTENSIBLE, 1, 48000, 32);
+        chunk.extend_from_slice(&22u16.to_le_bytes());
+        chunk.extend_from_slice(&32u16.to_le_bytes());
+        chunk.extend_from_slice(&4u32.to_le_bytes());
+        chunk.extend_from_slice(&FORMAT_IEEE_FLOAT.to_le_bytes());
+        chunk.extend_from_slice(&[0; 14]);
+        let clip = decode(&wav(&chunk, &0.5f32.to_le_bytes())).unwrap();
+        assert_eq!(clip.samples(), &[0.5]);
+    }
+
+    #[test]
+    fn skips_unknown_chunks_and_tolerates_bad_sizes() {
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        // An odd-sized chunk, padded to an even length
+        bytes.extend_from_slice(b"LIST\x03\0\0\0abc\0");
+        let mut rest = wav(&fmt(FORMAT_PCM, 1, 8000, 8), &[255, 0]);
+        // Claims more data than the file holds
+        let len = rest.len();
+        rest[len - 6..len - 2].copy_from_slice(&1000u32.to_le_bytes());
+        bytes.extend_from_slice(&rest[12..]);
+        let clip = decode(&bytes).unwrap();
+        assert_eq!(clip.samples(), &[127.0 / 128.0, -1.0]);
+
+        // A streaming writer that never patched the data size
+        let mut unpatched = wav(&fmt(FORMAT_PCM, 1, 8000, 8), &[128, 128, 128]);
+        let len = unpatched.len();
+        unpatched[len - 8..len - 4].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(decode(&unpatched).unwrap().frames(), 4);
+    }
+
+    #[test]
+    fn drops_a_trailing_partial_frame() {
+        let data: Vec<u8> = [1i16, 2, 3].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let clip = decode(&wav(&fmt(FORMAT_PCM, 2, 8000, 16), &data)).unwrap();
+        assert_eq!(clip.frames(), 1);
+    }
+
+    #[test]
+    fn rejects_broken_headers() {
+        assert!(malformed_reason(b"RIFF\0\0\0\0AVI ").contains("RIFF"));
+        assert!(malformed_reason(&wav(&[0; 8], &[])).contains("too short"));
+        assert!(malformed_reason(&wav(&fmt(FORMAT_PCM, 0, 8000, 16), &[])).contains("zero"));
+        assert!(malformed_reason(&wav(&fmt(FORMAT_PCM, 1, 0, 16), &[])).contains("zero"));
+        assert!(
+            malformed_reason(&wav(&fmt(FORMAT_PCM, 1, 8000, 0), &[1, 2]))
+                .contains("bits per sample")
+        );
+        let mut data_first = b"RIFF\0\0\0\0WAVEdata\x02\0\0\0\0\0".to_vec();
+        data_first.extend_from_slice(&wav(&fmt(FORMAT_PCM, 1, 8000, 8), &[])[12..]);
+        assert!(malformed_reason(&data_first).contains("before fmt"));
+        assert!(malformed_reason(b"RIFF\0\0\0\0WAVE").contains("no data"));
+    }
+
+    #[test]
+    fn rejects_unsupported_encodings() {
+        // A-law
+        let unsupported = decode(&wav(&fmt(6, 1, 8000, 8), &[0])).unwrap_err();
+        assert!(matches!(unsupported, AssetError::Unsupported(_)));
+        let unsupported = decode(&wav(&fmt(FORMAT_IEEE_FLOAT, 1, 8000, 16), &[0, 0])).unwrap_err();
+        assert!(matches!(unsupported, AssetError::Unsupported(_)));
+    }
+}
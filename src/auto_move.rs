@@ -0,0 +1,597 @@
+//! Moves the local user between channels automatically: following another user, going to an
+//! AFK channel after a stretch of silence, and returning to the previous channel.
+//!
+//! Automation is set up per connection. Moves are limited to one per `MOVE_INTERVAL`; a
+//! move wanted sooner waits and replaces any move already waiting, so following someone who
+//! hops channels ends in their last channel rather than a burst of requests. The outcome of
+//! each move reaches `MumblePlugin::on_auto_move`. Mumble accepts some requests the server
+//! then refuses, such as ones with a wrong channel password, so a move not seen through
+//! `on_channel_entered` within `CONFIRM_TIMEOUT` is reported as `MoveError::Unconfirmed`.
+//!
+//! Silence is measured from the local user's talking state. The AFK timer, waiting moves
+//! and confirmations are checked from every callback Mumble makes on its main thread, since
+//! moves may only be requested from there, so they happen on the first such callback after
+//! coming due. Other users talking or moving is enough to keep them on time.
+
+use crate::types as m;
+use crate::{MumbleAPI, MumbleResult};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// The shortest time between two moves on one connection
+pub const MOVE_INTERVAL: Duration = Duration::from_secs(2);
+/// How long a requested move may take to show up in `on_channel_entered`
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many earlier channels are remembered for `return_to_previous`
+pub const HISTORY_LIMIT: usize = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AfkConfig {
+    pub channel: m::ChannelIdT,
+    /// How long the local user must be silent
+    pub after: Duration,
+    /// Whether talking again moves them back to where they were
+    pub return_on_activity: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveReason {
+    Follow(m::UserIdT),
+    Afk,
+    Return,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AutoMove {
+    pub connection: m::ConnectionT,
+    pub channel: m::ChannelIdT,
+    pub reason: MoveReason,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// Mumble refused the request, e.g. with `EC_CHANNEL_NOT_FOUND`
+    Mumble(m::ErrorT),
+    /// Mumble took the request but the local user didn't arrive, e.g. because of a wrong
+    /// password or missing permission
+    Unconfirmed,
+}
+
+type Report = (AutoMove, Result<(), MoveError>);
+
+struct Connection {
+    local_user: m::UserIdT,
+    channel: Option<m::ChannelIdT>,
+    /// Most recent last
+    history: Vec<m::ChannelIdT>,
+    follow: Option<m::UserIdT>,
+    afk: Option<AfkConfig>,
+    /// Where the AFK timer moved the user from, while they're away
+    afk_from: Option<m::ChannelIdT>,
+    last_activity: Instant,
+    last_move: Option<Instant>,
+    waiting: Option<AutoMove>,
+    unconfirmed: Option<(AutoMove, Instant)>,
+    passwords: BTreeMap<m::ChannelIdT, String>,
+}
+
+impl Connection {
+    fn is_idle(&self) -> bool {
+        self.follow.is_none()
+            && self.afk.is_none()
+            && self.waiting.is_none()
+            && self.unconfirmed.is_none()
+    }
+
+    /// Queues a move unless the user is already there
+    fn want(&mut self, movement: AutoMove) {
+        if self.channel != Some(movement.channel) {
+            self.waiting = Some(movement);
+        }
+    }
+
+    fn remember(&mut self, channel: m::ChannelIdT) {
+        self.history.retain(|previous| *previous != channel);
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.history.push(channel);
+    }
+}
+
+static CONNECTIONS: Mutex<BTreeMap<m::ConnectionT, Connection>> = Mutex::new(BTreeMap::new());
+/// Outcomes not yet passed to the plugin, which may be locked when they happen
+static REPORTS: Mutex<Vec<Report>> = Mutex::new(Vec::new());
+
+/// Looks up the local user and their channel the first time a connection is automated
+fn connection<T>(
+    api: &mut MumbleAPI,
+    connection: m::ConnectionT,
+    f: impl FnOnce(&mut Connection) -> T,
+) -> MumbleResult<T> {
+    if !CONNECTIONS.lock().contains_key(&connection) {
+        let local_user = api.get_local_user_id(connection)?;
+        let channel = api.get_channel_of_user(connection, local_user)?;
+        CONNECTIONS
+            .lock()
+            .entry(connection)
+            .or_insert_with(|| Connection {
+                local_user,
+                channel: Some(channel),
+                history: Vec::new(),
+                follow: None,
+                afk: None,
+                afk_from: None,
+                last_activity: Instant::now(),
+                last_move: None,
+                waiting: None,
+                unconfirmed: None,
+                passwords: BTreeMap::new(),
+            });
+    }
+    Ok(f(CONNECTIONS.lock().get_mut(&connection).unwrap()))
+}
+
+/// Follows `user` into every channel they enter, starting with their current one.
+pub fn follow(
+    api: &mut MumbleAPI,
+    connection: m::ConnectionT,
+    user: m::UserIdT,
+) -> MumbleResult<()> {
+    let channel = api.get_channel_of_user(connection, user)?;
+    self::connection(api, connection, |automated| {
+        automated.follow = Some(user);
+        automated.want(AutoMove {
+            connection,
+            channel,
+            reason: MoveReason::Follow(user),
+        });
+    })?;
+    process(api, Instant::now());
+    Ok(())
+}
+
+pub fn unfollow(connection: m::ConnectionT) {
+    if let Some(automated) = CONNECTIONS.lock().get_mut(&connection) {
+        automated.follow = None;
+    }
+}
+
+pub fn following(connection: m::ConnectionT) -> Option<m::UserIdT> {
+    CONNECTIONS
+        .lock()
+        .get(&connection)
+        .and_then(|automated| automated.follow)
+}
+
+/// Starts or stops moving the local user to an AFK channel after silence. The silence is
+/// counted from now.
+pub fn set_afk(
+    api: &mut MumbleAPI,
+    connection: m::ConnectionT,
+    afk: Option<AfkConfig>,
+) -> MumbleResult<()> {
+    self::connection(api, connection, |automated| {
+        automated.afk = afk;
+        automated.afk_from = None;
+        automated.last_activity = Instant::now();
+    })
+}
+
+pub fn afk(connection: m::ConnectionT) -> Option<AfkConfig> {
+    CONNECTIONS
+        .lock()
+        .get(&connection)
+        .and_then(|automated| automated.afk)
+}
+
+/// The password to send when moving into `channel`, or none.
+pub fn set_channel_password(
+    api: &mut MumbleAPI,
+    connection: m::ConnectionT,
+    channel: m::ChannelIdT,
+    password: Option<String>,
+) -> MumbleResult<()> {
+    self::connection(api, connection, |automated| match password {
+        Some(password) => automated.passwords.insert(channel, password),
+        None => automated.passwords.remove(&channel),
+    })?;
+    Ok(())
+}
+
+/// The channel the local user was in before their current one, as far as has been seen
+pub fn previous_channel(connection: m::ConnectionT) -> Option<m::ChannelIdT> {
+    CONNECTIONS
+        .lock()
+        .get(&connection)
+        .and_then(|automated| automated.history.last().copied())
+}
+
+/// Moves the local user back to their previous channel. `false` if there is none.
+pub fn return_to_previous(api: &mut MumbleAPI, connection: m::ConnectionT) -> MumbleResult<bool> {
+    let found = self::connection(api, connection, |automated| {
+        let previous = automated.history.last().copied();
+        if let Some(channel) = previous {
+            automated.want(AutoMove {
+                connection,
+                channel,
+                reason: MoveReason::Return,
+            });
+        }
+        previous.is_some()
+    })?;
+    process(api, Instant::now());
+    Ok(found)
+}
+
+/// Sends the moves that are due and reports the ones that failed or went unconfirmed.
+///
+/// Mumble is called without the state locked, since its API may wait on a thread that is
+/// itself waiting for the state.
+fn process(api: &mut MumbleAPI, now: Instant) {
+    let mut reports = Vec::new();
+    let mut due = Vec::new();
+    for automated in CONNECTIONS.lock().values_mut() {
+        if let Some((movement, requested)) = automated.unconfirmed {
+            if now.saturating_duration_since(requested) >= CONFIRM_TIMEOUT {
+                automated.unconfirmed = None;
+                reports.push((movement, Err(MoveError::Unconfirmed)));
+            }
+        }
+        let ready = automated.last_move.map_or(true, |last| {
+            now.saturating_duration_since(last) >= MOVE_INTERVAL
+        });
+        if let (true, Some(movement)) = (ready, automated.waiting) {
+            automated.waiting = None;
+            automated.last_move = Some(now);
+            let password = automated.passwords.get(&movement.channel).cloned();
+            due.push((movement, automated.local_user, password));
+        }
+    }
+    for (movement, local_user, password) in due {
+        let result = api.request_user_move(
+            movement.connection,
+            local_user,
+            movement.channel,
+            password.as_deref(),
+        );
+        let mut connections = CONNECTIONS.lock();
+        let automated = match connections.get_mut(&movement.connection) {
+            Some(automated) => automated,
+            None => continue,
+        };
+        match result {
+            Ok(()) => automated.unconfirmed = Some((movement, now)),
+            Err(e) => {
+                log::warn!("Could not move to channel {}: {:?}", movement.channel, e);
+                if e.0 == m::ErrorCode::EC_CHANNEL_NOT_FOUND {
+                    forget_channel(automated, movement);
+                }
+                reports.push((movement, Err(MoveError::Mumble(e))));
+            }
+        }
+    }
+    REPORTS.lock().extend(reports);
+}
+
+/// Outcomes since the last call, for `MumblePlugin::on_auto_move`
+pub(crate) fn take_reports() -> Vec<Report> {
+    std::mem::take(&mut *REPORTS.lock())
+}
+
+/// Stops automation from trying a channel that no longer exists again
+fn forget_channel(automated: &mut Connection, movement: AutoMove) {
+    automated
+        .history
+        .retain(|channel| *channel != movement.channel);
+    match movement.reason {
+        MoveReason::Afk => {
+            log::warn!("The AFK channel is gone; no longer moving there");
+            automated.afk = None;
+            automated.afk_from = None;
+        }
+        MoveReason::Return if automated.afk_from == Some(movement.channel) => {
+            automated.afk_from = None;
+        }
+        // The followed user's channel existed when they entered it; the next one may work
+        MoveReason::Follow(_) | MoveReason::Return => {}
+    }
+}
+
+pub(crate) fn channel_entered(
+    api: &mut MumbleAPI,
+    connection: m::ConnectionT,
+    user: m::UserIdT,
+    previous: Option<m::ChannelIdT>,
+    current: Option<m::ChannelIdT>,
+    now: Instant,
+) {
+    {
+        let mut connections = CONNECTIONS.lock();
+        let automated = match connections.get_mut(&connection) {
+            Some(automated) => automated,
+            None => return,
+        };
+        if user == automated.local_user {
+            automated.channel = current;
+            if let Some(previous) = previous.filter(|previous| Some(*previous) != current) {
+                automated.remember(previous);
+            }
+            let arrived = automated
+                .unconfirmed
+                .filter(|(movement, _)| Some(movement.channel) == current);
+            match arrived {
+                Some((movement, _)) => {
+                    automated.unconfirmed = None;
+                    REPORTS.lock().push((movement, Ok(())));
+                }
+                None => {
+                    // Moved by hand, or by someone else
+                    automated.last_activity = now;
+                    automated.afk_from = None;
+                }
+            }
+        } else if Some(user) == automated.follow {
+            if let Some(channel) = current {
+                automated.want(AutoMove {
+                    connection,
+                    channel,
+                    reason: MoveReason::Follow(user),
+                });
+            }
+        }
+    }
+    process(api, now);
+}
+
+pub(crate) fn talking_state_changed(
+    api: &mut MumbleAPI,
+    connection: m::ConnectionT,
+    user: m::UserIdT,
+    state: m::TalkingStateT,
+    now: Instant,
+) {
+    let talking = match state.0 {
+        m::TalkingState::TALKING | m::TalkingState::WHISPERING | m::TalkingState::SHOUTING => true,
+        m::TalkingState::PASSIVE | m::TalkingState::INVALID => false,
+    };
+    {
+        let mut connections = CONNECTIONS.lock();
+        let automated = match connections.get_mut(&connection) {
+            Some(automated) if talking && user == automated.local_user => automated,
+            _ => return,
+        };
+        automated.last_activity = now;
+        let return_on_activity = automated.afk.map_or(false, |afk| afk.return_on_activity);
+        if let (true, Some(channel)) = (return_on_activity, automated.afk_from.take()) {
+            automated.want(AutoMove {
+                connection,
+                channel,
+                reason: MoveReason::Return,
+            });
+        }
+    }
+    process(api, now);
+}
+
+/// Starts AFK moves that are due and sends waiting moves
+pub(crate) fn tick(api: &mut MumbleAPI, now: Instant) {
+    {
+        let mut connections = CONNECTIONS.lock();
+        if connections.values().all(Connection::is_idle) {
+            return;
+        }
+        for (id, automated) in connections.iter_mut() {
+            let afk = match automated.afk {
+                Some(afk) if automated.afk_from.is_none() => afk,
+                _ => continue,
+            };
+            let silent = now.saturating_duration_since(automated.last_activity);
+            match automated.channel {
+                Some(channel) if channel != afk.channel && silent >= afk.after => {
+                    automated.afk_from = Some(channel);
+                    automated.want(AutoMove {
+                        connection: *id,
+                        channel: afk.channel,
+                        reason: MoveReason::Afk,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    process(api, now);
+}
+
+pub(crate) fn user_removed(connection: m::ConnectionT, user: m::UserIdT) {
+    if let Some(automated) = CONNECTIONS.lock().get_mut(&connection) {
+        if automated.follow == Some(user) {
+            log::info!("User {} left; no longer following them", user);
+            automated.follow = None;
+            automated.waiting = None;
+        }
+    }
+}
+
+pub(crate) fn server_disconnected(connection: m::ConnectionT) {
+    CONNECTIONS.lock().remove(&connection);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_tests::fake::{self, sample};
+    use crate::api_tests::unexpected_v1_2;
+    use crate::auto_move::{self, AfkConfig, AutoMove, MoveError, MoveReason};
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use crate::MumbleAPI;
+    use parking_lot::Mutex;
+    use std::ffi::CStr;
+    use std::os::raw;
+    use std::time::{Duration, Instant};
+
+    /// Reports are global, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+    static MOVES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    const GONE: m::ChannelIdT = m::ChannelIdT(99);
+
+    unsafe extern "C" fn local_user(
+        _: m::PluginId,
+        _: m::ConnectionT,
+        user_id: *mut m::UserIdT,
+    ) -> m::ErrorT {
+        *user_id = sample();
+        fake::ok()
+    }
+
+    unsafe extern "C" fn channel_of_user(
+        _: m::PluginId,
+        _: m::ConnectionT,
+        user_id: m::UserIdT,
+        channel: *mut m::ChannelIdT,
+    ) -> m::ErrorT {
+        *channel = if user_id == sample() {
+            m::ChannelIdT::ROOT
+        } else {
+            m::ChannelIdT(5)
+        };
+        fake::ok()
+    }
+
+    unsafe extern "C" fn request_move(
+        _: m::PluginId,
+        _: m::ConnectionT,
+        user_id: m::UserIdT,
+        channel_id: m::ChannelIdT,
+        password: *const raw::c_char,
+    ) -> m::ErrorT {
+        let mut request = format!("{} -> {}", user_id, channel_id);
+        if !password.is_null() {
+            request += &format!(" ({})", CStr::from_ptr(password).to_str().unwrap());
+        }
+        MOVES.lock().push(request);
+        if channel_id == GONE {
+            return m::ErrorT(m::ErrorCode::EC_CHANNEL_NOT_FOUND);
+        }
+        fake::ok()
+    }
+
+    fn api() -> MumbleAPI {
+        let mut table = unexpected_v1_2();
+        table.getLocalUserID = local_user;
+        table.getChannelOfUser = channel_of_user;
+        table.requestUserMove = request_move;
+        fake::api(RawMumbleAPI::V1_2(table))
+    }
+
+    fn moves() -> Vec<String> {
+        std::mem::take(&mut *MOVES.lock())
+    }
+
+    #[test]
+    fn following_is_rate_limited_and_confirmed() {
+        let _serial = SERIAL.lock();
+        let mut api = api();
+        let connection = m::ConnectionT(11);
+        let (me, them) = (sample::<m::UserIdT>(), m::UserIdT(7));
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        auto_move::follow(&mut api, connection, them).unwrap();
+        assert_eq!(moves(), vec!["42 -> 5"]);
+        let moved = |channel| AutoMove {
+            connection,
+            channel: m::ChannelIdT(channel),
+            reason: MoveReason::Follow(them),
+        };
+
+        let (root, five) = (Some(m::ChannelIdT::ROOT), Some(m::ChannelIdT(5)));
+        auto_move::channel_entered(&mut api, connection, me, root, five, at(1000));
+        auto_move::channel_entered(
+            &mut api,
+            connection,
+            them,
+            five,
+            Some(m::ChannelIdT(6)),
+            at(1500),
+        );
+        auto_move::channel_entered(
+            &mut api,
+            connection,
+            them,
+            None,
+            Some(m::ChannelIdT(8)),
+            at(1800),
+        );
+        assert!(moves().is_empty());
+        auto_move::tick(&mut api, at(3000));
+        assert_eq!(moves(), vec!["42 -> 8"]);
+        auto_move::tick(&mut api, at(9000));
+        assert_eq!(
+            auto_move::take_reports(),
+            vec![(moved(5), Ok(())), (moved(8), Err(MoveError::Unconfirmed))]
+        );
+        assert_eq!(auto_move::previous_channel(connection), root);
+
+        auto_move::user_removed(connection, them);
+        assert_eq!(auto_move::following(connection), None);
+        auto_move::server_disconnected(connection);
+        fake::finish("following_is_rate_limited_and_confirmed");
+    }
+
+    #[test]
+    fn afk_moves_away_and_back_with_passwords() {
+        let _serial = SERIAL.lock();
+        let mut api = api();
+        let connection = m::ConnectionT(12);
+        let me = sample::<m::UserIdT>();
+        let afk_channel = m::ChannelIdT(3);
+        auto_move::set_channel_password(&mut api, connection, afk_channel, Some("secret".into()))
+            .unwrap();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let afk = AfkConfig {
+            channel: afk_channel,
+            after: Duration::from_secs(60),
+            return_on_activity: true,
+        };
+        auto_move::set_afk(&mut api, connection, Some(afk)).unwrap();
+
+        auto_move::tick(&mut api, at(30));
+        assert!(moves().is_empty());
+        auto_move::tick(&mut api, at(61));
+        assert_eq!(moves(), vec!["42 -> 3 (secret)"]);
+        let root = Some(m::ChannelIdT::ROOT);
+        auto_move::channel_entered(&mut api, connection, me, root, Some(afk_channel), at(62));
+        let talking = m::TalkingStateT(m::TalkingState::TALKING);
+        auto_move::talking_state_changed(&mut api, connection, me, talking, at(100));
+        assert_eq!(moves(), vec!["42 -> 0"]);
+        auto_move::channel_entered(&mut api, connection, me, Some(afk_channel), root, at(101));
+        let reports: Vec<_> = auto_move::take_reports()
+            .into_iter()
+            .map(|(movement, result)| (movement.reason, result))
+            .collect();
+        assert_eq!(
+            reports,
+            vec![(MoveReason::Afk, Ok(())), (MoveReason::Return, Ok(()))]
+        );
+
+        let gone = AfkConfig {
+            channel: GONE,
+            after: Duration::from_secs(10),
+            ..afk
+        };
+        auto_move::set_afk(&mut api, connection, Some(gone)).unwrap();
+        auto_move::tick(&mut api, at(200));
+        assert_eq!(moves(), vec!["42 -> 99"]);
+        let not_found = m::ErrorT(m::ErrorCode::EC_CHANNEL_NOT_FOUND);
+        assert_eq!(
+            auto_move::take_reports()[0].1,
+            Err(MoveError::Mumble(not_found))
+        );
+        assert_eq!(auto_move::afk(connection), None);
+        auto_move::server_disconnected(connection);
+        fake::finish("afk_moves_away_and_back_with_passwords");
+    }
+}
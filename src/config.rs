@@ -0,0 +1,454 @@
+//! Settings storage for plugins, in a per-plugin directory under the platform's
+//! configuration directory.
+//!
+//! A `ConfigStore` saves one serde type as TOML or JSON, together with the plugin version
+//! that wrote it. Loading a file written by an older version runs the migrations registered
+//! for the versions in between, then saves the result. Writes go to a temporary file that
+//! replaces the old one, so a crash never leaves a half-written configuration.
+//! `ConfigStore::watch` hot-applies edits made to the file from outside the plugin.
+
+use crate::traits::MumblePluginDescriptor;
+use crate::types as m;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often a watched file is checked for changes
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    /// The file isn't valid for its format, or doesn't match the settings type
+    Malformed(String),
+    /// No configuration directory could be determined for this platform
+    NoConfigDir,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "I/O error: {}", e),
+            ConfigError::Malformed(reason) => write!(f, "Malformed configuration: {}", reason),
+            ConfigError::NoConfigDir => write!(f, "No configuration directory available"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+fn malformed(e: impl fmt::Display) -> ConfigError {
+    ConfigError::Malformed(e.to_string())
+}
+
+/// The platform's directory for user configuration: `$XDG_CONFIG_HOME` (or `~/.config`)
+/// on Linux and other Unixes, `~/Library/Application Support` on macOS and `%APPDATA%` on
+/// Windows.
+pub fn config_dir() -> Option<PathBuf> {
+    let absolute = |name: &str| {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    };
+    if cfg!(windows) {
+        absolute("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        absolute("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        absolute("XDG_CONFIG_HOME").or_else(|| absolute("HOME").map(|home| home.join(".config")))
+    }
+}
+
+/// The directory for `P`'s files: its name, with anything unsafe in a path replaced.
+pub fn plugin_dir<P: MumblePluginDescriptor>() -> Option<PathBuf> {
+    let name: String = P::name()
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches('.');
+    let name = if name.is_empty() { "plugin" } else { name };
+    config_dir().map(|dir| dir.join(name))
+}
+
+fn version_string(version: m::Version) -> String {
+    format!("{}.{}.{}", version.major, version.minor, version.patch)
+}
+
+fn parse_version(text: &str) -> Option<m::Version> {
+    let mut parts = text.split('.').map(|part| part.parse().ok());
+    let version = m::Version {
+        major: parts.next()??,
+        minor: parts.next().unwrap_or(Some(0))?,
+        patch: parts.next().unwrap_or(Some(0))?,
+    };
+    Some(version)
+}
+
+fn version_key(version: m::Version) -> (i32, i32, i32) {
+    (version.major, version.minor, version.patch)
+}
+
+/// The file's layout: the settings under `settings`, beside the version that wrote them
+struct Stored<'a, T> {
+    version: String,
+    settings: &'a T,
+}
+
+impl<T: Serialize> Serialize for Stored<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut stored = serializer.serialize_struct("Stored", 2)?;
+        stored.serialize_field("version", &self.version)?;
+        stored.serialize_field("settings", self.settings)?;
+        stored.end()
+    }
+}
+
+/// Replaces `path` with `contents` through a temporary file beside it, creating the
+/// directory if needed.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temporary = path.to_path_buf().into_os_string();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    {
+        let mut file = fs::File::create(&temporary)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temporary, path)
+}
+
+type Migration = Box<dyn Fn(&mut serde_json::Value) + Send + Sync>;
+
+/// Modification time and length, to notice edits
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+struct Store {
+    path: PathBuf,
+    format: ConfigFormat,
+    version: m::Version,
+    /// Sorted by version
+    migrations: Vec<(m::Version, Migration)>,
+    /// The file as last read or written here, so our own saves aren't reported as edits
+    seen: Mutex<Stamp>,
+}
+
+impl Store {
+    /// The settings as a JSON value, migrated to the current version. `None` if the file
+    /// doesn't exist; `true` alongside if migrations ran.
+    fn read(&self) -> ConfigResult<Option<(serde_json::Value, bool)>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        *self.seen.lock() = stamp(&self.path);
+        let mut stored: serde_json::Value = match self.format {
+            ConfigFormat::Toml => {
+                let value: toml::Value = toml::from_str(&text).map_err(malformed)?;
+                serde_json::to_value(value).map_err(malformed)?
+            }
+            ConfigFormat::Json => serde_json::from_str(&text).map_err(malformed)?,
+        };
+        let written_by = stored
+            .get("version")
+            .and_then(|version| version.as_str())
+            .and_then(parse_version)
+            .unwrap_or(m::Version {
+                major: 0,
+                minor: 0,
+                patch: 0,
+            });
+        let mut settings = match stored.get_mut("settings") {
+            Some(settings) => settings.take(),
+            None => return Err(malformed("missing `settings`")),
+        };
+        let mut migrated = false;
+        for (version, migration) in &self.migrations {
+            let key = version_key(*version);
+            if key > version_key(written_by) && key <= version_key(self.version) {
+                migration(&mut settings);
+                migrated = true;
+            }
+        }
+        Ok(Some((settings, migrated)))
+    }
+
+    fn write<T: Serialize>(&self, settings: &T) -> ConfigResult<()> {
+        let stored = Stored {
+            version: version_string(self.version),
+            settings,
+        };
+        let text = match self.format {
+            ConfigFormat::Toml => toml::to_string_pretty(&stored).map_err(malformed)?,
+            ConfigFormat::Json => serde_json::to_string_pretty(&stored).map_err(malformed)?,
+        };
+        write_atomically(&self.path, text.as_bytes())?;
+        *self.seen.lock() = stamp(&self.path);
+        Ok(())
+    }
+
+    fn load<T: DeserializeOwned + Serialize + Default>(&self) -> ConfigResult<T> {
+        match self.read()? {
+            Some((settings, migrated)) => {
+                let settings = serde_json::from_value(settings).map_err(malformed)?;
+                if migrated {
+                    self.write(&settings)?;
+                }
+                Ok(settings)
+            }
+            None => Ok(T::default()),
+        }
+    }
+}
+
+/// Loads and saves settings of type `T`.
+pub struct ConfigStore<T> {
+    store: Arc<Store>,
+    settings: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned + Serialize + Default + 'static> ConfigStore<T> {
+    /// Stores settings at `path`, marked as written by `version`.
+    pub fn new(path: impl Into<PathBuf>, format: ConfigFormat, version: m::Version) -> Self {
+        ConfigStore {
+            store: Arc::new(Store {
+                path: path.into(),
+                format,
+                version,
+                migrations: Vec::new(),
+                seen: Mutex::new(None),
+            }),
+            settings: PhantomData,
+        }
+    }
+
+    /// `config.toml` or `config.json` in `plugin_dir::<P>()`, marked with `P::version()`.
+    pub fn for_plugin<P: MumblePluginDescriptor>(format: ConfigFormat) -> ConfigResult<Self> {
+        let dir = plugin_dir::<P>().ok_or(ConfigError::NoConfigDir)?;
+        let path = dir.join(format!("config.{}", format.extension()));
+        Ok(Self::new(path, format, P::version()))
+    }
+
+    /// Runs `migration` on settings written before `version`, when loading them with
+    /// `version` or later. Migrations run in version order.
+    pub fn with_migration(
+        mut self,
+        version: m::Version,
+        migration: impl Fn(&mut serde_json::Value) + Send + Sync + 'static,
+    ) -> Self {
+        let store = Arc::get_mut(&mut self.store).expect("Migrations are added before watching");
+        store.migrations.push((version, Box::new(migration)));
+        store
+            .migrations
+            .sort_by_key(|(version, _)| version_key(*version));
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.store.path
+    }
+
+    /// The stored settings, or `T::default()` if there are none yet. Migrated settings are
+    /// saved back right away.
+    pub fn load(&self) -> ConfigResult<T> {
+        self.store.load()
+    }
+
+    pub fn save(&self, settings: &T) -> ConfigResult<()> {
+        self.store.write(settings)
+    }
+
+    /// Calls `on_change` on a background thread with the new settings whenever the file is
+    /// changed by something other than this store. Unreadable edits are logged and skipped.
+    /// Watching stops when the returned `ConfigWatcher` is dropped.
+    pub fn watch(
+        &self,
+        mut on_change: impl FnMut(T) + Send + 'static,
+    ) -> io::Result<ConfigWatcher> {
+        let store = self.store.clone();
+        if store.seen.lock().is_none() {
+            *store.seen.lock() = stamp(&store.path);
+        }
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::Builder::new()
+            .name("mumble-sys config watcher".into())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(WATCH_INTERVAL) {
+                    let current = stamp(&store.path);
+                    if current.is_none() || current == *store.seen.lock() {
+                        continue;
+                    }
+                    match store.load::<T>() {
+                        Ok(settings) => on_change(settings),
+                        Err(e) => {
+                            *store.seen.lock() = current;
+                            log::warn!("Ignoring edit to {:?}: {}", store.path, e);
+                        }
+                    }
+                }
+            })?;
+        Ok(ConfigWatcher {
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Stops watching when dropped.
+pub struct ConfigWatcher {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            // Not from the watcher's own callback, which would wait on itself
+            if thread.thread().id() != thread::current().id() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{ConfigFormat, ConfigStore, WATCH_INTERVAL};
+    use crate::types as m;
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+
+    type Settings = BTreeMap<String, Value>;
+
+    const VERSION: m::Version = m::Version {
+        major: 1,
+        minor: 2,
+        patch: 0,
+    };
+
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mumble-sys-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn settings(value: Value) -> Settings {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let dir = temp_dir("config-round-trip");
+        for format in [ConfigFormat::Toml, ConfigFormat::Json] {
+            let path = dir.join(format!("config.{}", format.extension()));
+            let store = ConfigStore::<Settings>::new(&path, format, VERSION);
+            assert_eq!(store.load().unwrap(), Settings::new());
+            let saved = settings(json!({ "volume": 3, "name": "ünïcode" }));
+            store.save(&saved).unwrap();
+            assert_eq!(store.load().unwrap(), saved);
+            assert!(fs::read_to_string(&path).unwrap().contains("1.2.0"));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn older_settings_are_migrated_and_saved() {
+        let dir = temp_dir("config-migration");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "version = \"1.0.3\"\n\n[settings]\nvol = 3\n").unwrap();
+        let version = |major, minor| m::Version {
+            major,
+            minor,
+            patch: 0,
+        };
+        let store = ConfigStore::<Settings>::new(&path, ConfigFormat::Toml, VERSION)
+            .with_migration(version(2, 0), |settings| settings["future"] = json!(true))
+            .with_migration(version(1, 0), |settings| settings["past"] = json!(true))
+            .with_migration(version(1, 1), |settings| {
+                let vol = settings["vol"].take();
+                settings["volume"] = vol;
+                settings.as_object_mut().unwrap().remove("vol");
+            });
+        let expected = settings(json!({ "volume": 3 }));
+        assert_eq!(store.load().unwrap(), expected);
+        // Saved with the current version, so nothing runs again
+        assert!(fs::read_to_string(&path).unwrap().contains("1.2.0"));
+        assert_eq!(store.load().unwrap(), expected);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn external_edits_are_reported() {
+        let dir = temp_dir("config-watch");
+        let path = dir.join("config.json");
+        let store = ConfigStore::<Settings>::new(&path, ConfigFormat::Json, VERSION);
+        store.save(&settings(json!({ "volume": 1 }))).unwrap();
+        let (changed, changes) = mpsc::channel();
+        let watcher = store
+            .watch(move |settings| changed.send(settings).unwrap())
+            .unwrap();
+
+        let edited = r#"{ "version": "1.2.0", "settings": { "volume": 20 } }"#;
+        fs::write(&path, edited).unwrap();
+        let reported = changes.recv_timeout(WATCH_INTERVAL * 5).unwrap();
+        assert_eq!(reported, settings(json!({ "volume": 20 })));
+
+        store.save(&settings(json!({ "volume": 300 }))).unwrap();
+        assert!(changes.recv_timeout(WATCH_INTERVAL * 2).is_err());
+        drop(watcher);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+use super::{db_to_linear, linear_to_db, time_coefficient, Effect, Param};
+
+/// Peak across all channels of a frame, so linked channels are gained identically
+fn frame_peak(frame: &[f32]) -> f32 {
+    frame
+        .iter()
+        .fold(0f32, |peak, sample| peak.max(sample.abs()))
+}
+
+/// Silences the signal while it stays below the threshold.
+pub struct NoiseGate {
+    threshold_db: Param,
+    attack_ms: Param,
+    hold_ms: Param,
+    release_ms: Param,
+    gain: f32,
+    /// Frames since the signal was last above the threshold; a fresh gate starts closed
+    held_frames: u32,
+}
+
+impl NoiseGate {
+    pub fn new(threshold_db: f32) -> Self {
+        NoiseGate {
+            threshold_db: Param::new(threshold_db),
+            attack_ms: Param::new(1.0),
+            hold_ms: Param::new(50.0),
+            release_ms: Param::new(100.0),
+            gain: 0.0,
+            held_frames: u32::MAX,
+        }
+    }
+
+    pub fn threshold_db(&self) -> Param {
+        self.threshold_db.clone()
+    }
+
+    pub fn attack_ms(&self) -> Param {
+        self.attack_ms.clone()
+    }
+
+    pub fn hold_ms(&self) -> Param {
+        self.hold_ms.clone()
+    }
+
+    pub fn release_ms(&self) -> Param {
+        self.release_ms.clone()
+    }
+}
+
+impl Effect for NoiseGate {
+    fn process(&mut self, pcm: &mut [f32], channel_count: u16, sample_rate: u32) {
+        let threshold = db_to_linear(self.threshold_db.get());
+        let attack = time_coefficient(self.attack_ms.get(), sample_rate);
+        let release = time_coefficient(self.release_ms.get(), sample_rate);
+        let hold_frames = (self.hold_ms.get().max(0.0) * 0.001 * sample_rate as f32) as u32;
+        for frame in pcm.chunks_mut(channel_count as usize) {
+            let open = if frame_peak(frame) >= threshold {
+                self.held_frames = 0;
+                true
+            } else if self.held_frames < hold_frames {
+                self.held_frames += 1;
+                true
+            } else {
+                false
+            };
+            let (target, coefficient) = if open { (1.0, attack) } else { (0.0, release) };
+            self.gain = target + (self.gain - target) * coefficient;
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.gain = 0.0;
+        self.held_frames = u32::MAX;
+    }
+
+    fn fork(&self) -> Box<dyn Effect> {
+        Box::new(NoiseGate {
+            threshold_db: self.threshold_db.clone(),
+            attack_ms: self.attack_ms.clone(),
+            hold_ms: self.hold_ms.clone(),
+            release_ms: self.release_ms.clone(),
+            gain: 0.0,
+            held_frames: u32::MAX,
+        })
+    }
+}
+
+/// Downward compressor with a hard knee and linked channels.
+pub struct Compressor {
+    threshold_db: Param,
+    ratio: Param,
+    attack_ms: Param,
+    release_ms: Param,
+    makeup_db: Param,
+    envelope_db: f32,
+}
+
+impl Compressor {
+    pub fn new(threshold_db: f32, ratio: f32) -> Self {
+        Compressor {
+            threshold_db: Param::new(threshold_db),
+            ratio: Param::new(ratio),
+            attack_ms: Param::new(5.0),
+            release_ms: Param::new(80.0),
+            makeup_db: Param::new(0.0),
+            envelope_db: -120.0,
+        }
+    }
+
+    pub fn threshold_db(&self) -> Param {
+        self.threshold_db.clone()
+    }
+
+    pub fn ratio(&self) -> Param {
+        self.ratio.clone()
+    }
+
+    pub fn attack_ms(&self) -> Param {
+        self.attack_ms.clone()
+    }
+
+    pub fn release_ms(&self) -> Param {
+        self.release_ms.clone()
+    }
+
+    pub fn makeup_db(&self) -> Param {
+        self.makeup_db.clone()
+    }
+}
+
+impl Effect for Compressor {
+    fn process(&mut self, pcm: &mut [f32], channel_count: u16, sample_rate: u32) {
+        let threshold_db = self.threshold_db.get();
+        let slope = 1.0 - 1.0 / self.ratio.get().max(1.0);
+        let attack = time_coefficient(self.attack_ms.get(), sample_rate);
+        let release = time_coefficient(self.release_ms.get(), sample_rate);
+        let makeup_db = self.makeup_db.get();
+        for frame in pcm.chunks_mut(channel_count as usize) {
+            let level_db = linear_to_db(frame_peak(frame));
+            let coefficient = if level_db > self.envelope_db {
+                attack
+            } else {
+                release
+            };
+            self.envelope_db = level_db + (self.envelope_db - level_db) * coefficient;
+            let reduction_db = (self.envelope_db - threshold_db).max(0.0) * slope;
+            let gain = db_to_linear(makeup_db - reduction_db);
+            for sample in frame.iter_mut() {
+                *sample *= gain;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.envelope_db = -120.0;
+    }
+
+    fn fork(&self) -> Box<dyn Effect> {
+        Box::new(Compressor {
+            threshold_db: self.threshold_db.clone(),
+            ratio: self.ratio.clone(),
+            attack_ms: self.attack_ms.clone(),
+            release_ms: self.release_ms.clone(),
+            makeup_db: self.makeup_db.clone(),
+            envelope_db: -120.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48000;
+
+    #[test]
+    fn gate_closes_on_quiet_input_and_opens_on_loud() {
+        let mut gate = NoiseGate::new(-30.0);
+        gate.hold_ms().set(0.0);
+        gate.release_ms().set(1.0);
+        let mut quiet = vec![0.01f32; 4800];
+        gate.process(&mut quiet, 1, RATE);
+        assert!(quiet[4799].abs() < 1e-4);
+
+        let mut loud = vec![0.5f32; 4800];
+        gate.process(&mut loud, 1, RATE);
+        assert!((loud[4799] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn gate_holds_open_before_releasing() {
+        let mut gate = NoiseGate::new(-30.0);
+        gate.attack_ms().set(0.0);
+        gate.release_ms().set(0.0);
+        let mut loud = vec![0.5f32; 480];
+        gate.process(&mut loud, 1, RATE);
+        // 50ms of hold is 2400 frames
+        let mut quiet = vec![0.01f32; 4800];
+        gate.process(&mut quiet, 1, RATE);
+        assert_eq!(quiet[2399], 0.01);
+        assert_eq!(quiet[2401], 0.0);
+    }
+
+    #[test]
+    fn compressor_reduces_by_its_ratio_above_the_threshold() {
+        let mut compressor = Compressor::new(-20.0, 4.0);
+        // 0dBFS is 20dB over, so 15dB of reduction once the envelope settles
+        let mut pcm = vec![1.0f32; 48000];
+        compressor.process(&mut pcm, 1, RATE);
+        let level = linear_to_db(pcm[47999]);
+        assert!((level + 15.0).abs() < 0.1, "{}", level);
+
+        let mut quiet = vec![0.01f32; 48000];
+        compressor.reset();
+        compressor.process(&mut quiet, 1, RATE);
+        assert!((quiet[47999] - 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn compressor_makeup_gain_applies_everywhere() {
+        let mut compressor = Compressor::new(0.0, 2.0);
+        compressor.makeup_db().set(6.0);
+        let mut pcm = vec![0.1f32; 4800];
+        compressor.process(&mut pcm, 2, RATE);
+        assert!((pcm[4799] - 0.1 * db_to_linear(6.0)).abs() < 1e-4);
+    }
+}
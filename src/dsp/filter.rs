@@ -0,0 +1,332 @@
+use super::{Effect, Param, MAX_CHANNELS};
+use std::f32::consts::PI;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BiquadKind {
+    LowPass,
+    HighPass,
+    /// Bell boost or cut by `gain_db` around the frequency
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct Coefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Coefficients {
+    // Robert Bristow-Johnson's audio EQ cookbook formulae
+    fn design(kind: BiquadKind, frequency: f32, q: f32, gain_db: f32, sample_rate: u32) -> Self {
+        let nyquist = sample_rate as f32 / 2.0;
+        let w0 = 2.0 * PI * frequency.max(1.0).min(nyquist * 0.99) / sample_rate as f32;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q.max(0.01));
+        let a = 10f32.powf(gain_db / 40.0);
+        let (b0, b1, b2, a0, a1, a2) = match kind {
+            BiquadKind::LowPass => (
+                (1.0 - cos) / 2.0,
+                1.0 - cos,
+                (1.0 - cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BiquadKind::HighPass => (
+                (1.0 + cos) / 2.0,
+                -(1.0 + cos),
+                (1.0 + cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BiquadKind::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            BiquadKind::LowShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + k),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - k),
+                    (a + 1.0) + (a - 1.0) * cos + k,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - k,
+                )
+            }
+            BiquadKind::HighShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + k),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - k),
+                    (a + 1.0) - (a - 1.0) * cos + k,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - k,
+                )
+            }
+        };
+        Coefficients {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+// Transposed direct form II history
+#[derive(Debug, Copy, Clone, Default)]
+struct State {
+    z1: f32,
+    z2: f32,
+}
+
+/// Second-order IIR filter, with independent history for each channel.
+pub struct Biquad {
+    kind: BiquadKind,
+    frequency: Param,
+    q: Param,
+    gain_db: Param,
+    designed_for: (f32, f32, f32, u32),
+    coefficients: Coefficients,
+    state: [State; MAX_CHANNELS],
+}
+
+impl Biquad {
+    pub fn new(kind: BiquadKind, frequency: f32, q: f32, gain_db: f32) -> Self {
+        Biquad {
+            kind,
+            frequency: Param::new(frequency),
+            q: Param::new(q),
+            gain_db: Param::new(gain_db),
+            designed_for: (f32::NAN, f32::NAN, f32::NAN, 0),
+            coefficients: Coefficients::default(),
+            state: [State::default(); MAX_CHANNELS],
+        }
+    }
+
+    pub fn low_pass(frequency: f32) -> Self {
+        Self::new(
+            BiquadKind::LowPass,
+            frequency,
+            std::f32::consts::FRAC_1_SQRT_2,
+            0.0,
+        )
+    }
+
+    pub fn high_pass(frequency: f32) -> Self {
+        Self::new(
+            BiquadKind::HighPass,
+            frequency,
+            std::f32::consts::FRAC_1_SQRT_2,
+            0.0,
+        )
+    }
+
+    pub fn kind(&self) -> BiquadKind {
+        self.kind
+    }
+
+    pub fn frequency(&self) -> Param {
+        self.frequency.clone()
+    }
+
+    pub fn q(&self) -> Param {
+        self.q.clone()
+    }
+
+    /// Only meaningful for peaking and shelving filters
+    pub fn gain_db(&self) -> Param {
+        self.gain_db.clone()
+    }
+
+    /// Same parameters, fresh history
+    fn forked(&self) -> Self {
+        Biquad {
+            kind: self.kind,
+            frequency: self.frequency.clone(),
+            q: self.q.clone(),
+            gain_db: self.gain_db.clone(),
+            designed_for: (f32::NAN, f32::NAN, f32::NAN, 0),
+            coefficients: Coefficients::default(),
+            state: [State::default(); MAX_CHANNELS],
+        }
+    }
+
+    fn update_coefficients(&mut self, sample_rate: u32) {
+        let wanted = (
+            self.frequency.get(),
+            self.q.get(),
+            self.gain_db.get(),
+            sample_rate,
+        );
+        if wanted != self.designed_for {
+            self.coefficients =
+                Coefficients::design(self.kind, wanted.0, wanted.1, wanted.2, wanted.3);
+            self.designed_for = wanted;
+        }
+    }
+}
+
+impl Effect for Biquad {
+    fn process(&mut self, pcm: &mut [f32], channel_count: u16, sample_rate: u32) {
+        self.update_coefficients(sample_rate);
+        let c = self.coefficients;
+        let channels = channel_count as usize;
+        for frame in pcm.chunks_mut(channels) {
+            for (sample, state) in frame.iter_mut().zip(self.state.iter_mut()) {
+                let x = *sample;
+                let y = c.b0 * x + state.z1;
+                state.z1 = c.b1 * x - c.a1 * y + state.z2;
+                state.z2 = c.b2 * x - c.a2 * y;
+                *sample = y;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = [State::default(); MAX_CHANNELS];
+    }
+
+    fn fork(&self) -> Box<dyn Effect> {
+        Box::new(self.forked())
+    }
+}
+
+/// A bank of filters applied in series, each band adjustable through its own `Param`s.
+pub struct ParametricEq {
+    bands: Vec<Biquad>,
+}
+
+impl Default for ParametricEq {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParametricEq {
+    pub fn new() -> Self {
+        ParametricEq { bands: Vec::new() }
+    }
+
+    pub fn with_band(mut self, kind: BiquadKind, frequency: f32, q: f32, gain_db: f32) -> Self {
+        self.bands.push(Biquad::new(kind, frequency, q, gain_db));
+        self
+    }
+
+    pub fn band(&self, index: usize) -> Option<&Biquad> {
+        self.bands.get(index)
+    }
+
+    pub fn band_count(&self) -> usize {
+        self.bands.len()
+    }
+}
+
+impl Effect for ParametricEq {
+    fn process(&mut self, pcm: &mut [f32], channel_count: u16, sample_rate: u32) {
+        for band in self.bands.iter_mut() {
+            band.process(pcm, channel_count, sample_rate);
+        }
+    }
+
+    fn reset(&mut self) {
+        for band in self.bands.iter_mut() {
+            band.reset();
+        }
+    }
+
+    fn fork(&self) -> Box<dyn Effect> {
+        Box::new(ParametricEq {
+            bands: self.bands.iter().map(Biquad::forked).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48000;
+
+    fn sine(frequency: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * PI * frequency * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    /// Peak of the second half, once the filter has settled
+    fn settled_peak(pcm: &[f32]) -> f32 {
+        pcm[pcm.len() / 2..]
+            .iter()
+            .fold(0f32, |peak, sample| peak.max(sample.abs()))
+    }
+
+    #[test]
+    fn low_pass_keeps_lows_and_cuts_highs() {
+        let mut low = sine(100.0, 4800);
+        let mut high = sine(10000.0, 4800);
+        Biquad::low_pass(1000.0).process(&mut low, 1, RATE);
+        Biquad::low_pass(1000.0).process(&mut high, 1, RATE);
+        assert!(settled_peak(&low) > 0.95);
+        assert!(settled_peak(&high) < 0.02);
+    }
+
+    #[test]
+    fn high_pass_cuts_lows() {
+        let mut low = sine(50.0, 9600);
+        Biquad::high_pass(2000.0).process(&mut low, 1, RATE);
+        assert!(settled_peak(&low) < 0.01);
+    }
+
+    #[test]
+    fn channels_keep_separate_history() {
+        let mut filter = Biquad::low_pass(1000.0);
+        // Left carries a tone, right is silent
+        let mut pcm: Vec<f32> = sine(100.0, 4800)
+            .into_iter()
+            .flat_map(|sample| vec![sample, 0.0])
+            .collect();
+        filter.process(&mut pcm, 2, RATE);
+        assert!(pcm.iter().skip(1).step_by(2).all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn forks_share_parameters_but_not_history() {
+        let filter = Biquad::low_pass(1000.0);
+        let mut fork = filter.fork();
+        filter.frequency().set(20000.0);
+        let mut high = sine(10000.0, 4800);
+        fork.process(&mut high, 1, RATE);
+        assert!(settled_peak(&high) > 0.9);
+
+        let mut filter = Biquad::low_pass(1000.0);
+        let mut signal = vec![1.0f32; 64];
+        filter.process(&mut signal, 1, RATE);
+        let mut silence = vec![0.0f32; 4];
+        filter.fork().process(&mut silence, 1, RATE);
+        assert_eq!(silence, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn peaking_band_boosts_its_frequency() {
+        let mut eq = ParametricEq::default().with_band(BiquadKind::Peaking, 1000.0, 1.0, 6.0);
+        let mut tone = sine(1000.0, 4800);
+        eq.process(&mut tone, 1, RATE);
+        let boosted = settled_peak(&tone);
+        assert!((boosted - 2.0).abs() < 0.05, "{}", boosted);
+        assert_eq!(eq.band_count(), 1);
+    }
+}
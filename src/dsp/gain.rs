@@ -0,0 +1,151 @@
+use super::{db_to_linear, Effect, Param};
+
+/// Volume change in decibels, ramped across each buffer to avoid zipper noise.
+pub struct Gain {
+    db: Param,
+    current: f32,
+}
+
+impl Gain {
+    pub fn new(db: f32) -> Self {
+        Gain {
+            db: Param::new(db),
+            current: db_to_linear(db),
+        }
+    }
+
+    pub fn db(&self) -> Param {
+        self.db.clone()
+    }
+}
+
+impl Effect for Gain {
+    fn process(&mut self, pcm: &mut [f32], channel_count: u16, _sample_rate: u32) {
+        let target = db_to_linear(self.db.get());
+        let channels = channel_count as usize;
+        let frames = pcm.len() / channels;
+        if frames == 0 {
+            return;
+        }
+        let step = (target - self.current) / frames as f32;
+        for frame in pcm.chunks_mut(channels) {
+            self.current += step;
+            for sample in frame.iter_mut() {
+                *sample *= self.current;
+            }
+        }
+        self.current = target;
+    }
+
+    fn fork(&self) -> Box<dyn Effect> {
+        Box::new(Gain {
+            db: self.db.clone(),
+            current: db_to_linear(self.db.get()),
+        })
+    }
+}
+
+/// Clamps samples to a ceiling given in dBFS.
+pub struct HardLimiter {
+    ceiling_db: Param,
+}
+
+impl HardLimiter {
+    pub fn new(ceiling_db: f32) -> Self {
+        HardLimiter {
+            ceiling_db: Param::new(ceiling_db),
+        }
+    }
+
+    pub fn ceiling_db(&self) -> Param {
+        self.ceiling_db.clone()
+    }
+}
+
+impl Effect for HardLimiter {
+    fn process(&mut self, pcm: &mut [f32], _channel_count: u16, _sample_rate: u32) {
+        let ceiling = db_to_linear(self.ceiling_db.get());
+        for sample in pcm.iter_mut() {
+            *sample = sample.max(-ceiling).min(ceiling);
+        }
+    }
+
+    fn fork(&self) -> Box<dyn Effect> {
+        Box::new(HardLimiter {
+            ceiling_db: self.ceiling_db.clone(),
+        })
+    }
+}
+
+/// Leaves samples below the threshold untouched and bends everything above it
+/// smoothly towards full scale, so peaks never exceed 0dBFS.
+pub struct SoftLimiter {
+    threshold_db: Param,
+}
+
+impl SoftLimiter {
+    pub fn new(threshold_db: f32) -> Self {
+        SoftLimiter {
+            threshold_db: Param::new(threshold_db),
+        }
+    }
+
+    pub fn threshold_db(&self) -> Param {
+        self.threshold_db.clone()
+    }
+}
+
+impl Effect for SoftLimiter {
+    fn process(&mut self, pcm: &mut [f32], _channel_count: u16, _sample_rate: u32) {
+        let threshold = db_to_linear(self.threshold_db.get()).min(0.999);
+        let headroom = 1.0 - threshold;
+        for sample in pcm.iter_mut() {
+            let magnitude = sample.abs();
+            if magnitude > threshold {
+                let bent = threshold + headroom * ((magnitude - threshold) / headroom).tanh();
+                *sample = bent.copysign(*sample);
+            }
+        }
+    }
+
+    fn fork(&self) -> Box<dyn Effect> {
+        Box::new(SoftLimiter {
+            threshold_db: self.threshold_db.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_ramps_to_its_target() {
+        let mut gain = Gain::new(0.0);
+        gain.db().set(-6.0);
+        let mut pcm = vec![1.0f32; 96];
+        gain.process(&mut pcm, 2, 48000);
+        assert!(pcm[0] > pcm[95]);
+        assert!((pcm[95] - db_to_linear(-6.0)).abs() < 1e-6);
+
+        let mut pcm = vec![1.0f32; 4];
+        gain.process(&mut pcm, 2, 48000);
+        assert!(pcm
+            .iter()
+            .all(|sample| (sample - db_to_linear(-6.0)).abs() < 1e-6));
+    }
+
+    #[test]
+    fn limiters_keep_peaks_under_their_ceiling() {
+        let mut pcm = vec![2.0, -2.0, 0.25];
+        HardLimiter::new(-6.0).process(&mut pcm, 1, 48000);
+        let ceiling = db_to_linear(-6.0);
+        assert_eq!(pcm, vec![ceiling, -ceiling, 0.25]);
+
+        let mut pcm = vec![4.0, -4.0, 0.25];
+        SoftLimiter::new(-6.0).process(&mut pcm, 1, 48000);
+        assert!(pcm[0] < 1.0 && pcm[0] > ceiling);
+        assert_eq!(pcm[1], -pcm[0]);
+        assert_eq!(pcm[2], 0.25);
+    }
+}
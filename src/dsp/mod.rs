@@ -0,0 +1,430 @@
+//! Composable audio effects which can be attached to the audio callbacks.
+//!
+//! Effects never allocate while processing; all state is sized on construction.
+//! Parameters are held in `Param`s, which can be cloned and adjusted from any thread.
+
+mod dynamics;
+mod filter;
+mod gain;
+mod resample;
+mod source;
+
+pub use dynamics::{Compressor, NoiseGate};
+pub use filter::{Biquad, BiquadKind, ParametricEq};
+pub use gain::{Gain, HardLimiter, SoftLimiter};
+pub use resample::Resampler;
+pub(crate) use source::process_user;
+pub use source::{
+    clear_default_user_processor, register_user_processor, reset_all_user_state,
+    reset_connection_state, reset_user_state, set_default_user_processor,
+    unregister_user_processor, ProcessorFactory,
+};
+
+use crate::ids::Scoped;
+use crate::types as m;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Mumble mixes and captures audio at 48kHz internally, unless told otherwise
+pub const DEFAULT_SAMPLE_RATE: u32 = 48000;
+
+/// Upper bound of interleaved channels effects keep per-channel state for
+pub const MAX_CHANNELS: usize = 8;
+
+const CHAIN_SCRATCH_LEN: usize = 4096;
+
+/// A lock-free `f32` shared between an effect and whoever controls it.
+#[derive(Clone, Debug)]
+pub struct Param(Arc<AtomicU32>);
+
+impl Param {
+    pub fn new(value: f32) -> Self {
+        Param(Arc::new(AtomicU32::new(value.to_bits())))
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, value: f32) {
+        self.0.store(value.to_bits(), Ordering::Relaxed)
+    }
+}
+
+pub fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+pub fn linear_to_db(linear: f32) -> f32 {
+    20.0 * linear.max(1e-10).log10()
+}
+
+/// Coefficient for a one-pole smoother reaching ~63% of its target after `ms` milliseconds
+fn time_coefficient(ms: f32, sample_rate: u32) -> f32 {
+    if ms <= 0.0 {
+        return 0.0;
+    }
+    (-1.0 / (ms * 0.001 * sample_rate as f32)).exp()
+}
+
+pub trait Effect: Send {
+    /// Processes interleaved samples in-place; `pcm.len()` is always a multiple of `channel_count`.
+    fn process(&mut self, pcm: &mut [f32], channel_count: u16, sample_rate: u32);
+
+    /// Clears any internal history, e.g. when the stream is interrupted.
+    fn reset(&mut self) {}
+
+    /// A copy sharing this effect's `Param`s but with fresh history, to run the same effect
+    /// over another stream.
+    fn fork(&self) -> Box<dyn Effect>;
+}
+
+/// An ordered list of effects, applied one after another.
+pub struct EffectChain {
+    effects: Vec<Box<dyn Effect>>,
+    scratch: Box<[f32]>,
+    bypass: Arc<AtomicBool>,
+}
+
+impl Default for EffectChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EffectChain {
+    pub fn new() -> Self {
+        EffectChain {
+            effects: Vec::new(),
+            scratch: vec![0f32; CHAIN_SCRATCH_LEN].into_boxed_slice(),
+            bypass: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Forks every effect, keeping the `Param`s and bypass handle shared with this chain.
+    pub fn fork(&self) -> Self {
+        EffectChain {
+            effects: self.effects.iter().map(|effect| effect.fork()).collect(),
+            scratch: vec![0f32; self.scratch.len()].into_boxed_slice(),
+            bypass: self.bypass.clone(),
+        }
+    }
+
+    pub fn with<E: Effect + 'static>(mut self, effect: E) -> Self {
+        self.push(effect);
+        self
+    }
+
+    pub fn push<E: Effect + 'static>(&mut self, effect: E) {
+        self.effects.push(Box::new(effect));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Handle which can toggle the chain off without detaching it
+    pub fn bypass_handle(&self) -> Arc<AtomicBool> {
+        self.bypass.clone()
+    }
+
+    fn is_active(&self) -> bool {
+        !self.effects.is_empty() && !self.bypass.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the buffer was touched.
+    pub fn process_f32(&mut self, pcm: &mut [f32], channel_count: u16, sample_rate: u32) -> bool {
+        if !self.is_active() || channel_count == 0 {
+            return false;
+        }
+        for effect in self.effects.iter_mut() {
+            effect.process(pcm, channel_count, sample_rate);
+        }
+        true
+    }
+
+    /// Converts to `f32` through the chain's scratch space, one run of whole frames at a time.
+    pub fn process_i16(&mut self, pcm: &mut [i16], channel_count: u16, sample_rate: u32) -> bool {
+        if !self.is_active() || channel_count == 0 {
+            return false;
+        }
+        let channels = channel_count as usize;
+        let run_len = (self.scratch.len() / channels) * channels;
+        if run_len == 0 {
+            // Not even one frame fits the scratch space
+            return false;
+        }
+        for run in pcm.chunks_mut(run_len) {
+            let scratch = &mut self.scratch[..run.len()];
+            for (out, sample) in scratch.iter_mut().zip(run.iter()) {
+                *out = *sample as f32 / 32768.0;
+            }
+            for effect in self.effects.iter_mut() {
+                effect.process(scratch, channel_count, sample_rate);
+            }
+            for (out, sample) in run.iter_mut().zip(scratch.iter()) {
+                *out = (*sample * 32768.0).clamp(-32768.0, 32767.0) as i16;
+            }
+        }
+        true
+    }
+
+    pub fn reset(&mut self) {
+        for effect in self.effects.iter_mut() {
+            effect.reset();
+        }
+    }
+}
+
+/// Points in Mumble's audio pipeline a chain can be attached to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Stage {
+    /// The local microphone, before encoding (`on_audio_input`)
+    Input,
+    /// Every decoded audio source, before mixing (`on_audio_source_fetched`). Each speaking
+    /// user gets a fork of the attached chain, so one speaker's audio never shapes another's;
+    /// audio from no particular user (e.g. samples) shares one more fork.
+    Source,
+    /// The final mix, before playback (`on_audio_output_about_to_play`)
+    Output,
+}
+
+struct StageChains {
+    input: Option<EffectChain>,
+    source: Option<EffectChain>,
+    output: Option<EffectChain>,
+    /// Forks of `source`, by the user whose audio they process
+    sources: BTreeMap<Option<Scoped<m::UserIdT>>, EffectChain>,
+}
+
+impl StageChains {
+    const fn new() -> Self {
+        StageChains {
+            input: None,
+            source: None,
+            output: None,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    fn get_mut(&mut self, stage: Stage) -> &mut Option<EffectChain> {
+        match stage {
+            Stage::Input => &mut self.input,
+            Stage::Source => &mut self.source,
+            Stage::Output => &mut self.output,
+        }
+    }
+}
+
+struct StageRates {
+    input: AtomicU32,
+    source: AtomicU32,
+    output: AtomicU32,
+}
+
+impl StageRates {
+    fn get(&self, stage: Stage) -> &AtomicU32 {
+        match stage {
+            Stage::Input => &self.input,
+            Stage::Source => &self.source,
+            Stage::Output => &self.output,
+        }
+    }
+}
+
+static STAGE_RATES: StageRates = StageRates {
+    input: AtomicU32::new(DEFAULT_SAMPLE_RATE),
+    source: AtomicU32::new(DEFAULT_SAMPLE_RATE),
+    output: AtomicU32::new(DEFAULT_SAMPLE_RATE),
+};
+
+/// The rate audio at `stage` is currently running at.
+///
+/// Each stage follows the rate Mumble passes with its callback, and is `DEFAULT_SAMPLE_RATE`
+/// until the first one arrives.
+pub fn stage_sample_rate(stage: Stage) -> u32 {
+    STAGE_RATES.get(stage).load(Ordering::Relaxed)
+}
+
+/// Records the rate Mumble reported for `stage`. A rate of zero is ignored.
+pub(crate) fn set_stage_sample_rate(stage: Stage, sample_rate: u32) {
+    if sample_rate == 0 {
+        log::warn!("Ignoring a sample rate of 0 for {:?}", stage);
+        return;
+    }
+    STAGE_RATES.get(stage).store(sample_rate, Ordering::Relaxed);
+}
+
+static STAGES: Mutex<StageChains> = Mutex::new(StageChains::new());
+
+/// Installs `chain` at `stage`, returning the chain it replaced.
+pub fn attach(stage: Stage, chain: EffectChain) -> Option<EffectChain> {
+    let mut stages = STAGES.lock();
+    if stage == Stage::Source {
+        stages.sources.clear();
+    }
+    stages.get_mut(stage).replace(chain)
+}
+
+pub fn detach(stage: Stage) -> Option<EffectChain> {
+    let mut stages = STAGES.lock();
+    if stage == Stage::Source {
+        stages.sources.clear();
+    }
+    stages.get_mut(stage).take()
+}
+
+/// Drops the source stage's fork for `user`
+fn forget_source(user: Scoped<m::UserIdT>) {
+    STAGES.lock().sources.remove(&Some(user));
+}
+
+fn forget_connection_sources(connection: m::ConnectionT) {
+    STAGES
+        .lock()
+        .sources
+        .retain(|user, _| user.map_or(true, |user| user.connection() != connection));
+}
+
+fn forget_all_sources() {
+    STAGES.lock().sources.clear();
+}
+
+pub(crate) fn process_stage_i16(
+    stage: Stage,
+    pcm: &mut [i16],
+    channel_count: u16,
+    sample_rate: u32,
+) -> bool {
+    match STAGES.lock().get_mut(stage) {
+        Some(chain) => chain.process_i16(pcm, channel_count, sample_rate),
+        None => false,
+    }
+}
+
+pub(crate) fn process_stage_f32(
+    stage: Stage,
+    pcm: &mut [f32],
+    channel_count: u16,
+    sample_rate: u32,
+) -> bool {
+    match STAGES.lock().get_mut(stage) {
+        Some(chain) => chain.process_f32(pcm, channel_count, sample_rate),
+        None => false,
+    }
+}
+
+/// Runs the source stage over audio from `user`, forking the chain for them on their first
+/// audio.
+pub(crate) fn process_source(
+    user: Option<Scoped<m::UserIdT>>,
+    pcm: &mut [f32],
+    channel_count: u16,
+    sample_rate: u32,
+) -> bool {
+    let mut stages = STAGES.lock();
+    let stages = &mut *stages;
+    let template = match &stages.source {
+        Some(chain) => chain,
+        None => return false,
+    };
+    stages
+        .sources
+        .entry(user)
+        .or_insert_with(|| template.fork())
+        .process_f32(pcm, channel_count, sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `STAGES` is global
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn chain_runs_effects_in_order_on_i16() {
+        let mut chain = EffectChain::default()
+            .with(Gain::new(6.0))
+            .with(HardLimiter::new(-6.0));
+        let mut pcm = vec![16384i16, -16384, 1000, -1000];
+        // First buffer ramps the gain up from unity
+        chain.process_i16(&mut [0i16; 2], 2, 48000);
+        assert!(chain.process_i16(&mut pcm, 2, 48000));
+        let ceiling = (db_to_linear(-6.0) * 32768.0) as i16;
+        assert_eq!(pcm[0], ceiling);
+        assert_eq!(pcm[1], -ceiling);
+        assert!((pcm[2] - 1995).abs() <= 1);
+    }
+
+    #[test]
+    fn bypassed_or_empty_chains_leave_audio_alone() {
+        let mut pcm = vec![0.5f32; 8];
+        assert!(!EffectChain::new().process_f32(&mut pcm, 2, 48000));
+        let mut chain = EffectChain::new().with(Gain::new(-20.0));
+        chain.bypass_handle().store(true, Ordering::Relaxed);
+        assert!(!chain.process_f32(&mut pcm, 2, 48000));
+        assert_eq!(pcm, vec![0.5; 8]);
+    }
+
+    #[test]
+    fn frames_wider_than_the_scratch_space_are_skipped() {
+        let mut chain = EffectChain::new().with(Gain::new(-20.0));
+        let channels = (CHAIN_SCRATCH_LEN + 1) as u16;
+        let mut pcm = vec![1000i16; channels as usize];
+        assert!(!chain.process_i16(&mut pcm, channels, 48000));
+        assert!(pcm.iter().all(|sample| *sample == 1000));
+    }
+
+    #[test]
+    fn zero_sample_rates_are_ignored() {
+        let rate = stage_sample_rate(Stage::Source);
+        set_stage_sample_rate(Stage::Source, 0);
+        assert_eq!(stage_sample_rate(Stage::Source), rate);
+    }
+
+    #[test]
+    fn each_source_gets_its_own_chain_state() {
+        let _serial = SERIAL.lock();
+        let gate = NoiseGate::new(-30.0);
+        gate.attack_ms().set(0.0);
+        gate.release_ms().set(0.0);
+        attach(Stage::Source, EffectChain::new().with(gate));
+        let server = m::ConnectionT(1);
+        let (alice, bob) = (m::UserIdT(1).on(server), m::UserIdT(2).on(server));
+
+        // Alice opens her gate; Bob's stays closed on his quiet audio
+        let mut loud = vec![0.5f32; 480];
+        assert!(process_source(Some(alice), &mut loud, 1, 48000));
+        assert_eq!(loud[479], 0.5);
+        let mut quiet = vec![0.01f32; 480];
+        process_source(Some(bob), &mut quiet, 1, 48000);
+        assert_eq!(quiet[0], 0.0);
+        // Alice's gate is still held open from her own audio
+        let mut quiet = vec![0.01f32; 480];
+        process_source(Some(alice), &mut quiet, 1, 48000);
+        assert_eq!(quiet[0], 0.01);
+
+        reset_user_state(alice);
+        let mut quiet = vec![0.01f32; 480];
+        process_source(Some(alice), &mut quiet, 1, 48000);
+        assert_eq!(quiet[0], 0.0);
+
+        // Only leaving Alice's own server forgets her
+        let mut loud = vec![0.5f32; 480];
+        process_source(Some(alice), &mut loud, 1, 48000);
+        reset_connection_state(m::ConnectionT(2));
+        let mut quiet = vec![0.01f32; 480];
+        process_source(Some(alice), &mut quiet, 1, 48000);
+        assert_eq!(quiet[0], 0.01);
+        reset_connection_state(server);
+        let mut quiet = vec![0.01f32; 480];
+        process_source(Some(alice), &mut quiet, 1, 48000);
+        assert_eq!(quiet[0], 0.0);
+
+        detach(Stage::Source);
+        assert!(!process_source(Some(alice), &mut quiet, 1, 48000));
+    }
+}
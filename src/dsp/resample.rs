@@ -0,0 +1,291 @@
+use super::MAX_CHANNELS;
+use std::f64::consts::PI;
+
+/// Zero crossings of the kernel on each side at full bandwidth
+const ZERO_CROSSINGS: usize = 16;
+/// Longest kernel half, in input frames, once narrowed for downsampling
+const MAX_HALF_WIDTH: usize = 64;
+const HISTORY: usize = 2 * MAX_HALF_WIDTH;
+/// Where the passband ends relative to the lower Nyquist frequency, leaving the rest for
+/// the filter's transition band
+const PASSBAND: f64 = 0.9;
+
+/// Streaming sample-rate converter using a Blackman-windowed sinc kernel.
+///
+/// The kernel's cutoff follows the lower of the two rates, so downsampling filters out what
+/// the new rate can't carry instead of aliasing it. Output lags the input by
+/// [`latency`](Resampler::latency) frames; state carries over between calls so a stream may
+/// be fed in buffers of any size.
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    channels: usize,
+    step: f64,
+    phase: f64,
+    /// Kernel cutoff as a fraction of the input's Nyquist frequency
+    cutoff: f64,
+    half_width: usize,
+    /// Each channel's recent input, written twice so the newest frames are always contiguous
+    history: [[f32; 2 * HISTORY]; MAX_CHANNELS],
+    write: usize,
+}
+
+/// A sine and cosine advanced by a fixed angle, sparing a call to `sin` per kernel tap.
+struct Rotor {
+    sin: f64,
+    cos: f64,
+    step_sin: f64,
+    step_cos: f64,
+}
+
+impl Rotor {
+    fn new(angle: f64, step: f64) -> Self {
+        Rotor {
+            sin: angle.sin(),
+            cos: angle.cos(),
+            step_sin: step.sin(),
+            step_cos: step.cos(),
+        }
+    }
+
+    fn advance(&mut self) {
+        let sin = self.sin * self.step_cos + self.cos * self.step_sin;
+        self.cos = self.cos * self.step_cos - self.sin * self.step_sin;
+        self.sin = sin;
+    }
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32, channel_count: u16) -> Self {
+        assert!(
+            from_rate > 0 && to_rate > 0,
+            "Sample rates must be non-zero"
+        );
+        assert!(
+            channel_count > 0 && channel_count as usize <= MAX_CHANNELS,
+            "Resampler supports 1 to {} channels",
+            MAX_CHANNELS
+        );
+        // At equal rates the kernel collapses to a plain delay
+        let cutoff = if from_rate == to_rate {
+            1.0
+        } else {
+            PASSBAND * (to_rate as f64 / from_rate as f64).min(1.0)
+        };
+        let half_width = ((ZERO_CROSSINGS as f64 / cutoff).ceil() as usize).min(MAX_HALF_WIDTH);
+        Resampler {
+            from_rate,
+            to_rate,
+            channels: channel_count as usize,
+            step: from_rate as f64 / to_rate as f64,
+            phase: 0.0,
+            cutoff,
+            half_width,
+            history: [[0f32; 2 * HISTORY]; MAX_CHANNELS],
+            write: 0,
+        }
+    }
+
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    pub fn is_passthrough(&self) -> bool {
+        self.from_rate == self.to_rate
+    }
+
+    /// How many input frames the output trails the input by.
+    pub fn latency(&self) -> usize {
+        self.half_width + 1
+    }
+
+    /// Upper bound of frames produced from `input_frames` frames, for sizing output buffers.
+    pub fn max_output_frames(&self, input_frames: usize) -> usize {
+        ((input_frames as f64 + 1.0) / self.step).ceil() as usize + 1
+    }
+
+    /// Fills `weights` with the kernel for an output `phase` frames past the middle of the
+    /// history, normalized so a constant signal keeps its level.
+    fn kernel(&self, phase: f64, weights: &mut [f32]) {
+        let half = self.half_width as f64;
+        // Offset of the first tap from the output position
+        let first = -(half - 1.0) - phase;
+        let mut sinc = Rotor::new(PI * self.cutoff * first, PI * self.cutoff);
+        let mut window = Rotor::new(PI * first / half, PI / half);
+        let mut window2 = Rotor::new(2.0 * PI * first / half, 2.0 * PI / half);
+        let mut sum = 0.0;
+        for (tap, weight) in weights.iter_mut().enumerate() {
+            let offset = first + tap as f64;
+            let ideal = if offset.abs() < 1e-9 {
+                self.cutoff
+            } else {
+                sinc.sin / (PI * offset)
+            };
+            let blackman = 0.42 + 0.5 * window.cos + 0.08 * window2.cos;
+            let value = ideal * blackman;
+            sum += value;
+            *weight = value as f32;
+            sinc.advance();
+            window.advance();
+            window2.advance();
+        }
+        let scale = (1.0 / sum) as f32;
+        for weight in weights.iter_mut() {
+            *weight *= scale;
+        }
+    }
+
+    /// Converts interleaved `input` into `output`, returning `(frames consumed, frames produced)`.
+    ///
+    /// Stops early if `output` fills up; unconsumed input must be passed again next call.
+    pub fn process(&mut self, input: &[f32], output: &mut [f32]) -> (usize, usize) {
+        let channels = self.channels;
+        let taps = 2 * self.half_width;
+        let input_frames = input.len() / channels;
+        let output_frames = output.len() / channels;
+        let mut weights = [0f32; HISTORY];
+        let weights = &mut weights[..taps];
+        let (mut consumed, mut produced) = (0, 0);
+        loop {
+            while self.phase < 1.0 {
+                if produced == output_frames {
+                    return (consumed, produced);
+                }
+                self.kernel(self.phase, weights);
+                let newest = self.write + HISTORY;
+                let frame = &mut output[produced * channels..(produced + 1) * channels];
+                for (sample, history) in frame.iter_mut().zip(self.history.iter()) {
+                    *sample = history[newest - taps..newest]
+                        .iter()
+                        .zip(weights.iter())
+                        .map(|(sample, weight)| sample * weight)
+                        .sum();
+                }
+                produced += 1;
+                self.phase += self.step;
+            }
+            if consumed == input_frames {
+                return (consumed, produced);
+            }
+            let frame = &input[consumed * channels..(consumed + 1) * channels];
+            for (sample, history) in frame.iter().zip(self.history.iter_mut()) {
+                history[self.write] = *sample;
+                history[self.write + HISTORY] = *sample;
+            }
+            self.write = (self.write + 1) % HISTORY;
+            consumed += 1;
+            self.phase -= 1.0;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+        self.history = [[0f32; 2 * HISTORY]; MAX_CHANNELS];
+        self.write = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn sine(frequency: f32, rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * PI * frequency * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    fn resample_all(resampler: &mut Resampler, input: &[f32]) -> Vec<f32> {
+        let channels = resampler.channels;
+        let mut output = vec![0f32; resampler.max_output_frames(input.len() / channels) * channels];
+        let (consumed, produced) = resampler.process(input, &mut output);
+        assert_eq!(consumed, input.len() / channels);
+        output.truncate(produced * channels);
+        output
+    }
+
+    #[test]
+    fn passthrough_is_only_delayed() {
+        let mut resampler = Resampler::new(48000, 48000, 1);
+        assert!(resampler.is_passthrough());
+        let latency = resampler.latency();
+        let mut input = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        input.resize(5 + latency, 0.0);
+        let output = resample_all(&mut resampler, &input);
+        assert!(output[..latency].iter().all(|sample| sample.abs() < 1e-6));
+        for (out, expected) in output[latency..].iter().zip(&input) {
+            assert!((out - expected).abs() < 1e-5, "{:?}", output);
+        }
+    }
+
+    #[test]
+    fn converting_keeps_the_tone() {
+        let mut resampler = Resampler::new(48000, 16000, 1);
+        let output = resample_all(&mut resampler, &sine(440.0, 48000, 4800));
+        assert!((output.len() as i64 - 1600).abs() <= 2);
+        // Past the lead-in and clear of the tail, which the kernel sees zeros beyond
+        let settled = &output[resampler.latency()..output.len() - resampler.latency() / 3];
+        let crossings = settled
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count();
+        let expected = 2.0 * 440.0 * settled.len() as f32 / 16000.0;
+        assert!((crossings as f32 - expected).abs() <= 1.5, "{}", crossings);
+        let rms = (settled.iter().map(|s| s * s).sum::<f32>() / settled.len() as f32).sqrt();
+        assert!(
+            (rms - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01,
+            "{}",
+            rms
+        );
+    }
+
+    #[test]
+    fn downsampling_filters_what_the_new_rate_cannot_carry() {
+        let level = |frequency: f32| {
+            let mut resampler = Resampler::new(48000, 16000, 1);
+            let output = resample_all(&mut resampler, &sine(frequency, 48000, 9600));
+            let settled = &output[resampler.latency()..output.len() - resampler.latency()];
+            (settled.iter().map(|s| s * s).sum::<f32>() / settled.len() as f32).sqrt()
+        };
+        // 12kHz would fold down to 4kHz
+        assert!(level(12000.0) < 0.001, "{}", level(12000.0));
+        assert!(level(9000.0) < 0.001, "{}", level(9000.0));
+        assert!((level(3000.0) - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+    }
+
+    #[test]
+    fn buffers_of_any_size_give_the_same_stream() {
+        let input: Vec<f32> = sine(1000.0, 44100, 2000)
+            .into_iter()
+            .flat_map(|sample| vec![sample, -sample])
+            .collect();
+        let whole = resample_all(&mut Resampler::new(44100, 48000, 2), &input);
+
+        let mut resampler = Resampler::new(44100, 48000, 2);
+        let mut pieces = Vec::new();
+        for chunk in input.chunks(2 * 37) {
+            pieces.extend(resample_all(&mut resampler, chunk));
+        }
+        assert_eq!(whole, pieces);
+    }
+
+    #[test]
+    fn full_output_stops_early() {
+        let mut resampler = Resampler::new(16000, 48000, 1);
+        let mut output = [0f32; 10];
+        let (consumed, produced) = resampler.process(&[0.5; 100], &mut output);
+        assert_eq!(produced, 10);
+        assert!(consumed < 100);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_channels_are_rejected() {
+        Resampler::new(48000, 44100, 0);
+    }
+}
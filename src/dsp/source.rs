@@ -0,0 +1,243 @@
+use super::Effect;
+use crate::ids::Scoped;
+use crate::types as m;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+
+/// Builds the processing state for a user the first time audio arrives from them
+pub type ProcessorFactory = Box<dyn Fn(Scoped<m::UserIdT>) -> Box<dyn Effect> + Send>;
+
+/// User IDs repeat across servers, so everything is keyed by the connection as well
+struct SourceProcessors {
+    factories: BTreeMap<Scoped<m::UserIdT>, ProcessorFactory>,
+    fallback: Option<ProcessorFactory>,
+    active: BTreeMap<Scoped<m::UserIdT>, Box<dyn Effect>>,
+}
+
+impl SourceProcessors {
+    const fn new() -> Self {
+        SourceProcessors {
+            factories: BTreeMap::new(),
+            fallback: None,
+            active: BTreeMap::new(),
+        }
+    }
+
+    fn factory_for(&self, user: Scoped<m::UserIdT>) -> Option<&ProcessorFactory> {
+        self.factories.get(&user).or(self.fallback.as_ref())
+    }
+}
+
+static SOURCE_PROCESSORS: Mutex<SourceProcessors> = Mutex::new(SourceProcessors::new());
+
+/// Registers the processor used for audio spoken by `user`, replacing any live state for them.
+pub fn register_user_processor<F>(user: Scoped<m::UserIdT>, factory: F)
+where
+    F: Fn(Scoped<m::UserIdT>) -> Box<dyn Effect> + Send + 'static,
+{
+    let mut processors = SOURCE_PROCESSORS.lock();
+    processors.factories.insert(user, Box::new(factory));
+    processors.active.remove(&user);
+}
+
+pub fn unregister_user_processor(user: Scoped<m::UserIdT>) {
+    let mut processors = SOURCE_PROCESSORS.lock();
+    processors.factories.remove(&user);
+    processors.active.remove(&user);
+}
+
+/// Processor for every user without one of their own.
+pub fn set_default_user_processor<F>(factory: F)
+where
+    F: Fn(Scoped<m::UserIdT>) -> Box<dyn Effect> + Send + 'static,
+{
+    let mut processors = SOURCE_PROCESSORS.lock();
+    processors.fallback = Some(Box::new(factory));
+    forget_default_built(&mut processors);
+}
+
+pub fn clear_default_user_processor() {
+    let mut processors = SOURCE_PROCESSORS.lock();
+    processors.fallback = None;
+    forget_default_built(&mut processors);
+}
+
+fn forget_default_built(processors: &mut SourceProcessors) {
+    let factories = &processors.factories;
+    processors
+        .active
+        .retain(|user, _| factories.contains_key(user));
+}
+
+/// Drops the state built for `user`, including their fork of the source stage's chain; it
+/// is rebuilt if they speak again.
+pub fn reset_user_state(user: Scoped<m::UserIdT>) {
+    SOURCE_PROCESSORS.lock().active.remove(&user);
+    super::forget_source(user);
+}
+
+/// Drops the state built for every user on `connection`.
+pub fn reset_connection_state(connection: m::ConnectionT) {
+    SOURCE_PROCESSORS
+        .lock()
+        .active
+        .retain(|user, _| user.connection() != connection);
+    super::forget_connection_sources(connection);
+}
+
+pub fn reset_all_user_state() {
+    SOURCE_PROCESSORS.lock().active.clear();
+    super::forget_all_sources();
+}
+
+pub(crate) fn process_user(
+    user: Scoped<m::UserIdT>,
+    pcm: &mut [f32],
+    channel_count: u16,
+    sample_rate: u32,
+) -> bool {
+    let mut processors = SOURCE_PROCESSORS.lock();
+    let processors = &mut *processors;
+    if !processors.active.contains_key(&user) {
+        let processor = match processors.factory_for(user) {
+            Some(factory) => factory(user),
+            None => return false,
+        };
+        processors.active.insert(user, processor);
+    }
+    let processor = processors.active.get_mut(&user).unwrap();
+    processor.process(pcm, channel_count, sample_rate);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// The processors are global
+    static SERIAL: Mutex<()> = Mutex::new(());
+    static BUILT: AtomicUsize = AtomicUsize::new(0);
+
+    /// Adds `offset` plus the number of buffers it has seen, so fresh state is visible
+    struct Counting {
+        offset: f32,
+        seen: f32,
+    }
+
+    impl Effect for Counting {
+        fn process(&mut self, pcm: &mut [f32], _channel_count: u16, _sample_rate: u32) {
+            for sample in pcm.iter_mut() {
+                *sample += self.offset + self.seen;
+            }
+            self.seen += 1.0;
+        }
+
+        fn fork(&self) -> Box<dyn Effect> {
+            counting(self.offset)
+        }
+    }
+
+    fn counting(offset: f32) -> Box<dyn Effect> {
+        BUILT.fetch_add(1, Ordering::Relaxed);
+        Box::new(Counting { offset, seen: 0.0 })
+    }
+
+    const SERVER: m::ConnectionT = m::ConnectionT(1);
+
+    fn user(user: u32) -> Scoped<m::UserIdT> {
+        m::UserIdT(user).on(SERVER)
+    }
+
+    fn processed(user: Scoped<m::UserIdT>) -> Option<f32> {
+        let mut pcm = [0.0];
+        process_user(user, &mut pcm, 1, 48000).then(|| pcm[0])
+    }
+
+    #[test]
+    fn each_user_gets_their_own_state() {
+        let _serial = SERIAL.lock();
+        register_user_processor(user(1), |_| counting(10.0));
+        assert_eq!(processed(user(2)), None);
+        set_default_user_processor(|user| counting(user.id().0 as f32 * 100.0));
+        assert_eq!(
+            [
+                processed(user(1)),
+                processed(user(1)),
+                processed(user(2)),
+                processed(user(3)),
+                processed(user(2))
+            ],
+            [
+                Some(10.0),
+                Some(11.0),
+                Some(200.0),
+                Some(300.0),
+                Some(201.0)
+            ]
+        );
+
+        reset_user_state(user(1));
+        assert_eq!(processed(user(1)), Some(10.0));
+        reset_all_user_state();
+        assert_eq!(
+            (processed(user(1)), processed(user(2))),
+            (Some(10.0), Some(200.0))
+        );
+        unregister_user_processor(user(1));
+        clear_default_user_processor();
+        assert_eq!(processed(user(1)), None);
+    }
+
+    #[test]
+    fn the_same_id_on_another_server_is_someone_else() {
+        let _serial = SERIAL.lock();
+        let elsewhere = m::UserIdT(6).on(m::ConnectionT(2));
+        register_user_processor(user(6), |_| counting(10.0));
+        assert_eq!(processed(elsewhere), None);
+        set_default_user_processor(|_| counting(30.0));
+        assert_eq!(
+            (processed(user(6)), processed(elsewhere), processed(user(7))),
+            (Some(10.0), Some(30.0), Some(30.0))
+        );
+
+        // Leaving one server keeps the state built on the other
+        reset_user_state(elsewhere);
+        assert_eq!(
+            (processed(user(6)), processed(elsewhere)),
+            (Some(11.0), Some(30.0))
+        );
+        reset_connection_state(SERVER);
+        assert_eq!(
+            (processed(user(6)), processed(user(7)), processed(elsewhere)),
+            (Some(10.0), Some(30.0), Some(31.0))
+        );
+        unregister_user_processor(user(6));
+        clear_default_user_processor();
+        reset_all_user_state();
+    }
+
+    #[test]
+    fn replacing_a_factory_drops_what_it_built() {
+        let _serial = SERIAL.lock();
+        register_user_processor(user(4), |_| counting(0.0));
+        set_default_user_processor(|_| counting(50.0));
+        assert_eq!(
+            (processed(user(4)), processed(user(4)), processed(user(5))),
+            (Some(0.0), Some(1.0), Some(50.0))
+        );
+
+        let built = BUILT.load(Ordering::Relaxed);
+        register_user_processor(user(4), |_| counting(20.0));
+        set_default_user_processor(|_| counting(70.0));
+        assert_eq!(
+            (processed(user(4)), processed(user(5))),
+            (Some(20.0), Some(70.0))
+        );
+        assert_eq!(BUILT.load(Ordering::Relaxed), built + 2);
+        // A user's own processor outlives changes to the default
+        clear_default_user_processor();
+        assert_eq!((processed(user(4)), processed(user(5))), (Some(21.0), None));
+        unregister_user_processor(user(4));
+    }
+}
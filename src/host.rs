@@ -0,0 +1,277 @@
+//! Ownership of memory Mumble allocates for the plugin, which must be handed back through
+//! `freeMemory` exactly once.
+//!
+//! A function returning a string or array writes its pointer into a `HostOut`. If the call
+//! succeeds, the pointer is claimed as a `HostString` or `HostSlice`, which borrow the memory
+//! without copying; anything left in a `HostOut` is freed when it's dropped, so returning
+//! early on an error never leaks. None of these panic in `Drop`: if `freeMemory` fails, the
+//! memory is leaked instead.
+
+use crate::types as m;
+use crate::versions::RawMumbleAPI;
+use crate::{MumbleAPI, MumbleResult};
+use std::ffi::CStr;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw;
+use std::ptr::{self, NonNull};
+
+/// A pointer owed to `freeMemory`; null owes nothing.
+struct Allocation<T> {
+    pointer: *mut T,
+    plugin_id: m::PluginId,
+    raw_api: RawMumbleAPI,
+}
+
+impl<T> Allocation<T> {
+    fn take(&mut self) -> Allocation<T> {
+        Allocation {
+            pointer: std::mem::replace(&mut self.pointer, ptr::null_mut()),
+            plugin_id: self.plugin_id,
+            raw_api: self.raw_api,
+        }
+    }
+
+    fn free(&mut self) {
+        let pointer = std::mem::replace(&mut self.pointer, ptr::null_mut());
+        if pointer.is_null() {
+            return;
+        }
+        let free_memory = api_fn!(self.raw_api, freeMemory);
+        // Nothing sensible can be done about a failure here; the memory stays allocated
+        let _ = unsafe { free_memory(self.plugin_id, pointer.cast()) };
+    }
+}
+
+impl<T> Drop for Allocation<T> {
+    fn drop(&mut self) {
+        self.free();
+    }
+}
+
+/// Receives a pointer Mumble allocates. Frees it when dropped, unless it has been claimed.
+pub struct HostOut<T> {
+    allocation: Allocation<T>,
+}
+
+impl<T> HostOut<T> {
+    pub fn new(api: &MumbleAPI) -> Self {
+        HostOut {
+            allocation: Allocation {
+                pointer: ptr::null_mut(),
+                plugin_id: *api.id(),
+                raw_api: *api.api(),
+            },
+        }
+    }
+
+    /// Where Mumble should write the pointer. Anything written by an earlier call is freed.
+    pub fn as_mut_ptr(&mut self) -> *mut *mut T {
+        self.allocation.free();
+        &mut self.allocation.pointer
+    }
+
+    pub fn as_mut_const_ptr(&mut self) -> *mut *const T {
+        self.as_mut_ptr().cast()
+    }
+
+    /// Claims an array of `len` elements.
+    ///
+    /// # Safety
+    /// Mumble must have written a valid array of at least `len` elements, or left it null.
+    pub unsafe fn into_slice(mut self, len: usize) -> HostSlice<T> {
+        let allocation = self.allocation.take();
+        let len = if allocation.pointer.is_null() { 0 } else { len };
+        HostSlice { allocation, len }
+    }
+}
+
+impl HostOut<raw::c_char> {
+    /// Claims a nul-terminated string. Null is read as empty; invalid UTF-8 is freed and
+    /// reported as `EC_GENERIC_ERROR`.
+    ///
+    /// # Safety
+    /// Mumble must have written a valid nul-terminated string, or left it null.
+    pub unsafe fn into_string(mut self) -> MumbleResult<HostString> {
+        let allocation = self.allocation.take();
+        let len = if allocation.pointer.is_null() {
+            0
+        } else {
+            let c_str = CStr::from_ptr(allocation.pointer);
+            c_str
+                .to_str()
+                .map_err(|_| m::ErrorT(m::ErrorCode::EC_GENERIC_ERROR))?
+                .len()
+        };
+        Ok(HostString { allocation, len })
+    }
+}
+
+/// A UTF-8 string owned by Mumble, borrowed in place.
+pub struct HostString {
+    allocation: Allocation<raw::c_char>,
+    len: usize,
+}
+
+impl HostString {
+    pub fn as_str(&self) -> &str {
+        if self.allocation.pointer.is_null() {
+            return "";
+        }
+        // Validated when claimed
+        unsafe {
+            let bytes = std::slice::from_raw_parts(self.allocation.pointer.cast(), self.len);
+            std::str::from_utf8_unchecked(bytes)
+        }
+    }
+}
+
+impl Deref for HostString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for HostString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for HostString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for HostString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+/// An array owned by Mumble, borrowed in place.
+pub struct HostSlice<T> {
+    allocation: Allocation<T>,
+    len: usize,
+}
+
+impl<T> HostSlice<T> {
+    pub fn as_slice(&self) -> &[T] {
+        let pointer = NonNull::new(self.allocation.pointer).unwrap_or_else(NonNull::dangling);
+        unsafe { std::slice::from_raw_parts(pointer.as_ptr(), self.len) }
+    }
+}
+
+impl<T> Deref for HostSlice<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> AsRef<[T]> for HostSlice<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HostSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_tests::fake::{self, samples};
+    use crate::api_tests::{unexpected_v1_0, unexpected_v1_2};
+    use crate::host::HostOut;
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use std::os::raw;
+
+    #[test]
+    fn claimed_string_is_borrowed_and_freed_once() {
+        let api = fake::api(RawMumbleAPI::V1_2(unexpected_v1_2()));
+        let mut out = HostOut::new(&api);
+        unsafe { *out.as_mut_const_ptr() = fake::alloc_string(fake::TEXT) };
+        let string = unsafe { out.into_string() }.unwrap();
+        assert_eq!(&*string, fake::TEXT);
+        drop(string);
+        fake::finish("claimed_string_is_borrowed_and_freed_once");
+    }
+
+    #[test]
+    fn claimed_slice_is_borrowed_and_freed_once() {
+        let api = fake::api(RawMumbleAPI::V1_2(unexpected_v1_2()));
+        let mut out = HostOut::new(&api);
+        let (pointer, len) = fake::alloc_array(samples::<m::UserIdT>());
+        unsafe { *out.as_mut_ptr() = pointer };
+        let slice = unsafe { out.into_slice(len) };
+        assert_eq!(&*slice, &samples::<m::UserIdT>()[..]);
+        drop(slice);
+        fake::finish("claimed_slice_is_borrowed_and_freed_once");
+    }
+
+    #[test]
+    fn unclaimed_pointer_is_freed() {
+        let api = fake::api(RawMumbleAPI::V1_0(unexpected_v1_0()));
+        let mut out = HostOut::new(&api);
+        unsafe { *out.as_mut_const_ptr() = fake::alloc_string(fake::TEXT) };
+        drop(out);
+        fake::finish("unclaimed_pointer_is_freed");
+    }
+
+    #[test]
+    fn reused_out_frees_previous_pointer() {
+        let api = fake::api(RawMumbleAPI::V1_2(unexpected_v1_2()));
+        let mut out = HostOut::new(&api);
+        unsafe { *out.as_mut_const_ptr() = fake::alloc_string("first") };
+        unsafe { *out.as_mut_const_ptr() = fake::alloc_string(fake::TEXT) };
+        assert_eq!(&*unsafe { out.into_string() }.unwrap(), fake::TEXT);
+        fake::finish("reused_out_frees_previous_pointer");
+    }
+
+    #[test]
+    fn null_is_empty_and_not_freed() {
+        let api = fake::api(RawMumbleAPI::V1_2(unexpected_v1_2()));
+        let string = unsafe { HostOut::<raw::c_char>::new(&api).into_string() }.unwrap();
+        assert_eq!(&*string, "");
+        let slice = unsafe { HostOut::<m::UserIdT>::new(&api).into_slice(3) };
+        assert!(slice.is_empty());
+        drop((string, slice));
+        fake::finish("null_is_empty_and_not_freed");
+    }
+
+    #[test]
+    fn invalid_utf8_is_freed_and_reported() {
+        let api = fake::api(RawMumbleAPI::V1_2(unexpected_v1_2()));
+        let mut out = HostOut::<raw::c_char>::new(&api);
+        let (pointer, _) = fake::alloc_array(vec![0xffu8, 0xfe, 0]);
+        unsafe { *out.as_mut_ptr() = pointer.cast() };
+        assert_eq!(
+            unsafe { out.into_string() }.unwrap_err(),
+            m::ErrorT(m::ErrorCode::EC_GENERIC_ERROR)
+        );
+        fake::finish("invalid_utf8_is_freed_and_reported");
+    }
+
+    #[test]
+    fn failed_free_does_not_panic() {
+        unsafe extern "C" fn refuse(_: m::PluginId, _: *const raw::c_void) -> m::ErrorT {
+            m::ErrorT(m::ErrorCode::EC_POINTER_NOT_FOUND)
+        }
+        let mut table = unexpected_v1_2();
+        table.freeMemory = refuse;
+        let api = fake::api(RawMumbleAPI::V1_2(table));
+        let mut out = HostOut::<u8>::new(&api);
+        let mut value = 0u8;
+        unsafe { *out.as_mut_ptr() = &mut value };
+        drop(unsafe { out.into_slice(1) });
+        fake::finish("failed_free_does_not_panic");
+    }
+}
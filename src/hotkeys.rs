@@ -0,0 +1,777 @@
+//! Named hotkeys built from `on_key_event`'s raw key codes.
+//!
+//! Bindings pair a name with a chord (`Ctrl+Shift+X`) and a mode: fire on press, while held,
+//! toggle on each press, on a double tap, or on a long press. When they fire, the plugin
+//! gets `MumblePlugin::on_hotkey`. Bindings can be written as strings such as
+//! `"hold Ctrl+Space"` or `"long-press 1500ms F"`, so a map of names to these in the plugin's
+//! settings (see `config`) can be handed to `configure` to make them user-configurable.
+//!
+//! Mumble only reports keys while it has focus, and only once the user has allowed the
+//! plugin to monitor the keyboard. Bindings only fire from callbacks Mumble makes on its main
+//! thread, so a long press is noticed on the next of those after it comes due: at the latest
+//! when the key is released, or sooner if anything else happens, such as someone talking.
+
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// The longest gap between the presses of a double tap
+pub const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(400);
+/// How long `Mode::LongPress` waits when no duration is given
+pub const LONG_PRESS: Duration = Duration::from_millis(600);
+
+/// A key as reported by Mumble.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Key {
+    End,
+    Left,
+    Right,
+    Up,
+    Down,
+    Delete,
+    Backspace,
+    Tab,
+    Enter,
+    Escape,
+    PageUp,
+    PageDown,
+    Shift,
+    Control,
+    Meta,
+    Alt,
+    AltGr,
+    CapsLock,
+    NumLock,
+    Super,
+    Home,
+    Print,
+    ScrollLock,
+    /// `°`, the one extended ASCII character Mumble names
+    Degree,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    /// A printable ASCII character, including `' '`. Mumble doesn't distinguish case, so
+    /// letters are always upper case, nor the keypad from the main keys.
+    Char(char),
+    /// A code outside `Mumble_KeyCode`
+    Other(u32),
+}
+
+/// Key codes from `Mumble_KeyCode`, with the name used when parsing and displaying keys
+const NAMED: &[(Key, u32, &str)] = &[
+    (Key::End, 1, "End"),
+    (Key::Left, 2, "Left"),
+    (Key::Right, 4, "Right"),
+    (Key::Up, 5, "Up"),
+    (Key::Down, 6, "Down"),
+    (Key::Delete, 7, "Delete"),
+    (Key::Backspace, 8, "Backspace"),
+    (Key::Tab, 9, "Tab"),
+    (Key::Enter, 10, "Enter"),
+    (Key::PageUp, 11, "PageUp"),
+    (Key::PageDown, 12, "PageDown"),
+    (Key::Shift, 13, "Shift"),
+    (Key::Control, 14, "Ctrl"),
+    (Key::Meta, 15, "Meta"),
+    (Key::Alt, 16, "Alt"),
+    (Key::AltGr, 17, "AltGr"),
+    (Key::CapsLock, 18, "CapsLock"),
+    (Key::NumLock, 19, "NumLock"),
+    (Key::Super, 20, "Super"),
+    (Key::Home, 21, "Home"),
+    (Key::Print, 22, "Print"),
+    (Key::ScrollLock, 23, "ScrollLock"),
+    (Key::Escape, 27, "Escape"),
+    (Key::Degree, 176, "Degree"),
+    (Key::F1, 256, "F1"),
+    (Key::F2, 257, "F2"),
+    (Key::F3, 258, "F3"),
+    (Key::F4, 259, "F4"),
+    (Key::F5, 260, "F5"),
+    (Key::F6, 261, "F6"),
+    (Key::F7, 262, "F7"),
+    (Key::F8, 263, "F8"),
+    (Key::F9, 264, "F9"),
+    (Key::F10, 265, "F10"),
+    (Key::F11, 266, "F11"),
+    (Key::F12, 267, "F12"),
+    (Key::F13, 268, "F13"),
+    (Key::F14, 269, "F14"),
+    (Key::F15, 270, "F15"),
+    (Key::F16, 271, "F16"),
+    (Key::F17, 272, "F17"),
+    (Key::F18, 273, "F18"),
+    (Key::F19, 274, "F19"),
+];
+
+/// Other accepted spellings
+const ALIASES: &[(&str, Key)] = &[
+    ("Control", Key::Control),
+    ("Esc", Key::Escape),
+    ("Return", Key::Enter),
+    ("Del", Key::Delete),
+    ("PgUp", Key::PageUp),
+    ("PgDn", Key::PageDown),
+    ("Win", Key::Super),
+    ("Space", Key::Char(' ')),
+    ("Plus", Key::Char('+')),
+    // The keypad reports the characters it types
+    ("Num0", Key::Char('0')),
+    ("Num1", Key::Char('1')),
+    ("Num2", Key::Char('2')),
+    ("Num3", Key::Char('3')),
+    ("Num4", Key::Char('4')),
+    ("Num5", Key::Char('5')),
+    ("Num6", Key::Char('6')),
+    ("Num7", Key::Char('7')),
+    ("Num8", Key::Char('8')),
+    ("Num9", Key::Char('9')),
+    ("NumPlus", Key::Char('+')),
+    ("NumMinus", Key::Char('-')),
+    ("NumMultiply", Key::Char('*')),
+    ("NumDivide", Key::Char('/')),
+    ("NumDecimal", Key::Char('.')),
+    ("NumEnter", Key::Enter),
+    ("°", Key::Degree),
+];
+
+/// In the order they're written in a chord
+const MODIFIERS: &[Key] = &[
+    Key::Control,
+    Key::Alt,
+    Key::AltGr,
+    Key::Shift,
+    Key::Super,
+    Key::Meta,
+];
+
+impl Key {
+    pub fn from_code(code: u32) -> Self {
+        if let Some((key, _, _)) = NAMED.iter().find(|(_, named, _)| *named == code) {
+            return *key;
+        }
+        match code {
+            32..=126 => Key::Char((code as u8 as char).to_ascii_uppercase()),
+            _ => Key::Other(code),
+        }
+    }
+
+    pub fn code(self) -> u32 {
+        match self {
+            Key::Char(c) => c as u32,
+            Key::Other(code) => code,
+            key => NAMED
+                .iter()
+                .find(|(named, _, _)| *named == key)
+                .map(|(_, code, _)| *code)
+                .unwrap(),
+        }
+    }
+
+    pub fn is_modifier(self) -> bool {
+        MODIFIERS.contains(&self)
+    }
+
+    /// Modifiers first, in `MODIFIERS` order
+    fn chord_order(self) -> (usize, Key) {
+        let rank = MODIFIERS.iter().position(|modifier| *modifier == self);
+        (rank.unwrap_or(MODIFIERS.len()), self)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(' ') => write!(f, "Space"),
+            Key::Char('+') => write!(f, "Plus"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Other(code) => write!(f, "#{}", code),
+            key => {
+                let (_, _, name) = NAMED.iter().find(|(named, _, _)| named == key).unwrap();
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = BindingError;
+
+    /// A name from `Display` or an alias, in any case, a printable character, or `#` and a
+    /// key code.
+    fn from_str(text: &str) -> Result<Self, BindingError> {
+        let named = NAMED.iter().map(|(key, _, name)| (*name, *key));
+        let aliases = ALIASES.iter().copied();
+        if let Some((_, key)) = named
+            .chain(aliases)
+            .find(|(name, _)| name.eq_ignore_ascii_case(text))
+        {
+            return Ok(key);
+        }
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_graphic() => return Ok(Key::from_code(c as u32)),
+            _ => {}
+        }
+        text.strip_prefix('#')
+            .and_then(|code| code.parse().ok())
+            .map(Key::from_code)
+            .ok_or_else(|| BindingError::UnknownKey(text.to_string()))
+    }
+}
+
+/// Keys held together. A chord is complete when all its keys are held and no other modifier
+/// is, so `Ctrl+X` doesn't fire for `Ctrl+Shift+X`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chord {
+    keys: Vec<Key>,
+}
+
+impl Chord {
+    pub fn new(keys: impl IntoIterator<Item = Key>) -> Result<Self, BindingError> {
+        let mut keys: Vec<Key> = keys.into_iter().collect();
+        keys.sort_by_key(|key| key.chord_order());
+        keys.dedup();
+        if keys.is_empty() {
+            return Err(BindingError::EmptyChord);
+        }
+        Ok(Chord { keys })
+    }
+
+    /// Modifiers first
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub fn contains(&self, key: Key) -> bool {
+        self.keys.contains(&key)
+    }
+
+    fn is_complete(&self, held: &BTreeSet<Key>) -> bool {
+        self.keys.iter().all(|key| held.contains(key))
+            && held
+                .iter()
+                .all(|key| !key.is_modifier() || self.contains(*key))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, key) in self.keys.iter().enumerate() {
+            if i > 0 {
+                write!(f, "+")?;
+            }
+            write!(f, "{}", key)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Chord {
+    type Err = BindingError;
+
+    fn from_str(text: &str) -> Result<Self, BindingError> {
+        let keys = text
+            .split('+')
+            .map(|key| key.trim().parse())
+            .collect::<Result<Vec<Key>, _>>()?;
+        Chord::new(keys)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Activates when the chord is completed
+    Press,
+    /// Activates when the chord is completed and deactivates when it's released
+    Hold,
+    /// Activates and deactivates on alternate presses
+    Toggle,
+    /// Activates when the chord is completed twice within `DOUBLE_TAP_WINDOW`
+    DoubleTap,
+    /// Activates once the chord has been held this long
+    LongPress(Duration),
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Press => write!(f, "press"),
+            Mode::Hold => write!(f, "hold"),
+            Mode::Toggle => write!(f, "toggle"),
+            Mode::DoubleTap => write!(f, "double-tap"),
+            Mode::LongPress(duration) => write!(f, "long-press {}ms", duration.as_millis()),
+        }
+    }
+}
+
+/// A chord and when it fires, written as `[mode] chord`: `"Ctrl+M"` (`press` by default),
+/// `"hold Space"`, `"toggle Ctrl+Shift+X"`, `"double-tap Alt"`, `"long-press F"`, or
+/// `"long-press 1500ms F"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Binding {
+    pub chord: Chord,
+    pub mode: Mode,
+}
+
+impl Binding {
+    pub fn new(chord: Chord, mode: Mode) -> Self {
+        Binding { chord, mode }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            Mode::Press => write!(f, "{}", self.chord),
+            mode => write!(f, "{} {}", mode, self.chord),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = BindingError;
+
+    fn from_str(text: &str) -> Result<Self, BindingError> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let (chord, mode) = match words.split_last() {
+            Some((chord, mode)) => (chord.parse()?, mode),
+            None => return Err(BindingError::EmptyChord),
+        };
+        let mode = match mode {
+            [] | ["press"] => Mode::Press,
+            ["hold"] => Mode::Hold,
+            ["toggle"] => Mode::Toggle,
+            ["double-tap"] => Mode::DoubleTap,
+            ["long-press"] => Mode::LongPress(LONG_PRESS),
+            ["long-press", duration] => duration
+                .strip_suffix("ms")
+                .and_then(|millis| millis.parse().ok())
+                .map(|millis| Mode::LongPress(Duration::from_millis(millis)))
+                .ok_or_else(|| BindingError::InvalidDuration(duration.to_string()))?,
+            _ => return Err(BindingError::UnknownMode(mode.join(" "))),
+        };
+        Ok(Binding { chord, mode })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingError {
+    UnknownKey(String),
+    UnknownMode(String),
+    /// A long press duration that isn't a whole number of milliseconds like `800ms`
+    InvalidDuration(String),
+    EmptyChord,
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingError::UnknownKey(key) => write!(f, "Unknown key: {}", key),
+            BindingError::UnknownMode(mode) => write!(f, "Unknown hotkey mode: {}", mode),
+            BindingError::InvalidDuration(duration) => {
+                write!(f, "Invalid long press duration: {}", duration)
+            }
+            BindingError::EmptyChord => write!(f, "No keys in chord"),
+        }
+    }
+}
+
+impl std::error::Error for BindingError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HotkeyAction {
+    Activated,
+    /// Only for `Mode::Hold` and `Mode::Toggle`
+    Deactivated,
+}
+
+struct BindingState {
+    binding: Binding,
+    /// Held or toggled on
+    active: bool,
+    /// When the chord was completed, while it still is
+    pressed_at: Option<Instant>,
+    last_tap: Option<Instant>,
+    long_press_fired: bool,
+}
+
+impl BindingState {
+    fn new(binding: Binding) -> Self {
+        BindingState {
+            binding,
+            active: false,
+            pressed_at: None,
+            last_tap: None,
+            long_press_fired: false,
+        }
+    }
+
+    fn pressed(&mut self, now: Instant) -> Option<HotkeyAction> {
+        self.pressed_at = Some(now);
+        self.long_press_fired = false;
+        match self.binding.mode {
+            Mode::Press => Some(HotkeyAction::Activated),
+            Mode::Hold => {
+                self.active = true;
+                Some(HotkeyAction::Activated)
+            }
+            Mode::Toggle => {
+                self.active = !self.active;
+                Some(if self.active {
+                    HotkeyAction::Activated
+                } else {
+                    HotkeyAction::Deactivated
+                })
+            }
+            Mode::DoubleTap => {
+                let previous = self.last_tap.take();
+                if previous.map_or(false, |tap| now - tap <= DOUBLE_TAP_WINDOW) {
+                    Some(HotkeyAction::Activated)
+                } else {
+                    self.last_tap = Some(now);
+                    None
+                }
+            }
+            Mode::LongPress(_) => None,
+        }
+    }
+
+    fn released(&mut self, now: Instant) -> Option<HotkeyAction> {
+        let action = self.long_press_due(now);
+        self.pressed_at = None;
+        if self.binding.mode == Mode::Hold && self.active {
+            self.active = false;
+            return Some(HotkeyAction::Deactivated);
+        }
+        action
+    }
+
+    fn long_press_due(&mut self, now: Instant) -> Option<HotkeyAction> {
+        match (self.binding.mode, self.pressed_at) {
+            (Mode::LongPress(duration), Some(pressed_at))
+                if !self.long_press_fired && now - pressed_at >= duration =>
+            {
+                self.long_press_fired = true;
+                Some(HotkeyAction::Activated)
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Hotkeys {
+    bindings: BTreeMap<String, BindingState>,
+    held: BTreeSet<Key>,
+}
+
+static HOTKEYS: Mutex<Hotkeys> = Mutex::new(Hotkeys {
+    bindings: BTreeMap::new(),
+    held: BTreeSet::new(),
+});
+
+/// Adds or replaces the binding called `name`.
+pub fn bind(name: impl Into<String>, binding: Binding) {
+    HOTKEYS
+        .lock()
+        .bindings
+        .insert(name.into(), BindingState::new(binding));
+}
+
+/// Whether there was a binding to remove
+pub fn unbind(name: &str) -> bool {
+    HOTKEYS.lock().bindings.remove(name).is_some()
+}
+
+/// Replaces every binding with ones parsed from `[mode] chord` strings, e.g. from the
+/// plugin's settings. Nothing changes if any of them is malformed.
+pub fn configure<N, S>(bindings: impl IntoIterator<Item = (N, S)>) -> Result<(), BindingError>
+where
+    N: Into<String>,
+    S: AsRef<str>,
+{
+    let parsed = bindings
+        .into_iter()
+        .map(|(name, spec)| Ok((name.into(), BindingState::new(spec.as_ref().parse()?))))
+        .collect::<Result<BTreeMap<_, _>, BindingError>>()?;
+    HOTKEYS.lock().bindings = parsed;
+    Ok(())
+}
+
+pub fn bindings() -> BTreeMap<String, Binding> {
+    HOTKEYS
+        .lock()
+        .bindings
+        .iter()
+        .map(|(name, state)| (name.clone(), state.binding.clone()))
+        .collect()
+}
+
+/// The bindings as strings `configure` accepts, e.g. to save defaults to the settings
+pub fn specs() -> BTreeMap<String, String> {
+    bindings()
+        .into_iter()
+        .map(|(name, binding)| (name, binding.to_string()))
+        .collect()
+}
+
+/// Whether a `Hold` binding is held or a `Toggle` binding is on.
+pub fn is_active(name: &str) -> bool {
+    HOTKEYS
+        .lock()
+        .bindings
+        .get(name)
+        .map_or(false, |state| state.active)
+}
+
+pub fn held_keys() -> Vec<Key> {
+    HOTKEYS.lock().held.iter().copied().collect()
+}
+
+pub fn is_held(key: Key) -> bool {
+    HOTKEYS.lock().held.contains(&key)
+}
+
+/// Forgets held keys, e.g. when releases may have been missed while Mumble lost focus.
+/// Held bindings deactivate without notice.
+pub fn release_all() {
+    let mut hotkeys = HOTKEYS.lock();
+    hotkeys.held.clear();
+    for state in hotkeys.bindings.values_mut() {
+        state.pressed_at = None;
+        if state.binding.mode == Mode::Hold {
+            state.active = false;
+        }
+    }
+}
+
+/// Tracks a key and returns the bindings it fired.
+pub(crate) fn key_event(code: u32, pressed: bool, now: Instant) -> Vec<(String, HotkeyAction)> {
+    let key = Key::from_code(code);
+    let mut hotkeys = HOTKEYS.lock();
+    let hotkeys = &mut *hotkeys;
+    let mut fired = long_presses_due(&mut hotkeys.bindings, now);
+    if pressed {
+        // Auto-repeat sends more presses without releases
+        if !hotkeys.held.insert(key) {
+            return fired;
+        }
+        for (name, state) in &mut hotkeys.bindings {
+            if state.binding.chord.contains(key) && state.binding.chord.is_complete(&hotkeys.held) {
+                if let Some(action) = state.pressed(now) {
+                    fired.push((name.clone(), action));
+                }
+            }
+        }
+    } else {
+        hotkeys.held.remove(&key);
+        for (name, state) in &mut hotkeys.bindings {
+            if state.pressed_at.is_some() && state.binding.chord.contains(key) {
+                if let Some(action) = state.released(now) {
+                    fired.push((name.clone(), action));
+                }
+            }
+        }
+    }
+    fired
+}
+
+/// Long presses that have come due since the last key event or tick
+pub(crate) fn tick(now: Instant) -> Vec<(String, HotkeyAction)> {
+    long_presses_due(&mut HOTKEYS.lock().bindings, now)
+}
+
+fn long_presses_due(
+    bindings: &mut BTreeMap<String, BindingState>,
+    now: Instant,
+) -> Vec<(String, HotkeyAction)> {
+    bindings
+        .iter_mut()
+        .filter_map(|(name, state)| Some((name.clone(), state.long_press_due(now)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hotkeys::{
+        self, Binding, BindingError, Chord, HotkeyAction, Key, Mode, DOUBLE_TAP_WINDOW,
+    };
+    use parking_lot::Mutex;
+    use std::time::{Duration, Instant};
+
+    /// Bindings are global, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    const CTRL: u32 = 14;
+    const SHIFT: u32 = 13;
+    const X: u32 = b'X' as u32;
+
+    fn fired(name: &str, action: HotkeyAction) -> Vec<(String, HotkeyAction)> {
+        vec![(name.to_string(), action)]
+    }
+
+    #[test]
+    fn keys_and_bindings_parse_and_display() {
+        assert_eq!(Key::from_code(14), Key::Control);
+        assert_eq!(Key::from_code(b'x' as u32), Key::Char('X'));
+        assert_eq!(Key::from_code(200), Key::Other(200));
+        assert_eq!(Key::from_code(256), Key::F1);
+        assert_eq!(Key::F19.code(), 274);
+        assert_eq!("f1".parse(), Ok(Key::F1));
+        assert_eq!("Num5".parse(), Ok(Key::Char('5')));
+        assert_eq!("°".parse(), Ok(Key::Degree));
+        assert_eq!(Key::Escape.code(), 27);
+        assert_eq!("esc".parse(), Ok(Key::Escape));
+        assert_eq!("#200".parse(), Ok(Key::Other(200)));
+        assert_eq!("x".parse(), Ok(Key::Char('X')));
+        assert_eq!(
+            "Hyper".parse::<Key>(),
+            Err(BindingError::UnknownKey("Hyper".into()))
+        );
+
+        let chord: Chord = "x+shift+Control".parse().unwrap();
+        assert_eq!(chord.keys(), &[Key::Control, Key::Shift, Key::Char('X')]);
+        assert_eq!(chord.to_string(), "Ctrl+Shift+X");
+        for spec in &[
+            "Ctrl+Space",
+            "hold Plus",
+            "double-tap Alt",
+            "long-press 1500ms F",
+        ] {
+            assert_eq!(spec.parse::<Binding>().unwrap().to_string(), *spec);
+        }
+        assert_eq!(
+            "long-press M".parse::<Binding>().unwrap().mode,
+            Mode::LongPress(hotkeys::LONG_PRESS)
+        );
+        assert_eq!(
+            "long-press soon M".parse::<Binding>(),
+            Err(BindingError::InvalidDuration("soon".into()))
+        );
+        assert_eq!(
+            "tap M".parse::<Binding>(),
+            Err(BindingError::UnknownMode("tap".into()))
+        );
+    }
+
+    #[test]
+    fn every_key_code_survives_display_and_parse() {
+        for code in 0..=300 {
+            let key = Key::from_code(code);
+            assert_eq!(key.to_string().parse(), Ok(key), "{}", code);
+            if !(b'a' as u32..=b'z' as u32).contains(&code) {
+                assert_eq!(key.code(), code);
+            }
+        }
+        for code in 256..=274 {
+            assert!(!matches!(Key::from_code(code), Key::Other(_)), "{}", code);
+        }
+    }
+
+    #[test]
+    fn chords_fire_only_with_their_own_modifiers() {
+        let _serial = SERIAL.lock();
+        hotkeys::configure(vec![("mute", "Ctrl+X"), ("talk", "hold Ctrl+Shift+X")]).unwrap();
+        let now = Instant::now();
+        assert!(hotkeys::key_event(X, true, now).is_empty());
+        hotkeys::key_event(X, false, now);
+
+        hotkeys::key_event(CTRL, true, now);
+        assert_eq!(
+            hotkeys::key_event(X, true, now),
+            fired("mute", HotkeyAction::Activated)
+        );
+        // Auto-repeat
+        assert!(hotkeys::key_event(X, true, now).is_empty());
+        hotkeys::key_event(X, false, now);
+
+        hotkeys::key_event(SHIFT, true, now);
+        assert_eq!(
+            hotkeys::key_event(X, true, now),
+            fired("talk", HotkeyAction::Activated)
+        );
+        assert!(hotkeys::is_active("talk"));
+        assert_eq!(
+            hotkeys::held_keys(),
+            vec![Key::Shift, Key::Control, Key::Char('X')]
+        );
+        assert_eq!(
+            hotkeys::key_event(SHIFT, false, now),
+            fired("talk", HotkeyAction::Deactivated)
+        );
+        assert!(!hotkeys::is_active("talk"));
+        hotkeys::release_all();
+        assert!(hotkeys::held_keys().is_empty());
+    }
+
+    #[test]
+    fn toggles_double_taps_and_long_presses() {
+        let _serial = SERIAL.lock();
+        hotkeys::configure(Vec::<(String, String)>::new()).unwrap();
+        hotkeys::bind("deafen", Binding::new("T".parse().unwrap(), Mode::Toggle));
+        hotkeys::bind(
+            "whisper",
+            Binding::new("D".parse().unwrap(), Mode::DoubleTap),
+        );
+        hotkeys::bind(
+            "record",
+            Binding::new(
+                "L".parse().unwrap(),
+                Mode::LongPress(Duration::from_secs(1)),
+            ),
+        );
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let tap = |code: u8, millis| {
+            let mut events = hotkeys::key_event(code as u32, true, at(millis));
+            events.extend(hotkeys::key_event(code as u32, false, at(millis + 10)));
+            events
+        };
+
+        assert_eq!(tap(b'T', 0), fired("deafen", HotkeyAction::Activated));
+        assert!(hotkeys::is_active("deafen"));
+        assert_eq!(tap(b'T', 100), fired("deafen", HotkeyAction::Deactivated));
+
+        assert!(tap(b'D', 0).is_empty());
+        let late = DOUBLE_TAP_WINDOW.as_millis() as u64 + 100;
+        assert!(tap(b'D', late).is_empty());
+        assert_eq!(
+            tap(b'D', late + 50),
+            fired("whisper", HotkeyAction::Activated)
+        );
+
+        assert!(hotkeys::key_event(b'L' as u32, true, at(0)).is_empty());
+        assert!(hotkeys::tick(at(500)).is_empty());
+        assert_eq!(
+            hotkeys::tick(at(1000)),
+            fired("record", HotkeyAction::Activated)
+        );
+        assert!(hotkeys::tick(at(1500)).is_empty());
+        assert!(hotkeys::key_event(b'L' as u32, false, at(2000)).is_empty());
+        // Released too early
+        assert!(tap(b'L', 3000).is_empty());
+
+        assert_eq!(hotkeys::specs()["record"], "long-press 1000ms L");
+        assert!(hotkeys::configure(vec![("broken", "Ctrl+Nope")]).is_err());
+        assert_eq!(hotkeys::bindings().len(), 3);
+    }
+}
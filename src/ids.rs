@@ -0,0 +1,346 @@
+//! Conveniences for the ID newtypes in `types`: display, sentinel values, serde, and
+//! binding an ID to the connection it came from.
+//!
+//! User and channel IDs are only meaningful on their own server, so `Scoped` pairs them
+//! with their `ConnectionT`. The scoped `MumbleAPI` methods below take the connection from
+//! the ID itself, and refuse to combine IDs from different connections.
+
+use crate::traits::CheckableId;
+use crate::types as m;
+use crate::{MumbleAPI, MumbleResult};
+use std::fmt;
+
+impl m::ConnectionT {
+    /// Never handed out by Mumble
+    pub const INVALID: m::ConnectionT = m::ConnectionT(-1);
+
+    pub fn is_valid(self) -> bool {
+        self.0 >= 0
+    }
+}
+
+impl m::UserIdT {
+    /// Session 0 is never assigned to a user; audio that doesn't come from a user reports it
+    pub const NONE: m::UserIdT = m::UserIdT(0);
+
+    pub fn is_none(self) -> bool {
+        self == Self::NONE
+    }
+
+    pub fn on(self, connection: m::ConnectionT) -> Scoped<m::UserIdT> {
+        Scoped::new(connection, self)
+    }
+}
+
+impl m::ChannelIdT {
+    /// Every server's root channel
+    pub const ROOT: m::ChannelIdT = m::ChannelIdT(0);
+    /// Reported as the previous channel of a user who just connected, and as the
+    /// current channel of one who just left
+    pub const INVALID: m::ChannelIdT = m::ChannelIdT(-1);
+
+    pub fn is_root(self) -> bool {
+        self == Self::ROOT
+    }
+
+    pub fn is_valid(self) -> bool {
+        self.0 >= 0
+    }
+
+    pub fn on(self, connection: m::ConnectionT) -> Scoped<m::ChannelIdT> {
+        Scoped::new(connection, self)
+    }
+}
+
+impl CheckableId for m::ChannelIdT {
+    fn check(self) -> Option<Self> {
+        if self.is_valid() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl CheckableId for m::UserIdT {
+    fn check(self) -> Option<Self> {
+        if self.is_none() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+}
+
+impl CheckableId for m::ConnectionT {
+    fn check(self) -> Option<Self> {
+        if self.is_valid() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+macro_rules! display_inner {
+    ($($id: ty),*) => {
+        $(
+            impl fmt::Display for $id {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.0, f)
+                }
+            }
+        )*
+    };
+}
+
+display_inner!(m::ConnectionT, m::UserIdT, m::ChannelIdT, m::PluginId);
+
+/// A user or channel ID together with the connection it belongs to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Scoped<T> {
+    connection: m::ConnectionT,
+    id: T,
+}
+
+impl<T: Copy> Scoped<T> {
+    pub fn new(connection: m::ConnectionT, id: T) -> Self {
+        Scoped { connection, id }
+    }
+
+    pub fn connection(&self) -> m::ConnectionT {
+        self.connection
+    }
+
+    pub fn id(&self) -> T {
+        self.id
+    }
+
+    pub fn same_connection<U>(&self, other: &Scoped<U>) -> bool {
+        self.connection == other.connection
+    }
+}
+
+/// Why a scoped `MumbleAPI` method failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScopedError {
+    /// The IDs given belong to different connections; Mumble was not called
+    ConnectionMismatch {
+        expected: m::ConnectionT,
+        found: m::ConnectionT,
+    },
+    Mumble(m::ErrorT),
+}
+
+impl fmt::Display for ScopedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScopedError::ConnectionMismatch { expected, found } => write!(
+                f,
+                "ID from connection {} used with connection {}",
+                found, expected
+            ),
+            ScopedError::Mumble(e) => write!(f, "Mumble error: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScopedError {}
+
+impl From<m::ErrorT> for ScopedError {
+    fn from(e: m::ErrorT) -> Self {
+        ScopedError::Mumble(e)
+    }
+}
+
+/// Formats as `id@connection`
+impl<T: fmt::Display> fmt::Display for Scoped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.id, self.connection)
+    }
+}
+
+impl MumbleAPI {
+    pub fn local_user(&mut self, connection: m::ConnectionT) -> MumbleResult<Scoped<m::UserIdT>> {
+        Ok(self.get_local_user_id(connection)?.on(connection))
+    }
+
+    pub fn user_name(&mut self, user: Scoped<m::UserIdT>) -> MumbleResult<String> {
+        self.get_user_name(user.connection, user.id)
+    }
+
+    pub fn channel_name(&mut self, channel: Scoped<m::ChannelIdT>) -> MumbleResult<String> {
+        self.get_channel_name(channel.connection, channel.id)
+    }
+
+    pub fn channel_of(&mut self, user: Scoped<m::UserIdT>) -> MumbleResult<Scoped<m::ChannelIdT>> {
+        Ok(self
+            .get_channel_of_user(user.connection, user.id)?
+            .on(user.connection))
+    }
+
+    pub fn users_in(
+        &mut self,
+        channel: Scoped<m::ChannelIdT>,
+    ) -> MumbleResult<Vec<Scoped<m::UserIdT>>> {
+        Ok(self
+            .get_users_in_channel_borrowed(channel.connection, channel.id)?
+            .iter()
+            .map(|user| user.on(channel.connection))
+            .collect())
+    }
+
+    /// Fails with `ScopedError::ConnectionMismatch` if the channel is on another connection
+    /// than the user.
+    pub fn move_user(
+        &mut self,
+        user: Scoped<m::UserIdT>,
+        channel: Scoped<m::ChannelIdT>,
+        password: Option<&str>,
+    ) -> Result<(), ScopedError> {
+        if !user.same_connection(&channel) {
+            return Err(ScopedError::ConnectionMismatch {
+                expected: user.connection,
+                found: channel.connection,
+            });
+        }
+        Ok(self.request_user_move(user.connection, user.id, channel.id, password)?)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::Scoped;
+    use crate::types as m;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    macro_rules! serde_inner {
+        ($($id: ident($inner: ty)),*) => {
+            $(
+                impl Serialize for m::$id {
+                    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                        self.0.serialize(serializer)
+                    }
+                }
+
+                impl<'de> Deserialize<'de> for m::$id {
+                    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                        <$inner>::deserialize(deserializer).map(m::$id)
+                    }
+                }
+            )*
+        };
+    }
+
+    serde_inner!(
+        ConnectionT(i32),
+        UserIdT(u32),
+        ChannelIdT(i32),
+        PluginId(u32)
+    );
+
+    /// As a `(connection, id)` pair
+    impl<T: Serialize> Serialize for Scoped<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            (self.connection, &self.id).serialize(serializer)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for Scoped<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (connection, id) = <(m::ConnectionT, T)>::deserialize(deserializer)?;
+            Ok(Scoped { connection, id })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScopedError;
+    use crate::api_tests::fake::{self, check, sample};
+    use crate::api_tests::unexpected_v1_2;
+    use crate::traits::CheckableId;
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use std::os::raw;
+
+    #[test]
+    fn sentinels_fail_the_check() {
+        assert_eq!(m::UserIdT::NONE.check(), None);
+        assert_eq!(m::UserIdT(3).check(), Some(m::UserIdT(3)));
+        assert_eq!(m::ChannelIdT::INVALID.check(), None);
+        assert_eq!(m::ChannelIdT::ROOT.check(), Some(m::ChannelIdT::ROOT));
+        assert!(m::ChannelIdT(0).is_root());
+        assert_eq!(m::ConnectionT::INVALID.check(), None);
+        assert!(m::ConnectionT(0).is_valid());
+    }
+
+    #[test]
+    fn scoped_ids_display_with_their_connection() {
+        assert_eq!(m::UserIdT(42).to_string(), "42");
+        let channel = m::ChannelIdT(5).on(m::ConnectionT(7));
+        assert_eq!(channel.to_string(), "5@7");
+        assert_eq!(
+            (channel.connection(), channel.id()),
+            (m::ConnectionT(7), m::ChannelIdT(5))
+        );
+        assert!(channel.same_connection(&m::UserIdT(1).on(m::ConnectionT(7))));
+        assert!(!channel.same_connection(&m::UserIdT(1).on(m::ConnectionT(8))));
+    }
+
+    #[test]
+    fn move_user_refuses_ids_from_other_connections() {
+        unsafe extern "C" fn request_user_move(
+            _: m::PluginId,
+            connection: m::ConnectionT,
+            user_id: m::UserIdT,
+            channel_id: m::ChannelIdT,
+            _: *const raw::c_char,
+        ) -> m::ErrorT {
+            check("connection", connection, sample());
+            check("user", user_id, sample());
+            check("channel", channel_id, sample());
+            if fake::failing() {
+                return m::ErrorT(m::ErrorCode::EC_CHANNEL_NOT_FOUND);
+            }
+            fake::ok()
+        }
+        let mut table = unexpected_v1_2();
+        table.requestUserMove = request_user_move;
+        let mut api = fake::api(RawMumbleAPI::V1_2(table));
+        let user = sample::<m::UserIdT>().on(sample());
+        let elsewhere = sample::<m::ChannelIdT>().on(m::ConnectionT(8));
+        assert_eq!(
+            api.move_user(user, elsewhere, None),
+            Err(ScopedError::ConnectionMismatch {
+                expected: sample(),
+                found: m::ConnectionT(8)
+            })
+        );
+        let channel = sample::<m::ChannelIdT>().on(sample());
+        assert_eq!(api.move_user(user, channel, None), Ok(()));
+        fake::set_failing(true);
+        assert_eq!(
+            api.move_user(user, channel, None),
+            Err(ScopedError::Mumble(m::ErrorT(
+                m::ErrorCode::EC_CHANNEL_NOT_FOUND
+            )))
+        );
+        fake::finish("move_user_refuses_ids_from_other_connections");
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn ids_serialize_as_their_numbers() {
+        let channel = m::ChannelIdT(5).on(m::ConnectionT(7));
+        assert_eq!(serde_json::to_string(&channel).unwrap(), "[7,5]");
+        assert_eq!(
+            serde_json::from_str::<super::Scoped<m::ChannelIdT>>("[7,5]").unwrap(),
+            channel
+        );
+        assert_eq!(
+            serde_json::from_str::<m::UserIdT>("42").unwrap(),
+            m::UserIdT(42)
+        );
+    }
+}
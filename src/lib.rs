@@ -1,7 +1,3 @@
-#![feature(const_fn)]
-#![feature(const_btree_new)]
-#![feature(nll)]
-#![feature(option_expect_none)]
 #![allow(dead_code)]
 
 use parking_lot::Mutex;
@@ -9,26 +5,54 @@ use std::ffi::{CStr, CString};
 use std::mem::MaybeUninit;
 use std::os::raw;
 
+#[cfg(test)]
+mod api_tests;
+pub mod assets;
+pub mod auto_move;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod dsp;
+pub mod hotkeys;
+pub mod ids;
+pub mod lists;
+pub mod local_user;
+pub mod logging;
+pub mod meter;
+pub mod mixer;
 mod mumble;
+pub mod record;
+mod ring;
+pub mod spatial;
+#[cfg(feature = "storage")]
+pub mod storage;
+#[doc(hidden)]
 pub mod traits;
+pub mod transmission;
+pub mod vad;
+#[macro_use]
+pub mod versions;
+pub mod host;
 
 pub use crate::mumble::m as types;
 use crate::traits::MumblePlugin;
+pub use mumble_sys_macros::mumble_plugin;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
-use std::ops::Deref;
+use std::sync::atomic::{self, AtomicI32};
+use std::time::Instant;
 use traits::{CheckableId, ErrAsResult};
 use types as m;
+use versions::RawMumbleAPI;
 
 type MumbleResult<T> = Result<T, m::ErrorT>;
 
 pub struct MumbleAPI {
     id: m::PluginId,
-    api: m::MumbleAPI,
+    api: RawMumbleAPI,
 }
 
 impl MumbleAPI {
-    pub fn new(id: m::PluginId, raw_api: m::MumbleAPI) -> Self {
+    pub fn new(id: m::PluginId, raw_api: RawMumbleAPI) -> Self {
         Self { id, api: raw_api }
     }
 
@@ -36,33 +60,13 @@ impl MumbleAPI {
         &self.id
     }
 
-    pub fn api(&self) -> &m::MumbleAPI {
+    pub fn api(&self) -> &RawMumbleAPI {
         &self.api
     }
-}
-
-pub struct Freeable<T> {
-    pointer: *mut T,
-    plugin_id: m::PluginId,
-    raw_api: m::MumbleAPI,
-}
 
-pub struct FreeableMaybeUninit<T> {
-    uninit: MaybeUninit<*mut T>,
-    freeable: Option<Freeable<T>>,
-    plugin_id: m::PluginId,
-    raw_api: m::MumbleAPI,
-}
-
-impl FreeableMaybeUninit<raw::c_char> {
-    unsafe fn assume_init_to_str(&mut self) -> &str {
-        CStr::from_ptr(self.assume_init())
-            .to_str()
-            .expect("Must be valid utf8")
-    }
-
-    unsafe fn assume_init_to_string(&mut self) -> String {
-        self.assume_init_to_str().to_string()
+    /// The plugin API version Mumble's functions were provided for.
+    pub fn api_version(&self) -> m::Version {
+        self.api.version()
     }
 }
 
@@ -72,22 +76,9 @@ fn string_opt_to_nullable_ptr(s: &Option<CString>) -> *const raw::c_char {
 }
 
 impl MumbleAPI {
-    fn freeable_uninit<T>(&self) -> FreeableMaybeUninit<T> {
-        FreeableMaybeUninit {
-            freeable: None,
-            uninit: MaybeUninit::uninit(),
-            plugin_id: self.id,
-            raw_api: self.api,
-        }
-    }
-
-    fn freeable_for<T>(&self, pointer: *mut T) -> Freeable<T> {
-        Freeable::of(self.id, self.api, pointer)
-    }
-
     pub fn get_active_server_connection(&self) -> m::ConnectionT {
         let mut conn_id = MaybeUninit::uninit();
-        let f = self.api.getActiveServerConnection;
+        let f = api_fn!(self.api, getActiveServerConnection);
         unsafe {
             f(self.id, conn_id.as_mut_ptr())
                 .resultify()
@@ -98,7 +89,7 @@ impl MumbleAPI {
 
     pub fn is_connection_synchronized(&self, conn: m::ConnectionT) -> bool {
         let mut synchronized = MaybeUninit::uninit();
-        let f = self.api.isConnectionSynchronized;
+        let f = api_fn!(self.api, isConnectionSynchronized);
         unsafe {
             f(self.id, conn, synchronized.as_mut_ptr())
                 .resultify()
@@ -107,219 +98,19 @@ impl MumbleAPI {
         }
     }
 
-    pub fn get_local_user_id(&mut self, conn: m::ConnectionT) -> MumbleResult<m::UserIdT> {
-        let mut user_id = MaybeUninit::uninit();
-        let f = self.api.getLocalUserID;
-        unsafe {
-            f(self.id, conn, user_id.as_mut_ptr()).resultify()?;
-            Ok(user_id.assume_init())
-        }
-    }
-
-    pub fn get_user_name(
-        &mut self,
-        conn: m::ConnectionT,
-        user_id: m::UserIdT,
-    ) -> MumbleResult<String> {
-        let mut user_name_ref = self.freeable_uninit();
-        let f = self.api.getUserName;
-        unsafe {
-            f(self.id, conn, user_id, user_name_ref.as_mut_const_ptr()).resultify()?;
-            // user_name_ref.assume_init()
-            Ok(user_name_ref.assume_init_to_string())
-        }
-    }
-
-    pub fn get_channel_name(
-        &mut self,
-        conn: m::ConnectionT,
-        channel_id: m::ChannelIdT,
-    ) -> MumbleResult<String> {
-        let mut channel_name_ref = self.freeable_uninit();
-        let f = self.api.getChannelName;
-        unsafe {
-            f(
-                self.id,
-                conn,
-                channel_id,
-                channel_name_ref.as_mut_const_ptr(),
-            )
-            .resultify()?;
-            Ok(channel_name_ref.assume_init_to_string())
-        }
-    }
-
-    pub fn get_all_users(&mut self, conn: m::ConnectionT) -> MumbleResult<Box<[m::UserIdT]>> {
-        let mut user_array_ref = self.freeable_uninit();
-        let mut user_count_ref = MaybeUninit::uninit();
-        let f = self.api.getAllUsers;
-        unsafe {
-            f(
-                self.id,
-                conn,
-                user_array_ref.as_mut_ptr(),
-                user_count_ref.as_mut_ptr(),
-            )
-            .resultify()?;
-            let res = std::slice::from_raw_parts(
-                user_array_ref.assume_init(),
-                user_count_ref.assume_init(),
-            )
-            .clone()
-            .into();
-            Ok(res)
-        }
-    }
-
-    pub fn get_all_channels(&mut self, conn: m::ConnectionT) -> MumbleResult<Box<[m::ChannelIdT]>> {
-        let mut channel_array_ref = self.freeable_uninit();
-        let mut channel_count_ref = MaybeUninit::uninit();
-        let f = self.api.getAllChannels;
-        unsafe {
-            f(
-                self.id,
-                conn,
-                channel_array_ref.as_mut_ptr(),
-                channel_count_ref.as_mut_ptr(),
-            )
-            .resultify()?;
-            let res = std::slice::from_raw_parts(
-                channel_array_ref.assume_init(),
-                channel_count_ref.assume_init(),
-            )
-            .clone()
-            .into();
-            Ok(res)
-        }
-    }
-
-    pub fn get_channel_of_user(
-        &mut self,
-        conn: m::ConnectionT,
-        user_id: m::UserIdT,
-    ) -> MumbleResult<m::ChannelIdT> {
-        let mut user_channel_ref = MaybeUninit::uninit();
-        let f = self.api.getChannelOfUser;
-        unsafe {
-            f(self.id, conn, user_id, user_channel_ref.as_mut_ptr()).resultify()?;
-            Ok(user_channel_ref.assume_init())
-        }
-    }
-
-    pub fn get_users_in_channel(
-        &mut self,
-        conn: m::ConnectionT,
-        channel_id: m::ChannelIdT,
-    ) -> MumbleResult<Box<[m::UserIdT]>> {
-        let mut user_array_ref = self.freeable_uninit();
-        let mut user_count_ref = MaybeUninit::uninit();
-        let f = self.api.getUsersInChannel;
-        unsafe {
-            f(
-                self.id,
-                conn,
-                channel_id,
-                user_array_ref.as_mut_ptr(),
-                user_count_ref.as_mut_ptr(),
-            )
-            .resultify()?;
-            let res = std::slice::from_raw_parts(
-                user_array_ref.assume_init(),
-                user_count_ref.assume_init(),
-            )
-            .clone()
-            .into();
-            Ok(res)
-        }
-    }
-
-    pub fn get_local_user_transmission_mode(&mut self) -> MumbleResult<m::TransmissionModeT> {
-        let mut transmission_mode_ref = MaybeUninit::uninit();
-        let f = self.api.getLocalUserTransmissionMode;
-        unsafe {
-            f(self.id, transmission_mode_ref.as_mut_ptr()).resultify()?;
-            Ok(transmission_mode_ref.assume_init())
-        }
-    }
-
     pub fn get_user_locally_muted(
         &mut self,
         conn: m::ConnectionT,
         user_id: m::UserIdT,
     ) -> MumbleResult<bool> {
         let mut muted_ref = MaybeUninit::uninit();
-        let f = self.api.isUserLocallyMuted;
+        let f = api_fn!(self.api, isUserLocallyMuted);
         unsafe {
             f(self.id, conn, user_id, muted_ref.as_mut_ptr()).resultify()?;
             Ok(muted_ref.assume_init())
         }
     }
 
-    pub fn get_user_hash(
-        &mut self,
-        conn: m::ConnectionT,
-        user_id: m::UserIdT,
-    ) -> MumbleResult<String> {
-        let mut user_hash_ref = self.freeable_uninit();
-        let f = self.api.getUserHash;
-        unsafe {
-            f(self.id, conn, user_id, user_hash_ref.as_mut_const_ptr()).resultify()?;
-            Ok(user_hash_ref.assume_init_to_string())
-        }
-    }
-
-    pub fn get_server_hash(&mut self, conn: m::ConnectionT) -> MumbleResult<String> {
-        let mut server_hash_ref = self.freeable_uninit();
-        let f = self.api.getServerHash;
-        unsafe {
-            f(self.id, conn, server_hash_ref.as_mut_const_ptr()).resultify()?;
-            Ok(server_hash_ref.assume_init_to_string())
-        }
-    }
-
-    pub fn get_user_comment(
-        &mut self,
-        conn: m::ConnectionT,
-        user_id: m::UserIdT,
-    ) -> MumbleResult<String> {
-        let mut user_comment_ref = self.freeable_uninit();
-        let f = self.api.getUserComment;
-        unsafe {
-            f(self.id, conn, user_id, user_comment_ref.as_mut_const_ptr()).resultify()?;
-            Ok(user_comment_ref.assume_init_to_string())
-        }
-    }
-
-    pub fn get_channel_description(
-        &mut self,
-        conn: m::ConnectionT,
-        channel_id: m::ChannelIdT,
-    ) -> MumbleResult<String> {
-        let mut channel_description_ref = self.freeable_uninit();
-        let f = self.api.getChannelDescription;
-        unsafe {
-            f(
-                self.id,
-                conn,
-                channel_id,
-                channel_description_ref.as_mut_const_ptr(),
-            )
-            .resultify()?;
-            Ok(channel_description_ref.assume_init_to_string())
-        }
-    }
-
-    pub fn request_local_user_transmission_mode(
-        &mut self,
-        transmission_mode: m::TransmissionModeT,
-    ) -> MumbleResult<()> {
-        let f = self.api.requestLocalUserTransmissionMode;
-        unsafe {
-            f(self.id, transmission_mode).resultify()?;
-            Ok(())
-        }
-    }
-
     pub fn request_user_move(
         &mut self,
         conn: m::ConnectionT,
@@ -327,7 +118,7 @@ impl MumbleAPI {
         channel_id: m::ChannelIdT,
         password: Option<&str>,
     ) -> MumbleResult<()> {
-        let f = self.api.requestUserMove;
+        let f = api_fn!(self.api, requestUserMove);
         let password_cstring = password.map(|p| CString::new(p).unwrap());
         unsafe {
             f(
@@ -343,45 +134,19 @@ impl MumbleAPI {
     }
 
     pub fn request_microphone_activation_overwrite(&mut self, activated: bool) -> MumbleResult<()> {
-        let f = self.api.requestMicrophoneActivationOvewrite;
+        let f = api_fn!(self.api, requestMicrophoneActivationOvewrite);
         unsafe {
             f(self.id, activated).resultify()?;
             Ok(())
         }
     }
 
-    pub fn request_local_mute(
-        &mut self,
-        conn: m::ConnectionT,
-        user_id: m::UserIdT,
-        muted: bool,
-    ) -> MumbleResult<()> {
-        let f = self.api.requestLocalMute;
-        unsafe {
-            f(self.id, conn, user_id, muted).resultify()?;
-            Ok(())
-        }
-    }
-
-    pub fn request_set_local_user_comment(
-        &mut self,
-        conn: m::ConnectionT,
-        comment: &str,
-    ) -> MumbleResult<()> {
-        let f = self.api.requestSetLocalUserComment;
-        let comment = CString::new(comment).expect("Must be valid cstr");
-        unsafe {
-            f(self.id, conn, comment.as_ptr()).resultify()?;
-            Ok(())
-        }
-    }
-
     pub fn find_user_by_name(
         &mut self,
         conn: m::ConnectionT,
         user_name: &str,
     ) -> MumbleResult<Option<m::UserIdT>> {
-        let f = self.api.findUserByName;
+        let f = api_fn!(self.api, findUserByName);
         let user_name = CString::new(user_name).expect("Must be valid cstr");
         let mut user_id_ref = MaybeUninit::uninit();
         unsafe {
@@ -399,7 +164,7 @@ impl MumbleAPI {
         conn: m::ConnectionT,
         channel_name: &str,
     ) -> MumbleResult<Option<m::ChannelIdT>> {
-        let f = self.api.findChannelByName;
+        let f = api_fn!(self.api, findChannelByName);
         let channel_name = CString::new(channel_name).expect("Must be valid cstr");
         let mut channel_id_ref = MaybeUninit::uninit();
         unsafe {
@@ -424,7 +189,7 @@ impl MumbleAPI {
         data_string: &str,
         data_id: &str,
     ) -> MumbleResult<()> {
-        let f = self.api.sendData;
+        let f = api_fn!(self.api, sendData);
         let mut users = Vec::from(users);
         let len = data_string.len();
         let data_string = CString::new(data_string).expect("Must be valid cstr");
@@ -444,80 +209,34 @@ impl MumbleAPI {
         }
     }
 
-    pub fn log(&mut self, message: &str) -> MumbleResult<()> {
-        let f = self.api.log;
-        let message = CString::new(message).expect("Must be valid cstr");
+    pub fn play_sample(&mut self, sample_path: &str) -> MumbleResult<()> {
+        let sample_path = CString::new(sample_path).expect("Must be valid cstr");
         unsafe {
-            f(self.id, message.as_ptr()).resultify()?;
+            match self.api {
+                RawMumbleAPI::V1_0(api) => (api.playSample)(self.id, sample_path.as_ptr()),
+                RawMumbleAPI::V1_2(api) => (api.playSample)(self.id, sample_path.as_ptr(), 1.0),
+            }
+            .resultify()?;
             Ok(())
         }
     }
 
-    pub fn play_sample(&mut self, sample_path: &str) -> MumbleResult<()> {
-        let f = self.api.playSample;
+    /// Requires API 1.2; earlier versions return `EC_INVALID_API_VERSION`.
+    pub fn play_sample_with_volume(&mut self, sample_path: &str, volume: f32) -> MumbleResult<()> {
+        let f = match self.api {
+            RawMumbleAPI::V1_2(api) => api.playSample,
+            _ => return Err(versions::unsupported()),
+        };
         let sample_path = CString::new(sample_path).expect("Must be valid cstr");
         unsafe {
-            f(self.id, sample_path.as_ptr()).resultify()?;
+            f(self.id, sample_path.as_ptr(), volume).resultify()?;
             Ok(())
         }
     }
 }
 
-impl<T> Freeable<T> {
-    fn of(plugin_id: m::PluginId, api: m::MumbleAPI, pointer: *mut T) -> Freeable<T> {
-        // println!("+{:?}", pointer);
-        Freeable {
-            plugin_id,
-            raw_api: api,
-            pointer,
-        }
-    }
-}
-
-impl<T> Drop for Freeable<T> {
-    fn drop(&mut self) {
-        // println!("-{:?}", self.pointer);
-        let free_memory = self.raw_api.freeMemory;
-        let res = unsafe { free_memory(self.plugin_id, self.pointer.cast()) };
-        assert_eq!(
-            res,
-            m::ErrorT(m::ErrorCode::EC_OK.into()),
-            "free_memory must return OK"
-        );
-    }
-}
-
-impl<T> Deref for Freeable<T> {
-    type Target = *mut T;
-
-    fn deref(&self) -> &Self::Target {
-        &self.pointer
-    }
-}
-
-impl<T> FreeableMaybeUninit<T> {
-    pub fn as_mut_ptr(&mut self) -> *mut *mut T {
-        if self.freeable.is_some() {
-            self.freeable = None;
-        }
-        self.uninit.as_mut_ptr()
-    }
-
-    pub fn as_mut_const_ptr(&mut self) -> *mut *const T {
-        if self.freeable.is_some() {
-            self.freeable = None;
-        }
-        self.uninit.as_mut_ptr() as *mut *const T
-    }
-
-    pub unsafe fn assume_init(&mut self) -> *mut T {
-        let val = self.uninit.assume_init();
-        if self.freeable.is_none() {
-            self.freeable = Some(Freeable::of(self.plugin_id, self.raw_api, val));
-        }
-        val
-    }
-}
+// Wrappers for the rest of the function table, generated by build.rs from the bindings
+include!(concat!(env!("OUT_DIR"), "/mumble_api.rs"));
 
 struct PluginFFIMetadata {
     name: CString,
@@ -529,11 +248,11 @@ struct PluginFFIMetadata {
 
 pub struct PluginHolder {
     id: m::PluginId,
-    raw_api: m::MumbleAPI,
+    raw_api: RawMumbleAPI,
     plugin: Box<dyn traits::MumblePlugin>,
 }
 impl PluginHolder {
-    pub fn new(id: m::PluginId, raw_api: m::MumbleAPI, plugin: Box<dyn MumblePlugin>) -> Self {
+    pub fn new(id: m::PluginId, raw_api: RawMumbleAPI, plugin: Box<dyn MumblePlugin>) -> Self {
         Self {
             id,
             raw_api,
@@ -542,8 +261,6 @@ impl PluginHolder {
     }
 }
 
-unsafe impl Send for m::MumbleAPI {}
-
 #[repr(transparent)]
 struct SendConstPointer<T>(*const T);
 unsafe impl<T> Send for SendConstPointer<T> {}
@@ -555,7 +272,7 @@ impl<T> PartialEq for SendConstPointer<T> {
 impl<T> Eq for SendConstPointer<T> {}
 impl<T> PartialOrd for SendConstPointer<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
     }
 }
 impl<T> Ord for SendConstPointer<T> {
@@ -590,14 +307,15 @@ fn register_resource<
         let ptr: *mut _ = map_pointer(item_ref);
         ptr
     };
-    {
+    let previous = {
         let mut map = RESOURCES.lock();
         map.insert(SendConstPointer::new(ptr), item)
-    }
-    .expect_none(&format!(
+    };
+    assert!(
+        previous.is_none(),
         "Item with pointer {:?} already present in map",
         &ptr
-    ));
+    );
     ptr
 }
 fn release_resource(
@@ -608,14 +326,21 @@ fn release_resource(
         map.remove(&SendConstPointer::new(resource_ptr))
     }
     .ok_or_else(|| {
-        eprintln!("release_resource called on unregistered resource!");
-        ()
+        log::warn!("release_resource called on unregistered resource!");
     })
 }
 
-pub static PLUGIN_API_REF: Mutex<Option<m::MumbleAPI>> = Mutex::new(None);
+pub static PLUGIN_API_REF: Mutex<Option<RawMumbleAPI>> = Mutex::new(None);
 pub static PLUGIN: Mutex<Option<PluginHolder>> = Mutex::new(None);
 
+/// The connection whose audio Mumble is playing. The audio callbacks don't say, and Mumble
+/// only plays the active server, so this follows the last server to connect or synchronize.
+static AUDIO_CONNECTION: AtomicI32 = AtomicI32::new(m::ConnectionT::INVALID.0);
+
+fn audio_connection() -> m::ConnectionT {
+    m::ConnectionT(AUDIO_CONNECTION.load(atomic::Ordering::Relaxed))
+}
+
 fn try_lock_plugin<'a>() -> Result<parking_lot::MappedMutexGuard<'a, PluginHolder>, String> {
     use parking_lot::MutexGuard;
     let locked = PLUGIN.lock();
@@ -640,26 +365,20 @@ fn run_with_plugin<T>(cb: fn(&mut PluginHolder) -> T) -> T {
     cb(&mut holder)
 }
 
+#[doc(hidden)]
 #[macro_export]
-macro_rules! register_mumble_plugin {
-    ($typename: ident) => {
-        // #[allow(non_snake_case)]
-        // #[no_mangle]
-        // pub extern "C" fn mumble_registerAPIFunctions(api: m::MumbleAPI) {
-        //     let mut holder = lock_plugin();
-        //     holder.set_api(api);
-        // }
-
+macro_rules! __export_init {
+    ($typename: ty) => {
         #[no_mangle]
-        pub extern "C" fn mumble_init(plugin_id: m::PluginId) -> m::ErrorT {
-            let api_ref = std::mem::replace(&mut *$crate::PLUGIN_API_REF.lock(), None);
-            let api_ref = api_ref.expect("Plugin init called before API was provided?");
+        pub extern "C" fn mumble_init(plugin_id: $crate::types::PluginId) -> $crate::types::ErrorT {
+            // Panicking here would unwind into Mumble, so failures are reported instead
+            let api_ref = match $crate::begin_init() {
+                Ok(api_ref) => api_ref,
+                Err(e) => return e,
+            };
             let mut locked = $crate::PLUGIN.lock();
-            if locked.is_some() {
-                panic!("Plugin already initialized in call to mumble_init?");
-            }
-            use $crate::traits::MumblePluginDescriptor;
-            let plugin = $typename::init(plugin_id, api_ref);
+            let plugin =
+                <$typename as $crate::traits::MumblePluginDescriptor>::init(plugin_id, api_ref);
             let plugin = match plugin {
                 Ok(plugin) => Box::new(plugin),
                 Err(e) => {
@@ -667,14 +386,21 @@ macro_rules! register_mumble_plugin {
                 }
             };
             *locked = Some($crate::PluginHolder::new(plugin_id, api_ref, plugin));
-            m::ErrorT(m::ErrorCode::EC_OK)
+            drop(locked);
+            $crate::logging::attach(plugin_id, api_ref);
+            $crate::types::ErrorT($crate::types::ErrorCode::EC_OK)
         }
+    };
+}
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_metadata {
+    ($typename: ty) => {
         #[allow(non_snake_case)]
         #[no_mangle]
-        pub extern "C" fn mumble_getName() -> m::MumbleStringWrapper {
-            use $crate::traits::MumblePluginDescriptor;
-            let rust_name = $typename::name();
+        pub extern "C" fn mumble_getName() -> $crate::types::MumbleStringWrapper {
+            let rust_name = <$typename as $crate::traits::MumblePluginDescriptor>::name();
             let name = rust_name.as_ptr() as *const std::os::raw::c_char;
             $crate::types::MumbleStringWrapper {
                 data: name,
@@ -685,9 +411,8 @@ macro_rules! register_mumble_plugin {
 
         #[allow(non_snake_case)]
         #[no_mangle]
-        pub extern "C" fn mumble_getAuthor() -> m::MumbleStringWrapper {
-            use $crate::traits::MumblePluginDescriptor;
-            let rust_author = $typename::author();
+        pub extern "C" fn mumble_getAuthor() -> $crate::types::MumbleStringWrapper {
+            let rust_author = <$typename as $crate::traits::MumblePluginDescriptor>::author();
             let author = rust_author.as_ptr() as *const std::os::raw::c_char;
             $crate::types::MumbleStringWrapper {
                 data: author,
@@ -698,9 +423,9 @@ macro_rules! register_mumble_plugin {
 
         #[allow(non_snake_case)]
         #[no_mangle]
-        pub extern "C" fn mumble_getDescription() -> m::MumbleStringWrapper {
-            use $crate::traits::MumblePluginDescriptor;
-            let rust_description = $typename::description();
+        pub extern "C" fn mumble_getDescription() -> $crate::types::MumbleStringWrapper {
+            let rust_description =
+                <$typename as $crate::traits::MumblePluginDescriptor>::description();
             let description = rust_description.as_ptr() as *const std::os::raw::c_char;
             $crate::types::MumbleStringWrapper {
                 data: description,
@@ -708,19 +433,38 @@ macro_rules! register_mumble_plugin {
                 needsReleasing: false,
             }
         }
+    };
+}
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_versions {
+    ($typename: ty) => {
         #[allow(non_snake_case)]
         #[no_mangle]
-        pub extern "C" fn mumble_getAPIVersion() -> m::Version {
-            use $crate::traits::MumblePluginDescriptor;
-            $typename::api_version()
+        pub extern "C" fn mumble_getAPIVersion() -> $crate::types::Version {
+            <$typename as $crate::traits::MumblePluginDescriptor>::api_version()
         }
 
+        /// # Safety
+        ///
+        /// `api` must point to Mumble's function table for the version from
+        /// `mumble_getAPIVersion`, as Mumble passes it.
         #[allow(non_snake_case)]
         #[no_mangle]
-        pub extern "C" fn mumble_getVersion() -> m::Version {
-            use $crate::traits::MumblePluginDescriptor;
-            $typename::version()
+        pub unsafe extern "C" fn mumble_registerAPIFunctions(api: *const std::os::raw::c_void) {
+            unsafe {
+                $crate::register_api_functions(
+                    api,
+                    <$typename as $crate::traits::MumblePluginDescriptor>::api_version(),
+                )
+            }
+        }
+
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_getVersion() -> $crate::types::Version {
+            <$typename as $crate::traits::MumblePluginDescriptor>::version()
         }
 
         // API not implemented: mumble_setMumbleInfo
@@ -734,38 +478,242 @@ macro_rules! register_mumble_plugin {
     };
 }
 
-impl Into<m::TalkingStateT> for m::TalkingState {
-    fn into(self) -> m::TalkingStateT {
-        m::TalkingStateT(self)
-    }
-}
-
-impl Into<m::TalkingState> for m::TalkingStateT {
-    fn into(self) -> m::TalkingState {
-        self.0
-    }
-}
-
-impl Into<m::ErrorT> for m::ErrorCode {
-    fn into(self) -> m::ErrorT {
-        m::ErrorT(self)
-    }
-}
-
-impl Into<m::ErrorCode> for m::ErrorT {
-    fn into(self) -> m::ErrorCode {
-        self.0
-    }
+/// Exports the event callback for one `MumblePlugin` method, forwarding into this crate.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_callback {
+    (on_server_connected) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onServerConnected(conn: $crate::types::ConnectionT) {
+            $crate::mumble_onServerConnected(conn)
+        }
+    };
+    (on_server_disconnected) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onServerDisconnected(conn: $crate::types::ConnectionT) {
+            $crate::mumble_onServerDisconnected(conn)
+        }
+    };
+    (on_server_synchronized) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onServerSynchronized(conn: $crate::types::ConnectionT) {
+            $crate::mumble_onServerSynchronized(conn)
+        }
+    };
+    (on_channel_entered) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onChannelEntered(
+            conn: $crate::types::ConnectionT,
+            user: $crate::types::UserIdT,
+            previous: $crate::types::ChannelIdT,
+            current: $crate::types::ChannelIdT,
+        ) {
+            $crate::mumble_onChannelEntered(conn, user, previous, current)
+        }
+    };
+    (on_channel_exited) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onChannelExited(
+            conn: $crate::types::ConnectionT,
+            user: $crate::types::UserIdT,
+            exited: $crate::types::ChannelIdT,
+        ) {
+            $crate::mumble_onChannelExited(conn, user, exited)
+        }
+    };
+    (on_user_talking_state_changed) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onUserTalkingStateChanged(
+            conn: $crate::types::ConnectionT,
+            user: $crate::types::UserIdT,
+            talking_state: $crate::types::TalkingStateT,
+        ) {
+            $crate::mumble_onUserTalkingStateChanged(conn, user, talking_state)
+        }
+    };
+    (on_audio_input) => {
+        /// # Safety
+        ///
+        /// `input_pcm` must point to `sample_count * channel_count` writable samples, as
+        /// Mumble passes them.
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub unsafe extern "C" fn mumble_onAudioInput(
+            input_pcm: *mut ::std::os::raw::c_short,
+            sample_count: u32,
+            channel_count: u16,
+            sample_rate: u32,
+            is_speech: bool,
+        ) -> bool {
+            unsafe {
+                $crate::mumble_onAudioInput(
+                    input_pcm,
+                    sample_count,
+                    channel_count,
+                    sample_rate,
+                    is_speech,
+                )
+            }
+        }
+    };
+    (on_audio_source_fetched) => {
+        /// # Safety
+        ///
+        /// `output_pcm` must point to `sample_count * channel_count` writable samples, as
+        /// Mumble passes them.
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub unsafe extern "C" fn mumble_onAudioSourceFetched(
+            output_pcm: *mut f32,
+            sample_count: u32,
+            channel_count: u16,
+            sample_rate: u32,
+            is_speech: bool,
+            user_id: $crate::types::UserIdT,
+        ) -> bool {
+            unsafe {
+                $crate::mumble_onAudioSourceFetched(
+                    output_pcm,
+                    sample_count,
+                    channel_count,
+                    sample_rate,
+                    is_speech,
+                    user_id,
+                )
+            }
+        }
+    };
+    (on_audio_output_about_to_play) => {
+        /// # Safety
+        ///
+        /// `output_pcm` must point to `sample_count * channel_count` writable samples, as
+        /// Mumble passes them.
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub unsafe extern "C" fn mumble_onAudioOutputAboutToPlay(
+            output_pcm: *mut f32,
+            sample_count: u32,
+            channel_count: u16,
+            sample_rate: u32,
+        ) -> bool {
+            unsafe {
+                $crate::mumble_onAudioOutputAboutToPlay(
+                    output_pcm,
+                    sample_count,
+                    channel_count,
+                    sample_rate,
+                )
+            }
+        }
+    };
+    (on_receive_data) => {
+        /// # Safety
+        ///
+        /// `data_id` must be a NUL-terminated string and `data` must point to `data_length`
+        /// readable bytes, as Mumble passes them.
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub unsafe extern "C" fn mumble_onReceiveData(
+            conn: $crate::types::ConnectionT,
+            sender: $crate::types::UserIdT,
+            data: *const ::std::os::raw::c_char,
+            data_length: usize,
+            data_id: *const ::std::os::raw::c_char,
+        ) -> bool {
+            unsafe { $crate::mumble_onReceiveData(conn, sender, data, data_length, data_id) }
+        }
+    };
+    (on_user_added) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onUserAdded(
+            conn: $crate::types::ConnectionT,
+            user: $crate::types::UserIdT,
+        ) {
+            $crate::mumble_onUserAdded(conn, user)
+        }
+    };
+    (on_user_removed) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onUserRemoved(
+            conn: $crate::types::ConnectionT,
+            user: $crate::types::UserIdT,
+        ) {
+            $crate::mumble_onUserRemoved(conn, user)
+        }
+    };
+    (on_channel_added) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onChannelAdded(
+            conn: $crate::types::ConnectionT,
+            channel: $crate::types::ChannelIdT,
+        ) {
+            $crate::mumble_onChannelAdded(conn, channel)
+        }
+    };
+    (on_channel_removed) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onChannelRemoved(
+            conn: $crate::types::ConnectionT,
+            channel: $crate::types::ChannelIdT,
+        ) {
+            $crate::mumble_onChannelRemoved(conn, channel)
+        }
+    };
+    (on_channel_renamed) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onChannelRenamed(
+            conn: $crate::types::ConnectionT,
+            channel: $crate::types::ChannelIdT,
+        ) {
+            $crate::mumble_onChannelRenamed(conn, channel)
+        }
+    };
+    (on_key_event) => {
+        #[allow(non_snake_case)]
+        #[no_mangle]
+        pub extern "C" fn mumble_onKeyEvent(key_code: u32, pressed: bool) {
+            $crate::mumble_onKeyEvent(key_code, pressed)
+        }
+    };
 }
 
-impl self::traits::CheckableId for m::ChannelIdT {
-    fn check(self) -> Option<Self> {
-        if (*self).is_negative() {
-            None
-        } else {
-            Some(self)
-        }
-    }
+/// Exports every entry point for a type implementing `MumblePluginDescriptor`.
+///
+/// Prefer `#[mumble_plugin]`, which only exports the callbacks the plugin overrides.
+#[macro_export]
+macro_rules! register_mumble_plugin {
+    ($typename: ident) => {
+        $crate::__export_init!($typename);
+        $crate::__export_metadata!($typename);
+        $crate::__export_versions!($typename);
+        $crate::__export_callback!(on_server_connected);
+        $crate::__export_callback!(on_server_disconnected);
+        $crate::__export_callback!(on_server_synchronized);
+        $crate::__export_callback!(on_channel_entered);
+        $crate::__export_callback!(on_channel_exited);
+        $crate::__export_callback!(on_user_talking_state_changed);
+        $crate::__export_callback!(on_audio_input);
+        $crate::__export_callback!(on_audio_source_fetched);
+        $crate::__export_callback!(on_audio_output_about_to_play);
+        $crate::__export_callback!(on_receive_data);
+        $crate::__export_callback!(on_user_added);
+        $crate::__export_callback!(on_user_removed);
+        $crate::__export_callback!(on_channel_added);
+        $crate::__export_callback!(on_channel_removed);
+        $crate::__export_callback!(on_channel_renamed);
+        $crate::__export_callback!(on_key_event);
+    };
 }
 
 impl self::traits::ErrAsResult for m::ErrorT {
@@ -780,25 +728,54 @@ impl self::traits::ErrAsResult for m::ErrorT {
     }
 }
 
-#[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_registerAPIFunctions(api: &m::MumbleAPI) {
-    if let Some(_old_api) = PLUGIN_API_REF.lock().replace(api.clone()) {
-        eprintln!("mumble_registerAPIFunctions called twice without being cleared by init?");
+/// Backs `mumble_registerAPIFunctions`, which is exported alongside `mumble_getAPIVersion`
+/// so the struct can be read in the layout of the version the plugin asked for.
+///
+/// # Safety
+///
+/// `api` must point to Mumble's function table in the layout of `api_version`.
+#[doc(hidden)]
+pub unsafe fn register_api_functions(api: *const raw::c_void, api_version: m::Version) {
+    let api = match unsafe { RawMumbleAPI::from_ptr(api_version, api) } {
+        Some(api) => api,
+        None => {
+            log::error!(
+                "mumble_registerAPIFunctions called for unsupported API version {:?}",
+                api_version
+            );
+            return;
+        }
+    };
+    if let Some(_old_api) = PLUGIN_API_REF.lock().replace(api) {
+        log::warn!("mumble_registerAPIFunctions called twice without being cleared by init?");
     }
 }
 
+/// Backs `mumble_init`: takes the API `mumble_registerAPIFunctions` registered, or gives the
+/// error to hand Mumble if it registered none the crate supports or the plugin is running.
+#[doc(hidden)]
+pub fn begin_init() -> Result<RawMumbleAPI, m::ErrorT> {
+    if PLUGIN.lock().is_some() {
+        log::error!("Plugin already initialized in call to mumble_init?");
+        return Err(m::ErrorT(m::ErrorCode::EC_GENERIC_ERROR));
+    }
+    PLUGIN_API_REF.lock().take().ok_or_else(|| {
+        log::error!("mumble_init called without a supported API registered");
+        m::ErrorT(m::ErrorCode::EC_INVALID_API_VERSION)
+    })
+}
+
 #[allow(non_snake_case)]
 #[no_mangle]
 pub extern "C" fn mumble_releaseResource(resource_ptr: *const std::os::raw::c_void) {
     if let Ok(resource) = release_resource(resource_ptr) {
-        println!(
+        log::debug!(
             "Resource freed at pointer {:?} with TypeId {:?}",
             resource_ptr,
             resource.type_id()
         );
     } else {
-        eprintln!(
+        log::warn!(
             "Resource release attempt at pointer {:?} was not present",
             resource_ptr
         );
@@ -807,90 +784,193 @@ pub extern "C" fn mumble_releaseResource(resource_ptr: *const std::os::raw::c_vo
 
 #[no_mangle]
 pub extern "C" fn mumble_shutdown() {
-    let maybe_plugin = std::mem::replace(&mut *PLUGIN.lock(), None);
+    let maybe_plugin = PLUGIN.lock().take();
     if let Some(plugin) = maybe_plugin {
-        println!("Shutting down plugin...");
+        log::info!("Shutting down plugin...");
         plugin.plugin.shutdown();
-        println!("Plugin shut down.");
+        transmission::shutdown(&mut MumbleAPI::new(plugin.id, plugin.raw_api));
+        log::info!("Plugin shut down.");
     } else {
-        eprintln!("Cannot shutdown non-running plugin");
+        log::warn!("Cannot shutdown non-running plugin");
     }
+    assets::remove_temp_samples();
+    logging::flush_console();
+    logging::detach();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onServerConnected(conn: m::ConnectionT) {
+pub fn mumble_onServerConnected(conn: m::ConnectionT) {
     lock_plugin().plugin.on_server_connected(conn);
+    AUDIO_CONNECTION.store(conn.0, atomic::Ordering::Relaxed);
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onServerDisconnected(conn: m::ConnectionT) {
-    lock_plugin().plugin.on_server_disconnected(conn);
+pub fn mumble_onServerDisconnected(conn: m::ConnectionT) {
+    let mut api = {
+        let mut holder = lock_plugin();
+        holder.plugin.on_server_disconnected(conn);
+        MumbleAPI::new(holder.id, holder.raw_api)
+    };
+    let _ = AUDIO_CONNECTION.compare_exchange(
+        conn.0,
+        m::ConnectionT::INVALID.0,
+        atomic::Ordering::Relaxed,
+        atomic::Ordering::Relaxed,
+    );
+    dsp::reset_connection_state(conn);
+    meter::server_disconnected(conn);
+    spatial::server_disconnected(conn);
+    #[cfg(feature = "storage")]
+    storage::server_disconnected(conn);
+    transmission::server_disconnected(&mut api, conn);
+    auto_move::server_disconnected(conn);
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onServerSynchronized(conn: m::ConnectionT) {
-    lock_plugin().plugin.on_server_synchronized(conn);
+pub fn mumble_onServerSynchronized(conn: m::ConnectionT) {
+    let mut api = {
+        let mut holder = lock_plugin();
+        holder.plugin.on_server_synchronized(conn);
+        MumbleAPI::new(holder.id, holder.raw_api)
+    };
+    AUDIO_CONNECTION.store(conn.0, atomic::Ordering::Relaxed);
+    if local_user::is_tracking() {
+        refresh_local_user(&mut api);
+    }
+    #[cfg(feature = "storage")]
+    storage::server_synchronized(&mut api, conn);
+    transmission::server_synchronized(&mut api, conn);
+    run_due_work();
 }
 
+// Must be called without the plugin locked
+fn refresh_local_user(api: &mut MumbleAPI) {
+    match local_user::refresh(api) {
+        Ok((state, changes)) => {
+            if !changes.is_empty() {
+                let mut holder = lock_plugin();
+                for change in changes {
+                    holder.plugin.on_local_user_changed(change, &state);
+                }
+            }
+        }
+        Err(e) => log::warn!("Could not query the local user's state: {:?}", e),
+    }
+}
+
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onChannelEntered(
+pub fn mumble_onChannelEntered(
     conn: m::ConnectionT,
     user: m::UserIdT,
     previous: m::ChannelIdT,
     current: m::ChannelIdT,
 ) {
-    lock_plugin()
-        .plugin
-        .on_channel_entered(conn, user, previous.check(), current.check());
+    let mut api = {
+        let mut holder = lock_plugin();
+        holder
+            .plugin
+            .on_channel_entered(conn, user, previous.check(), current.check());
+        MumbleAPI::new(holder.id, holder.raw_api)
+    };
+    spatial::channel_entered(user.on(conn), current);
+    transmission::channel_entered(&mut api, conn, user, current);
+    auto_move::channel_entered(
+        &mut api,
+        conn,
+        user,
+        previous.check(),
+        current.check(),
+        Instant::now(),
+    );
+    report_auto_moves();
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onChannelExited(
-    conn: m::ConnectionT,
-    user: m::UserIdT,
-    exited: m::ChannelIdT,
-) {
+pub fn mumble_onChannelExited(conn: m::ConnectionT, user: m::UserIdT, exited: m::ChannelIdT) {
     lock_plugin()
         .plugin
         .on_channel_exited(conn, user, exited.check());
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onUserTalkingStateChanged(
+pub fn mumble_onUserTalkingStateChanged(
     conn: m::ConnectionT,
     user: m::UserIdT,
     talking_state: m::TalkingStateT,
 ) {
-    lock_plugin()
-        .plugin
-        .on_user_talking_state_changed(conn, user, talking_state);
+    let mut api = {
+        let mut holder = lock_plugin();
+        holder
+            .plugin
+            .on_user_talking_state_changed(conn, user, talking_state);
+        MumbleAPI::new(holder.id, holder.raw_api)
+    };
+    meter::talking_state_changed(conn, user, talking_state);
+    auto_move::talking_state_changed(&mut api, conn, user, talking_state, Instant::now());
+    report_auto_moves();
+    run_due_work();
+}
+
+fn report_auto_moves() {
+    let reports = auto_move::take_reports();
+    if !reports.is_empty() {
+        let mut holder = lock_plugin();
+        for (movement, result) in reports {
+            holder.plugin.on_auto_move(movement, result);
+        }
+    }
 }
 
+/// # Safety
+///
+/// `input_pcm` must point to `sample_count * channel_count` samples, valid for writes
+/// until this returns, as Mumble passes them.
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onAudioInput(
+pub unsafe fn mumble_onAudioInput(
     input_pcm: *mut raw::c_short,
     sample_count: u32,
     channel_count: u16,
+    sample_rate: u32,
     is_speech: bool,
 ) -> bool {
     let length = (sample_count as usize) * (channel_count as usize);
     // https://docs.rs/ndarray/0.13.1/ndarray/type.ArrayViewMut.html can be used for a nicer PCM API
     let pcm = unsafe { std::slice::from_raw_parts_mut::<i16>(input_pcm, length) };
-    lock_plugin()
-        .plugin
-        .on_audio_input(pcm, sample_count, channel_count, is_speech)
+    dsp::set_stage_sample_rate(dsp::Stage::Input, sample_rate);
+    let modified = lock_plugin().plugin.on_audio_input(
+        pcm,
+        sample_count,
+        channel_count,
+        sample_rate,
+        is_speech,
+    );
+    let voice_transmission = vad::process_input(pcm, channel_count, sample_rate, is_speech);
+    let (mixed, mixer_transmission) = mixer::input().mix_i16(pcm, channel_count, sample_rate);
+    transmission::set_audio_forced(voice_transmission || mixer_transmission);
+    let processed = dsp::process_stage_i16(dsp::Stage::Input, pcm, channel_count, sample_rate);
+    meter::meter_microphone(pcm, channel_count, sample_rate);
+    record::capture_i16(record::Track::Microphone, pcm, channel_count, sample_rate);
+    modified || mixed || processed
 }
 
+/// # Safety
+///
+/// `output_pcm` must point to `sample_count * channel_count` samples, valid for writes
+/// until this returns, as Mumble passes them.
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onAudioSourceFetched(
+pub unsafe fn mumble_onAudioSourceFetched(
     output_pcm: *mut f32,
     sample_count: u32,
     channel_count: u16,
@@ -901,39 +981,68 @@ pub extern "C" fn mumble_onAudioSourceFetched(
     let length = (sample_count as usize) * (channel_count as usize);
     // https://docs.rs/ndarray/0.13.1/ndarray/type.ArrayViewMut.html can be used for a nicer PCM API
     let pcm = unsafe { std::slice::from_raw_parts_mut::<f32>(output_pcm, length) };
-    let maybe_user_id = if is_speech && user_id.0 != 0 {
-        Some(user_id)
-    } else {
-        None
-    };
-    lock_plugin().plugin.on_audio_source_fetched(
+    let maybe_user_id = if is_speech { user_id.check() } else { None };
+    let maybe_user = maybe_user_id.map(|user_id| user_id.on(audio_connection()));
+    dsp::set_stage_sample_rate(dsp::Stage::Source, sample_rate);
+    let modified = lock_plugin().plugin.on_audio_source_fetched(
         pcm,
         sample_count,
         channel_count,
         sample_rate,
         is_speech,
         maybe_user_id,
-    )
+    );
+    let processed_user = match maybe_user {
+        Some(user) => {
+            let processed = dsp::process_user(user, pcm, channel_count, sample_rate);
+            let spatialized = spatial::process_user(user, pcm, channel_count, sample_rate);
+            processed || spatialized
+        }
+        None => false,
+    };
+    let processed = dsp::process_source(maybe_user, pcm, channel_count, sample_rate);
+    if let Some(user) = maybe_user {
+        meter::meter_user(user.id(), pcm, channel_count, sample_rate);
+        record::capture(record::Track::User(user), pcm, channel_count, sample_rate);
+    }
+    modified || processed_user || processed
 }
 
+/// # Safety
+///
+/// `output_pcm` must point to `sample_count * channel_count` samples, valid for writes
+/// until this returns, as Mumble passes them.
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onAudioOutputAboutToPlay(
+pub unsafe fn mumble_onAudioOutputAboutToPlay(
     output_pcm: *mut f32,
     sample_count: u32,
     channel_count: u16,
+    sample_rate: u32,
 ) -> bool {
     let length = (sample_count as usize) * (channel_count as usize);
     // https://docs.rs/ndarray/0.13.1/ndarray/type.ArrayViewMut.html can be used for a nicer PCM API
     let pcm = unsafe { std::slice::from_raw_parts_mut::<f32>(output_pcm, length) };
-    lock_plugin()
-        .plugin
-        .on_audio_output_about_to_play(pcm, sample_count, channel_count)
+    dsp::set_stage_sample_rate(dsp::Stage::Output, sample_rate);
+    let modified = lock_plugin().plugin.on_audio_output_about_to_play(
+        pcm,
+        sample_count,
+        channel_count,
+        sample_rate,
+    );
+    let mixed = mixer::output().mix_f32(pcm, channel_count, sample_rate);
+    let processed = dsp::process_stage_f32(dsp::Stage::Output, pcm, channel_count, sample_rate);
+    record::capture(record::Track::Mix, pcm, channel_count, sample_rate);
+    modified || mixed || processed
 }
 
+/// # Safety
+///
+/// `data_id` must be a NUL-terminated string and `data` must point to `data_length`
+/// readable bytes, as Mumble passes them.
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onReceiveData(
+pub unsafe fn mumble_onReceiveData(
     conn: m::ConnectionT,
     sender: m::UserIdT,
     data: *const raw::c_char,
@@ -952,7 +1061,7 @@ pub extern "C" fn mumble_onReceiveData(
         .on_receive_data(conn, sender, data_id, &|| {
             let data = data as *const u8;
             let data = unsafe {
-                let bytes = std::slice::from_raw_parts(data, data_length as usize);
+                let bytes = std::slice::from_raw_parts(data, data_length);
                 CStr::from_bytes_with_nul(bytes)
                     .expect("data must be a valid null-terminated string")
             };
@@ -960,40 +1069,95 @@ pub extern "C" fn mumble_onReceiveData(
         })
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onUserAdded(conn: m::ConnectionT, user: m::UserIdT) {
+pub fn mumble_onUserAdded(conn: m::ConnectionT, user: m::UserIdT) {
     lock_plugin().plugin.on_user_added(conn, user);
+    spatial::user_added(user.on(conn));
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onUserRemoved(conn: m::ConnectionT, user: m::UserIdT) {
+pub fn mumble_onUserRemoved(conn: m::ConnectionT, user: m::UserIdT) {
     lock_plugin().plugin.on_user_removed(conn, user);
+    dsp::reset_user_state(user.on(conn));
+    meter::user_removed(conn, user);
+    spatial::user_removed(user.on(conn));
+    auto_move::user_removed(conn, user);
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onChannelAdded(conn: m::ConnectionT, channel: m::ChannelIdT) {
+pub fn mumble_onChannelAdded(conn: m::ConnectionT, channel: m::ChannelIdT) {
     lock_plugin().plugin.on_channel_added(conn, channel);
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onChannelRemoved(conn: m::ConnectionT, channel: m::ChannelIdT) {
+pub fn mumble_onChannelRemoved(conn: m::ConnectionT, channel: m::ChannelIdT) {
     lock_plugin().plugin.on_channel_removed(conn, channel);
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onChannelRenamed(conn: m::ConnectionT, channel: m::ChannelIdT) {
+pub fn mumble_onChannelRenamed(conn: m::ConnectionT, channel: m::ChannelIdT) {
     lock_plugin().plugin.on_channel_renamed(conn, channel);
+    run_due_work();
 }
 
+#[doc(hidden)]
 #[allow(non_snake_case)]
-#[no_mangle]
-pub extern "C" fn mumble_onKeyEvent(key_code: u32, pressed: bool) {
-    lock_plugin().plugin.on_key_event(key_code, pressed);
+pub fn mumble_onKeyEvent(key_code: u32, pressed: bool) {
+    let mut api = {
+        let mut holder = lock_plugin();
+        holder.plugin.on_key_event(key_code, pressed);
+        MumbleAPI::new(holder.id, holder.raw_api)
+    };
+    let fired = hotkeys::key_event(key_code, pressed, Instant::now());
+    if !fired.is_empty() {
+        fire_hotkeys(fired);
+        transmission::reevaluate(&mut api);
+    }
+    if local_user::is_tracking() {
+        refresh_local_user(&mut api);
+    }
+    run_due_work();
+}
+
+/// Catches up on the work that waits on time rather than on an event. Mumble serves API calls
+/// made off its main thread on the main thread, blocking the caller until it gets to them, so
+/// this runs at the end of the callbacks Mumble makes from its main thread, with the plugin
+/// unlocked.
+fn run_due_work() {
+    let mut api = match PLUGIN.lock().as_ref() {
+        Some(holder) => MumbleAPI::new(holder.id, holder.raw_api),
+        None => return,
+    };
+    if local_user::poll_due() {
+        refresh_local_user(&mut api);
+    }
+    transmission::apply_audio_forced(&mut api);
+    let fired = hotkeys::tick(Instant::now());
+    if !fired.is_empty() {
+        fire_hotkeys(fired);
+        transmission::reevaluate(&mut api);
+    }
+    auto_move::tick(&mut api, Instant::now());
+    report_auto_moves();
+    logging::flush_console();
+}
+
+fn fire_hotkeys(fired: Vec<(String, hotkeys::HotkeyAction)>) {
+    if !fired.is_empty() {
+        let mut holder = lock_plugin();
+        for (binding, action) in fired {
+            holder.plugin.on_hotkey(&binding, action);
+        }
+    }
 }
 
 // #[allow(non_snake_case)]
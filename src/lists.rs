@@ -0,0 +1,184 @@
+//! Batched queries over a server's users and channels.
+//!
+//! Mumble has no bulk lookups, so each helper fetches the list once, borrowing Mumble's array
+//! in place, and then makes one call per entry. Results borrow Mumble's strings too. Users
+//! and channels that disappear between fetching the list and looking them up are left out
+//! instead of failing the whole batch.
+
+use crate::host::HostString;
+use crate::types as m;
+use crate::{MumbleAPI, MumbleResult};
+use std::collections::BTreeMap;
+
+/// `Ok(None)` if `result` failed with `missing`
+fn unless_missing<T>(result: MumbleResult<T>, missing: m::ErrorCode) -> MumbleResult<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(m::ErrorT(code)) if code == missing => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+impl MumbleAPI {
+    /// Every connected user with their name.
+    pub fn user_names(
+        &mut self,
+        connection: m::ConnectionT,
+    ) -> MumbleResult<Vec<(m::UserIdT, HostString)>> {
+        let users = self.get_all_users_borrowed(connection)?;
+        let mut names = Vec::with_capacity(users.len());
+        for &user in users.iter() {
+            let name = self.get_user_name_borrowed(connection, user);
+            if let Some(name) = unless_missing(name, m::ErrorCode::EC_USER_NOT_FOUND)? {
+                names.push((user, name));
+            }
+        }
+        Ok(names)
+    }
+
+    /// Every channel with its name.
+    pub fn channel_names(
+        &mut self,
+        connection: m::ConnectionT,
+    ) -> MumbleResult<Vec<(m::ChannelIdT, HostString)>> {
+        let channels = self.get_all_channels_borrowed(connection)?;
+        let mut names = Vec::with_capacity(channels.len());
+        for &channel in channels.iter() {
+            let name = self.get_channel_name_borrowed(connection, channel);
+            if let Some(name) = unless_missing(name, m::ErrorCode::EC_CHANNEL_NOT_FOUND)? {
+                names.push((channel, name));
+            }
+        }
+        Ok(names)
+    }
+
+    /// The users in each channel, including empty channels.
+    pub fn users_by_channel(
+        &mut self,
+        connection: m::ConnectionT,
+    ) -> MumbleResult<BTreeMap<m::ChannelIdT, Vec<m::UserIdT>>> {
+        let channels = self.get_all_channels_borrowed(connection)?;
+        let mut by_channel = BTreeMap::new();
+        for &channel in channels.iter() {
+            let users = self.get_users_in_channel_borrowed(connection, channel);
+            if let Some(users) = unless_missing(users, m::ErrorCode::EC_CHANNEL_NOT_FOUND)? {
+                by_channel.insert(channel, users.to_vec());
+            }
+        }
+        Ok(by_channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_tests::fake::{self, check, sample};
+    use crate::api_tests::{unexpected_v1_0, unexpected_v1_2};
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use std::os::raw;
+
+    unsafe extern "C" fn all_users(
+        _: m::PluginId,
+        _: m::ConnectionT,
+        users: *mut *mut m::UserIdT,
+        user_count: *mut usize,
+    ) -> m::ErrorT {
+        let (array, len) = fake::alloc_array(vec![m::UserIdT(1), m::UserIdT(2), m::UserIdT(3)]);
+        *users = array;
+        *user_count = len;
+        fake::ok()
+    }
+
+    unsafe extern "C" fn all_channels(
+        _: m::PluginId,
+        _: m::ConnectionT,
+        channels: *mut *mut m::ChannelIdT,
+        channel_count: *mut usize,
+    ) -> m::ErrorT {
+        let (array, len) = fake::alloc_array(vec![m::ChannelIdT(0), m::ChannelIdT(4)]);
+        *channels = array;
+        *channel_count = len;
+        fake::ok()
+    }
+
+    #[test]
+    fn user_names_skips_users_who_left() {
+        unsafe extern "C" fn user_name(
+            _: m::PluginId,
+            connection: m::ConnectionT,
+            user_id: m::UserIdT,
+            user_name: *mut *const raw::c_char,
+        ) -> m::ErrorT {
+            check("connection", connection, sample());
+            if user_id == m::UserIdT(2) {
+                return m::ErrorT(m::ErrorCode::EC_USER_NOT_FOUND);
+            }
+            *user_name = fake::alloc_string(&user_id.to_string());
+            fake::ok()
+        }
+        let mut table = unexpected_v1_2();
+        table.getAllUsers = all_users;
+        table.getUserName = user_name;
+        let mut api = fake::api(RawMumbleAPI::V1_2(table));
+        let names = api.user_names(sample()).unwrap();
+        let borrowed: Vec<(m::UserIdT, &str)> =
+            names.iter().map(|(id, name)| (*id, &**name)).collect();
+        assert_eq!(borrowed, vec![(m::UserIdT(1), "1"), (m::UserIdT(3), "3")]);
+        drop(names);
+        fake::finish("user_names_skips_users_who_left");
+    }
+
+    #[test]
+    fn users_by_channel() {
+        unsafe extern "C" fn users_in_channel(
+            _: m::PluginId,
+            _: m::ConnectionT,
+            channel_id: m::ChannelIdT,
+            users: *mut *mut m::UserIdT,
+            user_count: *mut usize,
+        ) -> m::ErrorT {
+            let in_channel = if channel_id.is_root() {
+                vec![m::UserIdT(1), m::UserIdT(3)]
+            } else {
+                Vec::new()
+            };
+            let (array, len) = fake::alloc_array(in_channel);
+            *users = array;
+            *user_count = len;
+            fake::ok()
+        }
+        let mut table = unexpected_v1_0();
+        table.getAllChannels = all_channels;
+        table.getUsersInChannel = users_in_channel;
+        let mut api = fake::api(RawMumbleAPI::V1_0(table));
+        let by_channel = api.users_by_channel(sample()).unwrap();
+        assert_eq!(by_channel.len(), 2);
+        assert_eq!(
+            by_channel[&m::ChannelIdT::ROOT],
+            vec![m::UserIdT(1), m::UserIdT(3)]
+        );
+        assert!(by_channel[&m::ChannelIdT(4)].is_empty());
+        fake::finish("users_by_channel");
+    }
+
+    #[test]
+    fn failures_other_than_missing_entries_fail_the_batch() {
+        unsafe extern "C" fn channel_name(
+            _: m::PluginId,
+            _: m::ConnectionT,
+            _: m::ChannelIdT,
+            _: *mut *const raw::c_char,
+        ) -> m::ErrorT {
+            m::ErrorT(m::ErrorCode::EC_CONNECTION_UNSYNCHRONIZED)
+        }
+        let mut table = unexpected_v1_2();
+        table.getAllChannels = all_channels;
+        table.getChannelName = channel_name;
+        let mut api = fake::api(RawMumbleAPI::V1_2(table));
+        assert_eq!(
+            api.channel_names(sample()).unwrap_err(),
+            m::ErrorT(m::ErrorCode::EC_CONNECTION_UNSYNCHRONIZED)
+        );
+        fake::finish("failures_other_than_missing_entries_fail_the_batch");
+    }
+}
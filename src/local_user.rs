@@ -0,0 +1,202 @@
+//! The local user's mute, deafen and transmission state, and notifications when it changes.
+//!
+//! Mumble has no callback for these, so once tracking is enabled the state is polled when
+//! a server finishes synchronizing, on key events (where mute and deafen shortcuts usually
+//! come from), and at most every `POLL_INTERVAL` from Mumble's other main-thread callbacks.
+//! Mumble's API may only be called from its main thread, so there is no background polling.
+//! Changes are delivered to `MumblePlugin::on_local_user_changed`.
+
+use crate::types as m;
+use crate::{MumbleAPI, MumbleResult};
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LocalUserState {
+    pub muted: bool,
+    pub deafened: bool,
+    pub transmission_mode: m::TransmissionMode,
+}
+
+impl LocalUserState {
+    pub fn query(api: &mut MumbleAPI) -> MumbleResult<Self> {
+        Ok(LocalUserState {
+            muted: api.is_local_user_muted()?,
+            deafened: api.is_local_user_deafened()?,
+            transmission_mode: api.get_local_user_transmission_mode()?.into(),
+        })
+    }
+
+    /// What differs in `self` compared to `previous`.
+    pub fn changes_since(&self, previous: &LocalUserState) -> Vec<LocalUserChange> {
+        let mut changes = Vec::new();
+        if self.muted != previous.muted {
+            changes.push(LocalUserChange::Muted(self.muted));
+        }
+        if self.deafened != previous.deafened {
+            changes.push(LocalUserChange::Deafened(self.deafened));
+        }
+        if self.transmission_mode != previous.transmission_mode {
+            changes.push(LocalUserChange::TransmissionMode(self.transmission_mode));
+        }
+        changes
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LocalUserChange {
+    Muted(bool),
+    Deafened(bool),
+    TransmissionMode(m::TransmissionMode),
+}
+
+struct Tracker {
+    enabled: bool,
+    state: Option<LocalUserState>,
+    last_poll: Option<Instant>,
+}
+
+static TRACKER: Mutex<Tracker> = Mutex::new(Tracker {
+    enabled: false,
+    state: None,
+    last_poll: None,
+});
+
+/// Starts or stops polling for changes. Stopping forgets the last known state.
+pub fn set_tracking(enabled: bool) {
+    let mut tracker = TRACKER.lock();
+    tracker.enabled = enabled;
+    if !enabled {
+        tracker.state = None;
+        tracker.last_poll = None;
+    }
+}
+
+pub fn is_tracking() -> bool {
+    TRACKER.lock().enabled
+}
+
+/// The state as of the last poll, if tracking.
+pub fn state() -> Option<LocalUserState> {
+    TRACKER.lock().state
+}
+
+/// Queries the state now and returns what changed since the last poll; nothing on the first.
+/// Call after requesting a change yourself to have it reported without waiting for a poll.
+pub fn refresh(api: &mut MumbleAPI) -> MumbleResult<(LocalUserState, Vec<LocalUserChange>)> {
+    let current = LocalUserState::query(api)?;
+    let mut tracker = TRACKER.lock();
+    tracker.last_poll = Some(Instant::now());
+    let changes = tracker
+        .state
+        .replace(current)
+        .map_or_else(Vec::new, |previous| current.changes_since(&previous));
+    Ok((current, changes))
+}
+
+pub(crate) fn poll_due() -> bool {
+    let tracker = TRACKER.lock();
+    tracker.enabled
+        && tracker
+            .last_poll
+            .map_or(true, |last| last.elapsed() >= POLL_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_tests::{fake, unexpected_v1_2};
+    use crate::local_user::{self, LocalUserChange, LocalUserState};
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use crate::MumbleAPI;
+    use m::TransmissionMode::*;
+    use parking_lot::Mutex;
+
+    /// Tracking is global, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+    static STATE: Mutex<(bool, bool)> = Mutex::new((false, false));
+
+    unsafe extern "C" fn muted(_: m::PluginId, muted: *mut bool) -> m::ErrorT {
+        if fake::failing() {
+            return fake::error();
+        }
+        *muted = STATE.lock().0;
+        fake::ok()
+    }
+
+    unsafe extern "C" fn deafened(_: m::PluginId, deafened: *mut bool) -> m::ErrorT {
+        *deafened = STATE.lock().1;
+        fake::ok()
+    }
+
+    unsafe extern "C" fn transmission_mode(
+        _: m::PluginId,
+        mode: *mut m::TransmissionModeT,
+    ) -> m::ErrorT {
+        *mode = TM_PUSH_TO_TALK.into();
+        fake::ok()
+    }
+
+    fn api() -> MumbleAPI {
+        let mut table = unexpected_v1_2();
+        table.isLocalUserMuted = muted;
+        table.isLocalUserDeafened = deafened;
+        table.getLocalUserTransmissionMode = transmission_mode;
+        fake::api(RawMumbleAPI::V1_2(table))
+    }
+
+    #[test]
+    fn changes_are_listed_field_by_field() {
+        let before = LocalUserState {
+            muted: false,
+            deafened: false,
+            transmission_mode: TM_VOICE_ACTIVATION,
+        };
+        assert!(before.changes_since(&before).is_empty());
+        let after = LocalUserState {
+            muted: true,
+            deafened: true,
+            transmission_mode: TM_CONTINOUS,
+        };
+        assert_eq!(
+            after.changes_since(&before),
+            vec![
+                LocalUserChange::Muted(true),
+                LocalUserChange::Deafened(true),
+                LocalUserChange::TransmissionMode(TM_CONTINOUS),
+            ]
+        );
+    }
+
+    #[test]
+    fn refreshing_reports_what_changed_since_the_last_poll() {
+        let _serial = SERIAL.lock();
+        let mut api = api();
+        *STATE.lock() = (false, false);
+        local_user::set_tracking(true);
+        assert!(local_user::poll_due());
+
+        let (state, changes) = local_user::refresh(&mut api).unwrap();
+        assert_eq!(state.transmission_mode, TM_PUSH_TO_TALK);
+        assert!(changes.is_empty());
+        assert!(!local_user::poll_due());
+
+        *STATE.lock() = (true, false);
+        let (_, changes) = local_user::refresh(&mut api).unwrap();
+        assert_eq!(changes, vec![LocalUserChange::Muted(true)]);
+        assert_eq!(local_user::state().map(|state| state.muted), Some(true));
+
+        fake::set_failing(true);
+        assert!(local_user::refresh(&mut api).is_err());
+        fake::set_failing(false);
+        assert_eq!(local_user::state().map(|state| state.muted), Some(true));
+
+        local_user::set_tracking(false);
+        assert!(!local_user::is_tracking());
+        assert!(!local_user::poll_due());
+        assert_eq!(local_user::state(), None);
+        fake::finish("refreshing_reports_what_changed_since_the_last_poll");
+    }
+}
@@ -0,0 +1,426 @@
+//! Forwards `log` records (and `tracing` events, with the `tracing` feature) to Mumble's
+//! console through `MumbleAPI::log`, and optionally to a rotating file.
+//!
+//! Unless the plugin installs another logger during `init`, the bridge is installed once
+//! `mumble_init` has registered the plugin. Records can be logged from any thread, the audio
+//! threads included, but Mumble's API may only be called from its main thread, so console
+//! lines are queued (up to `PENDING_LIMIT`) and sent from the next callback Mumble makes
+//! there. File output is written straight away. The crate's own diagnostics are logged the
+//! same way.
+
+use crate::types as m;
+use crate::versions::RawMumbleAPI;
+use crate::MumbleAPI;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use parking_lot::Mutex;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many console lines wait for Mumble's main thread; older ones are dropped first
+pub const PENDING_LIMIT: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    /// The most verbose level shown in Mumble's console
+    pub console_level: LevelFilter,
+    pub file: Option<FileLogConfig>,
+    /// Levels for targets and their submodules, e.g. `("my_plugin::net", LevelFilter::Warn)`,
+    /// replacing both outputs' levels; the longest matching target wins
+    pub targets: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig::DEFAULT
+    }
+}
+
+impl LogConfig {
+    const DEFAULT: LogConfig = LogConfig {
+        console_level: LevelFilter::Info,
+        file: None,
+        targets: Vec::new(),
+    };
+
+    fn target_level(&self, target: &str) -> Option<LevelFilter> {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .map_or(false, |rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+    }
+
+    fn console_allows(&self, level: Level, target: &str) -> bool {
+        level <= self.target_level(target).unwrap_or(self.console_level)
+    }
+
+    fn file_allows(&self, level: Level, target: &str) -> bool {
+        match &self.file {
+            Some(file) => level <= self.target_level(target).unwrap_or(file.level),
+            None => false,
+        }
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        let file_level = self
+            .file
+            .as_ref()
+            .map_or(LevelFilter::Off, |file| file.level);
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.console_level.max(file_level), Ord::max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLogConfig {
+    pub path: PathBuf,
+    pub level: LevelFilter,
+    /// The file is rotated before it would grow past this
+    pub max_bytes: u64,
+    /// Rotated files kept as `path.1` (newest) to `path.{keep}`
+    pub keep: usize,
+}
+
+impl FileLogConfig {
+    /// Everything down to `Debug`, rotated at 1 MiB keeping three old files
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileLogConfig {
+            path: path.into(),
+            level: LevelFilter::Debug,
+            max_bytes: 1 << 20,
+            keep: 3,
+        }
+    }
+}
+
+pub(crate) struct RotatingFile {
+    config: FileLogConfig,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFile {
+    pub(crate) fn open(config: FileLogConfig) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)?;
+        let written = file.metadata()?.len();
+        Ok(RotatingFile {
+            config,
+            file,
+            written,
+        })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.config.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.config.keep > 0 {
+            for index in (1..self.config.keep).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            fs::rename(&self.config.path, self.rotated_path(1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.config.path)?;
+        self.written = 0;
+        Ok(())
+    }
+
+    pub(crate) fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.config.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.written += len;
+        Ok(())
+    }
+}
+
+struct Sink {
+    config: LogConfig,
+    api: Option<(m::PluginId, RawMumbleAPI)>,
+    pending: Vec<String>,
+    dropped: usize,
+}
+
+static SINK: Mutex<Sink> = Mutex::new(Sink {
+    config: LogConfig::DEFAULT,
+    api: None,
+    pending: Vec::new(),
+    dropped: 0,
+});
+/// Locked on its own so writing to disk doesn't hold up records bound for the console
+static FILE: Mutex<Option<RotatingFile>> = Mutex::new(None);
+/// Whether `log` records come to the bridge, which then owns `log::max_level`
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Replaces the configuration, opening the log file if there is one. On error the
+/// previous configuration stays in place.
+pub fn configure(config: LogConfig) -> io::Result<()> {
+    let file = config.file.clone().map(RotatingFile::open).transpose()?;
+    if INSTALLED.load(Ordering::Relaxed) {
+        log::set_max_level(config.max_level());
+    }
+    let mut sink = SINK.lock();
+    sink.config = config;
+    *FILE.lock() = file;
+    Ok(())
+}
+
+pub fn config() -> LogConfig {
+    SINK.lock().config.clone()
+}
+
+/// Installs the bridge as the `log` crate's logger.
+pub fn install() -> Result<(), log::SetLoggerError> {
+    log::set_logger(&MumbleLogger)?;
+    INSTALLED.store(true, Ordering::Relaxed);
+    log::set_max_level(SINK.lock().config.max_level());
+    Ok(())
+}
+
+/// Starts sending to Mumble, beginning with anything logged so far, and installs the
+/// bridge unless another logger is already set. Called from `mumble_init`, on Mumble's main
+/// thread.
+#[doc(hidden)]
+pub fn attach(plugin_id: m::PluginId, raw_api: RawMumbleAPI) {
+    let _ = install();
+    SINK.lock().api = Some((plugin_id, raw_api));
+    flush_console();
+}
+
+/// Sends the queued console lines; only call this on Mumble's main thread.
+pub(crate) fn flush_console() {
+    let (api, pending, dropped) = {
+        let mut sink = SINK.lock();
+        let api = match sink.api {
+            Some(api) => api,
+            None => return,
+        };
+        (
+            api,
+            std::mem::take(&mut sink.pending),
+            std::mem::replace(&mut sink.dropped, 0),
+        )
+    };
+    // Unlocked, as Mumble may call back into the plugin, which may log again
+    let mut api = MumbleAPI::new(api.0, api.1);
+    if dropped > 0 {
+        send(
+            &mut api,
+            &format!("{} earlier log messages were dropped", dropped),
+        );
+    }
+    for line in pending {
+        send(&mut api, &line);
+    }
+}
+
+/// Holds back console output again until the next `attach`.
+pub(crate) fn detach() {
+    SINK.lock().api = None;
+}
+
+fn send(api: &mut MumbleAPI, line: &str) {
+    // Failing to log can't be logged
+    let _ = api.log(&line.replace('\0', "\u{fffd}"));
+}
+
+/// Routes one record to the configured outputs.
+pub(crate) fn write(level: Level, target: &str, message: &str) {
+    let (to_file, to_console) = {
+        let sink = SINK.lock();
+        (
+            sink.config.file_allows(level, target),
+            sink.config.console_allows(level, target),
+        )
+    };
+    if to_file {
+        let line = format!("{} {:<5} {}: {}", timestamp(), level, target, message);
+        let mut file = FILE.lock();
+        if let Some(open) = file.as_mut() {
+            if open.write_line(&line).is_err() {
+                // Stop writing to a file that errors rather than failing on every record
+                *file = None;
+            }
+        }
+    }
+    if to_console {
+        let line = format!("[{}] {}: {}", level, target, message);
+        let mut sink = SINK.lock();
+        if sink.pending.len() == PENDING_LIMIT {
+            sink.pending.remove(0);
+            sink.dropped += 1;
+        }
+        sink.pending.push(line);
+    }
+}
+
+/// Seconds since the Unix epoch, to the millisecond
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{}.{:03}", now.as_secs(), now.subsec_millis())
+}
+
+struct MumbleLogger;
+
+impl Log for MumbleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let sink = SINK.lock();
+        sink.config
+            .console_allows(metadata.level(), metadata.target())
+            || sink.config.file_allows(metadata.level(), metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        write(record.level(), record.target(), &record.args().to_string());
+    }
+
+    fn flush(&self) {
+        if let Some(file) = FILE.lock().as_mut() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+pub use self::tracing_layer::MumbleLayer;
+
+#[cfg(feature = "tracing")]
+mod tracing_layer {
+    use log::Level;
+    use std::fmt::{self, Write};
+    use tracing_core::field::{Field, Visit};
+    use tracing_core::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer};
+
+    /// A `tracing_subscriber` layer sending events through the same outputs as `log`
+    /// records, with their fields appended as `key=value`.
+    #[derive(Debug, Default, Copy, Clone)]
+    pub struct MumbleLayer;
+
+    impl<S: Subscriber> Layer<S> for MumbleLayer {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let metadata = event.metadata();
+            let level = match *metadata.level() {
+                tracing_core::Level::ERROR => Level::Error,
+                tracing_core::Level::WARN => Level::Warn,
+                tracing_core::Level::INFO => Level::Info,
+                tracing_core::Level::DEBUG => Level::Debug,
+                tracing_core::Level::TRACE => Level::Trace,
+            };
+            let mut message = Message::default();
+            event.record(&mut message);
+            super::write(level, metadata.target(), &message.text);
+        }
+    }
+
+    #[derive(Default)]
+    struct Message {
+        text: String,
+    }
+
+    impl Visit for Message {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if !self.text.is_empty() {
+                self.text.push(' ');
+            }
+            let _ = if field.name() == "message" {
+                write!(self.text, "{:?}", value)
+            } else {
+                write!(self.text, "{}={:?}", field.name(), value)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_tests::fake::{self, check};
+    use crate::api_tests::unexpected_v1_2;
+    use crate::logging::{self, FileLogConfig, RotatingFile};
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use log::{Level, LevelFilter};
+    use parking_lot::Mutex;
+    use std::ffi::CStr;
+    use std::fs;
+    use std::os::raw;
+
+    static LOGGED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    #[test]
+    fn records_wait_for_the_api_then_reach_mumble() {
+        unsafe extern "C" fn log(caller_id: m::PluginId, message: *const raw::c_char) -> m::ErrorT {
+            check("caller", caller_id, fake::PLUGIN);
+            let message = CStr::from_ptr(message).to_string_lossy().into_owned();
+            LOGGED.lock().push(message);
+            fake::ok()
+        }
+        logging::write(Level::Info, "plugin", "before");
+        logging::write(Level::Debug, "plugin", "too verbose");
+        let mut table = unexpected_v1_2();
+        table.log = log;
+        logging::attach(fake::PLUGIN, RawMumbleAPI::V1_2(table));
+        assert_eq!(*LOGGED.lock(), vec!["[INFO] plugin: before"]);
+
+        // Queued until Mumble's main thread comes by
+        logging::write(Level::Warn, "plugin::net", "after");
+        assert_eq!(LOGGED.lock().len(), 1);
+        logging::flush_console();
+        logging::detach();
+        logging::flush_console();
+        logging::write(Level::Error, "plugin", "detached");
+        assert_eq!(
+            *LOGGED.lock(),
+            vec!["[INFO] plugin: before", "[WARN] plugin::net: after"]
+        );
+        fake::finish("records_wait_for_the_api_then_reach_mumble");
+    }
+
+    #[test]
+    fn log_file_rotates() {
+        let dir = std::env::temp_dir().join(format!("mumble-sys-log-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("plugin.log");
+        let mut config = FileLogConfig::new(&path);
+        config.level = LevelFilter::Trace;
+        config.max_bytes = 10;
+        config.keep = 2;
+        let mut file = RotatingFile::open(config).unwrap();
+        for line in &["aaaaaaaa", "bbbbbbbb", "cccccccc", "dddddddd"] {
+            file.write_line(line).unwrap();
+        }
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("plugin.log"), "dddddddd\n");
+        assert_eq!(read("plugin.log.1"), "cccccccc\n");
+        assert_eq!(read("plugin.log.2"), "bbbbbbbb\n");
+        assert!(!dir.join("plugin.log.3").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
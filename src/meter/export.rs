@@ -0,0 +1,193 @@
+use super::{Levels, Snapshot, TalkTime};
+use std::fmt::Write;
+use std::time::UNIX_EPOCH;
+
+const CSV_HEADER: &str = "track,connection,user,talk_seconds,talk_spurts,talking,\
+                          rms_db,peak_db,max_peak_db,momentary_lufs,integrated_lufs";
+
+fn csv_levels(out: &mut String, levels: Option<&Levels>) {
+    match levels {
+        Some(levels) => {
+            let integrated = levels
+                .integrated_lufs
+                .map_or(String::new(), |lufs| format!("{:.2}", lufs));
+            let _ = write!(
+                out,
+                "{:.2},{:.2},{:.2},{:.2},{}",
+                levels.rms_db,
+                levels.peak_db,
+                levels.max_peak_db,
+                levels.momentary_lufs,
+                integrated
+            );
+        }
+        None => out.push_str(",,,,"),
+    }
+}
+
+fn json_levels(out: &mut String, levels: Option<&Levels>) {
+    match levels {
+        Some(levels) => {
+            let integrated = levels
+                .integrated_lufs
+                .map_or("null".to_owned(), |lufs| format!("{:.2}", lufs));
+            let _ = write!(
+                out,
+                "{{\"rms_db\":{:.2},\"peak_db\":{:.2},\"max_peak_db\":{:.2},\
+                 \"momentary_lufs\":{:.2},\"integrated_lufs\":{}}}",
+                levels.rms_db,
+                levels.peak_db,
+                levels.max_peak_db,
+                levels.momentary_lufs,
+                integrated
+            );
+        }
+        None => out.push_str("null"),
+    }
+}
+
+fn json_talk_time(out: &mut String, talk_time: &TalkTime) {
+    let _ = write!(
+        out,
+        "{{\"seconds\":{:.3},\"spurts\":{},\"talking\":{}}}",
+        talk_time.total.as_secs_f64(),
+        talk_time.spurts,
+        talk_time.talking
+    );
+}
+
+impl Snapshot {
+    /// One row for the microphone and one per user; empty cells where nothing was measured.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        out.push_str(CSV_HEADER);
+        out.push('\n');
+        if self.microphone.is_some() {
+            out.push_str("microphone,,,,,,");
+            csv_levels(&mut out, self.microphone.as_ref());
+            out.push('\n');
+        }
+        for user in &self.users {
+            let connection = user
+                .connection
+                .map_or(String::new(), |conn| conn.0.to_string());
+            let _ = write!(
+                out,
+                "user,{},{},{:.3},{},{},",
+                connection,
+                user.user.0,
+                user.talk_time.total.as_secs_f64(),
+                user.talk_time.spurts,
+                user.talk_time.talking
+            );
+            csv_levels(&mut out, user.levels.as_ref());
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let taken_at = self
+            .taken_at
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |since| since.as_secs_f64());
+        let mut out = String::new();
+        let _ = write!(out, "{{\"taken_at\":{:.3},\"microphone\":", taken_at);
+        json_levels(&mut out, self.microphone.as_ref());
+        out.push_str(",\"users\":[");
+        for (index, user) in self.users.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"connection\":");
+            match user.connection {
+                Some(conn) => {
+                    let _ = write!(out, "{}", conn.0);
+                }
+                None => out.push_str("null"),
+            }
+            let _ = write!(out, ",\"user\":{},\"talk_time\":", user.user.0);
+            json_talk_time(&mut out, &user.talk_time);
+            out.push_str(",\"levels\":");
+            json_levels(&mut out, user.levels.as_ref());
+            out.push('}');
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::meter::{Levels, Snapshot, TalkTime, UserStats};
+    use crate::types as m;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn snapshot() -> Snapshot {
+        let levels = Levels {
+            rms_db: -20.0,
+            peak_db: -6.5,
+            max_peak_db: -1.25,
+            momentary_lufs: -18.0,
+            integrated_lufs: None,
+        };
+        Snapshot {
+            taken_at: UNIX_EPOCH + Duration::from_millis(1500),
+            microphone: Some(Levels {
+                integrated_lufs: Some(-23.0),
+                ..levels
+            }),
+            users: vec![
+                UserStats {
+                    connection: Some(m::ConnectionT(2)),
+                    user: m::UserIdT(7),
+                    levels: None,
+                    talk_time: TalkTime {
+                        total: Duration::from_millis(2500),
+                        spurts: 3,
+                        talking: true,
+                    },
+                },
+                UserStats {
+                    connection: None,
+                    user: m::UserIdT(8),
+                    levels: Some(levels),
+                    talk_time: TalkTime::default(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn csv_has_a_row_per_track() {
+        assert_eq!(
+            snapshot().to_csv(),
+            "track,connection,user,talk_seconds,talk_spurts,talking,\
+             rms_db,peak_db,max_peak_db,momentary_lufs,integrated_lufs\n\
+             microphone,,,,,,-20.00,-6.50,-1.25,-18.00,-23.00\n\
+             user,2,7,2.500,3,true,,,,,\n\
+             user,,8,0.000,0,false,-20.00,-6.50,-1.25,-18.00,\n"
+        );
+        let empty = Snapshot {
+            microphone: None,
+            users: Vec::new(),
+            ..snapshot()
+        };
+        assert_eq!(empty.to_csv().lines().count(), 1);
+    }
+
+    #[test]
+    fn json_uses_null_for_what_was_not_measured() {
+        assert_eq!(
+            snapshot().to_json(),
+            "{\"taken_at\":1.500,\"microphone\":{\"rms_db\":-20.00,\"peak_db\":-6.50,\
+             \"max_peak_db\":-1.25,\"momentary_lufs\":-18.00,\"integrated_lufs\":-23.00},\
+             \"users\":[{\"connection\":2,\"user\":7,\
+             \"talk_time\":{\"seconds\":2.500,\"spurts\":3,\"talking\":true},\"levels\":null},\
+             {\"connection\":null,\"user\":8,\
+             \"talk_time\":{\"seconds\":0.000,\"spurts\":0,\"talking\":false},\
+             \"levels\":{\"rms_db\":-20.00,\"peak_db\":-6.50,\"max_peak_db\":-1.25,\
+             \"momentary_lufs\":-18.00,\"integrated_lufs\":null}}]}"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::dsp::{linear_to_db, MAX_CHANNELS};
+
+const SUB_BLOCKS: usize = 4;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+const HISTOGRAM_TOP_LUFS: f64 = 5.0;
+const HISTOGRAM_STEP_LU: f64 = 0.1;
+const HISTOGRAM_BINS: usize =
+    ((HISTOGRAM_TOP_LUFS - ABSOLUTE_GATE_LUFS) / HISTOGRAM_STEP_LU) as usize;
+
+/// Levels over the last 400ms, plus the whole-session figures.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Levels {
+    pub rms_db: f32,
+    pub peak_db: f32,
+    /// Highest peak since the meter was created or reset
+    pub max_peak_db: f32,
+    /// EBU R128 momentary loudness
+    pub momentary_lufs: f32,
+    /// EBU R128 gated loudness; `None` until a block passes the absolute gate
+    pub integrated_lufs: Option<f32>,
+}
+
+fn energy_to_lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.max(1e-20).log10()
+}
+
+#[derive(Copy, Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&self, state: &mut [f64; 2], x: f64) -> f64 {
+        let y = self.b[0] * x + state[0];
+        state[0] = self.b[1] * x - self.a[0] * y + state[1];
+        state[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+// ITU-R BS.1770 K-weighting, with the reference filters re-derived for any sample rate
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let rate = sample_rate as f64;
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+    [shelf, high_pass]
+}
+
+#[derive(Copy, Clone, Default)]
+struct SubBlock {
+    weighted_energy: f64,
+    sum_squares: f64,
+    samples: usize,
+    peak: f32,
+}
+
+/// RMS, peak and loudness meter; allocates only when created.
+pub struct LoudnessMeter {
+    sample_rate: u32,
+    filters: [Biquad; 2],
+    filter_state: [[[f64; 2]; 2]; MAX_CHANNELS],
+    sub_block_frames: usize,
+    current: SubBlock,
+    current_frames: usize,
+    recent: [SubBlock; SUB_BLOCKS],
+    filled: usize,
+    next: usize,
+    max_peak: f32,
+    // Per 0.1 LU bin above the absolute gate: block count and summed mean-square energy
+    histogram: Box<[(u64, f64)]>,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        LoudnessMeter {
+            sample_rate,
+            filters: k_weighting(sample_rate),
+            filter_state: [[[0.0; 2]; 2]; MAX_CHANNELS],
+            sub_block_frames: (sample_rate / 10).max(1) as usize,
+            current: SubBlock::default(),
+            current_frames: 0,
+            recent: [SubBlock::default(); SUB_BLOCKS],
+            filled: 0,
+            next: 0,
+            max_peak: 0.0,
+            histogram: vec![(0, 0.0); HISTOGRAM_BINS].into_boxed_slice(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Meters interleaved audio; a change of sample rate restarts the meter.
+    pub fn process(&mut self, pcm: &[f32], channel_count: u16, sample_rate: u32) {
+        self.process_with(pcm, channel_count, sample_rate, |sample| sample);
+    }
+
+    pub fn process_i16(&mut self, pcm: &[i16], channel_count: u16, sample_rate: u32) {
+        self.process_with(pcm, channel_count, sample_rate, |sample| {
+            sample as f32 / 32768.0
+        });
+    }
+
+    fn process_with<T: Copy, F: Fn(T) -> f32>(
+        &mut self,
+        pcm: &[T],
+        channel_count: u16,
+        sample_rate: u32,
+        to_f32: F,
+    ) {
+        if sample_rate != self.sample_rate {
+            *self = Self::new(sample_rate);
+        }
+        let channels = (channel_count as usize).min(MAX_CHANNELS);
+        if channels == 0 {
+            return;
+        }
+        for frame in pcm.chunks_exact(channel_count as usize) {
+            for (sample, state) in frame[..channels].iter().zip(self.filter_state.iter_mut()) {
+                let sample = to_f32(*sample);
+                let x = sample as f64;
+                let shelved = self.filters[0].process(&mut state[0], x);
+                let weighted = self.filters[1].process(&mut state[1], shelved);
+                self.current.weighted_energy += weighted * weighted;
+                self.current.sum_squares += x * x;
+                self.current.peak = self.current.peak.max(sample.abs());
+            }
+            self.current.samples += channels;
+            self.current_frames += 1;
+            if self.current_frames == self.sub_block_frames {
+                self.finish_sub_block();
+            }
+        }
+    }
+
+    fn finish_sub_block(&mut self) {
+        self.max_peak = self.max_peak.max(self.current.peak);
+        self.recent[self.next] = self.current;
+        self.next = (self.next + 1) % SUB_BLOCKS;
+        self.filled = (self.filled + 1).min(SUB_BLOCKS);
+        self.current = SubBlock::default();
+        self.current_frames = 0;
+        // Gating blocks are 400ms long and overlap by 75%, so each sub-block completes one
+        if self.filled == SUB_BLOCKS {
+            let energy = self.block_energy();
+            let lufs = energy_to_lufs(energy);
+            if lufs > ABSOLUTE_GATE_LUFS {
+                let bin = (((lufs - ABSOLUTE_GATE_LUFS) / HISTOGRAM_STEP_LU) as usize)
+                    .min(HISTOGRAM_BINS - 1);
+                self.histogram[bin].0 += 1;
+                self.histogram[bin].1 += energy;
+            }
+        }
+    }
+
+    // Mean square of the K-weighted signal, summed over channels as BS.1770 specifies
+    fn block_energy(&self) -> f64 {
+        let blocks = &self.recent[..self.filled];
+        let energy: f64 = blocks.iter().map(|b| b.weighted_energy).sum();
+        let frames = (self.filled * self.sub_block_frames).max(1);
+        energy / frames as f64
+    }
+
+    fn integrated_lufs(&self) -> Option<f64> {
+        let (count, energy) = self
+            .histogram
+            .iter()
+            .fold((0, 0.0), |(c, e), (count, energy)| (c + count, e + energy));
+        if count == 0 {
+            return None;
+        }
+        let gate = energy_to_lufs(energy / count as f64) + RELATIVE_GATE_LU;
+        let first_bin = ((gate - ABSOLUTE_GATE_LUFS) / HISTOGRAM_STEP_LU).max(0.0) as usize;
+        let (count, energy) = self.histogram[first_bin.min(HISTOGRAM_BINS)..]
+            .iter()
+            .fold((0, 0.0), |(c, e), (count, energy)| (c + count, e + energy));
+        if count == 0 {
+            return None;
+        }
+        Some(energy_to_lufs(energy / count as f64))
+    }
+
+    pub fn levels(&self) -> Levels {
+        let blocks = &self.recent[..self.filled];
+        let samples: usize = blocks.iter().map(|b| b.samples).sum();
+        let sum_squares: f64 = blocks.iter().map(|b| b.sum_squares).sum();
+        let rms = (sum_squares / samples.max(1) as f64).sqrt() as f32;
+        let peak = blocks.iter().map(|b| b.peak).fold(0.0, f32::max);
+        Levels {
+            rms_db: linear_to_db(rms),
+            peak_db: linear_to_db(peak),
+            max_peak_db: linear_to_db(self.max_peak),
+            momentary_lufs: energy_to_lufs(self.block_energy()) as f32,
+            integrated_lufs: self.integrated_lufs().map(|lufs| lufs as f32),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, amplitude: f32, channels: usize, rate: u32, seconds: f32) -> Vec<f32> {
+        let frames = (rate as f32 * seconds) as usize;
+        (0..frames)
+            .flat_map(|frame| {
+                let phase = 2.0 * std::f32::consts::PI * frequency * frame as f32 / rate as f32;
+                std::iter::repeat(amplitude * phase.sin()).take(channels)
+            })
+            .collect()
+    }
+
+    fn close(actual: f32, expected: f32) -> bool {
+        (actual - expected).abs() < 0.1
+    }
+
+    #[test]
+    fn full_scale_sine_reads_minus_three() {
+        let mut meter = LoudnessMeter::new(48000);
+        meter.process(&sine(1000.0, 1.0, 1, 48000, 2.0), 1, 48000);
+        let levels = meter.levels();
+        assert!(close(levels.rms_db, -3.01), "{:?}", levels);
+        assert!(close(levels.peak_db, 0.0), "{:?}", levels);
+        assert!(close(levels.max_peak_db, 0.0), "{:?}", levels);
+        // BS.1770 calibrates K-weighting so a 1kHz sine reads the same in LUFS as in dBFS RMS
+        assert!(close(levels.momentary_lufs, -3.01), "{:?}", levels);
+        assert!(
+            close(levels.integrated_lufs.unwrap(), -3.01),
+            "{:?}",
+            levels
+        );
+    }
+
+    #[test]
+    fn channels_add_up_and_rates_are_rederived() {
+        let mut meter = LoudnessMeter::new(44100);
+        meter.process(&sine(1000.0, 0.5, 2, 44100, 1.0), 2, 44100);
+        let levels = meter.levels();
+        assert!(close(levels.rms_db, -9.03), "{:?}", levels);
+        assert!(close(levels.momentary_lufs, -6.02), "{:?}", levels);
+
+        let pcm: Vec<i16> = sine(1000.0, 0.5, 1, 16000, 1.0)
+            .iter()
+            .map(|&sample| (sample * 32768.0) as i16)
+            .collect();
+        meter.process_i16(&pcm, 1, 16000);
+        assert_eq!(meter.sample_rate(), 16000);
+        let levels = meter.levels();
+        assert!(close(levels.momentary_lufs, -9.03), "{:?}", levels);
+        assert!(close(levels.max_peak_db, -6.02), "{:?}", levels);
+    }
+
+    #[test]
+    fn quiet_blocks_are_gated_out() {
+        let mut meter = LoudnessMeter::new(48000);
+        meter.process(&[0.0; 48000], 1, 48000);
+        assert_eq!(meter.levels().integrated_lufs, None);
+
+        // Far enough below the loud part to fall under the relative gate; averaged in, it
+        // would pull the result down to -6, while the blocks straddling both cost a little
+        meter.process(&sine(1000.0, 0.01, 1, 48000, 2.0), 1, 48000);
+        meter.process(&sine(1000.0, 1.0, 1, 48000, 2.0), 1, 48000);
+        let integrated = meter.levels().integrated_lufs.unwrap();
+        assert!(integrated > -3.5 && integrated < -3.0, "{}", integrated);
+
+        meter.reset();
+        assert_eq!(meter.levels().integrated_lufs, None);
+        assert!(meter.levels().max_peak_db < -150.0);
+    }
+}
@@ -0,0 +1,289 @@
+//! Level metering and talk-time statistics.
+//!
+//! Audio levels are measured per speaking user (after any per-user and source
+//! processing) and for the outgoing microphone once `set_enabled(true)` is called.
+//! Talk time is always accumulated from talking-state events, per user and connection.
+
+mod export;
+mod loudness;
+
+pub use loudness::{Levels, LoudnessMeter};
+
+use crate::types as m;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct TalkTime {
+    pub total: Duration,
+    /// How many separate times the user started talking
+    pub spurts: u32,
+    pub talking: bool,
+}
+
+struct TalkTracker {
+    since: Option<Instant>,
+    total: Duration,
+    spurts: u32,
+}
+
+impl TalkTracker {
+    fn set_talking(&mut self, talking: bool, now: Instant) {
+        match (self.since, talking) {
+            (None, true) => {
+                self.since = Some(now);
+                self.spurts += 1;
+            }
+            (Some(since), false) => {
+                self.total += now.saturating_duration_since(since);
+                self.since = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn talk_time(&self, now: Instant) -> TalkTime {
+        let ongoing = self.since.map_or(Duration::from_secs(0), |since| {
+            now.saturating_duration_since(since)
+        });
+        TalkTime {
+            total: self.total + ongoing,
+            spurts: self.spurts,
+            talking: self.since.is_some(),
+        }
+    }
+}
+
+struct Meters {
+    microphone: Option<LoudnessMeter>,
+    users: BTreeMap<m::UserIdT, LoudnessMeter>,
+    talk: BTreeMap<(m::ConnectionT, m::UserIdT), TalkTracker>,
+}
+
+static METERS: Mutex<Meters> = Mutex::new(Meters {
+    microphone: None,
+    users: BTreeMap::new(),
+    talk: BTreeMap::new(),
+});
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns audio level metering on or off; talk time is tracked regardless.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Release);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+pub fn microphone_levels() -> Option<Levels> {
+    METERS.lock().microphone.as_ref().map(LoudnessMeter::levels)
+}
+
+pub fn user_levels(user: m::UserIdT) -> Option<Levels> {
+    METERS.lock().users.get(&user).map(LoudnessMeter::levels)
+}
+
+pub fn talk_time(conn: m::ConnectionT, user: m::UserIdT) -> TalkTime {
+    METERS
+        .lock()
+        .talk
+        .get(&(conn, user))
+        .map(|tracker| tracker.talk_time(Instant::now()))
+        .unwrap_or_default()
+}
+
+/// Clears all levels and talk time.
+pub fn reset() {
+    let mut meters = METERS.lock();
+    meters.microphone = None;
+    meters.users.clear();
+    meters.talk.clear();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserStats {
+    /// `None` for users who were metered but never reported a talking state
+    pub connection: Option<m::ConnectionT>,
+    pub user: m::UserIdT,
+    pub levels: Option<Levels>,
+    pub talk_time: TalkTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub taken_at: SystemTime,
+    pub microphone: Option<Levels>,
+    pub users: Vec<UserStats>,
+}
+
+pub fn snapshot() -> Snapshot {
+    let now = Instant::now();
+    let meters = METERS.lock();
+    let mut users: Vec<UserStats> = meters
+        .talk
+        .iter()
+        .map(|(&(conn, user), tracker)| UserStats {
+            connection: Some(conn),
+            user,
+            levels: meters.users.get(&user).map(LoudnessMeter::levels),
+            talk_time: tracker.talk_time(now),
+        })
+        .collect();
+    for (&user, meter) in meters.users.iter() {
+        if !meters.talk.keys().any(|&(_, talker)| talker == user) {
+            users.push(UserStats {
+                connection: None,
+                user,
+                levels: Some(meter.levels()),
+                talk_time: TalkTime::default(),
+            });
+        }
+    }
+    Snapshot {
+        taken_at: SystemTime::now(),
+        microphone: meters.microphone.as_ref().map(LoudnessMeter::levels),
+        users,
+    }
+}
+
+pub(crate) fn meter_microphone(pcm: &[i16], channel_count: u16, sample_rate: u32) {
+    if !is_enabled() {
+        return;
+    }
+    METERS
+        .lock()
+        .microphone
+        .get_or_insert_with(|| LoudnessMeter::new(sample_rate))
+        .process_i16(pcm, channel_count, sample_rate);
+}
+
+pub(crate) fn meter_user(user: m::UserIdT, pcm: &[f32], channel_count: u16, sample_rate: u32) {
+    if !is_enabled() {
+        return;
+    }
+    METERS
+        .lock()
+        .users
+        .entry(user)
+        .or_insert_with(|| LoudnessMeter::new(sample_rate))
+        .process(pcm, channel_count, sample_rate);
+}
+
+pub(crate) fn talking_state_changed(
+    conn: m::ConnectionT,
+    user: m::UserIdT,
+    state: m::TalkingStateT,
+) {
+    let talking = match state.0 {
+        m::TalkingState::TALKING | m::TalkingState::WHISPERING | m::TalkingState::SHOUTING => true,
+        m::TalkingState::PASSIVE | m::TalkingState::INVALID => false,
+    };
+    METERS
+        .lock()
+        .talk
+        .entry((conn, user))
+        .or_insert(TalkTracker {
+            since: None,
+            total: Duration::from_secs(0),
+            spurts: 0,
+        })
+        .set_talking(talking, Instant::now());
+}
+
+// Mumble sends no PASSIVE event for users who leave mid-sentence
+pub(crate) fn user_removed(conn: m::ConnectionT, user: m::UserIdT) {
+    if let Some(tracker) = METERS.lock().talk.get_mut(&(conn, user)) {
+        tracker.set_talking(false, Instant::now());
+    }
+}
+
+pub(crate) fn server_disconnected(conn: m::ConnectionT) {
+    let now = Instant::now();
+    let mut meters = METERS.lock();
+    for (_, tracker) in meters
+        .talk
+        .range_mut((conn, m::UserIdT(0))..=(conn, m::UserIdT(u32::MAX)))
+    {
+        tracker.set_talking(false, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Metering is switched on and off globally, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    fn state(state: m::TalkingState) -> m::TalkingStateT {
+        m::TalkingStateT(state)
+    }
+
+    #[test]
+    fn talk_time_counts_spurts_and_ongoing_speech() {
+        let start = Instant::now();
+        let mut tracker = TalkTracker {
+            since: None,
+            total: Duration::from_secs(0),
+            spurts: 0,
+        };
+        tracker.set_talking(true, start);
+        tracker.set_talking(true, start + Duration::from_secs(1));
+        tracker.set_talking(false, start + Duration::from_secs(2));
+        tracker.set_talking(false, start + Duration::from_secs(3));
+        tracker.set_talking(true, start + Duration::from_secs(5));
+        assert_eq!(
+            tracker.talk_time(start + Duration::from_secs(6)),
+            TalkTime {
+                total: Duration::from_secs(3),
+                spurts: 2,
+                talking: true,
+            }
+        );
+    }
+
+    #[test]
+    fn talking_ends_when_users_or_servers_go() {
+        let (conn, other) = (m::ConnectionT(9301), m::ConnectionT(9302));
+        let (alice, bob) = (m::UserIdT(1), m::UserIdT(2));
+        talking_state_changed(conn, alice, state(m::TalkingState::WHISPERING));
+        talking_state_changed(conn, bob, state(m::TalkingState::TALKING));
+        talking_state_changed(other, alice, state(m::TalkingState::SHOUTING));
+        assert!(talk_time(conn, alice).talking);
+
+        user_removed(conn, alice);
+        assert!(!talk_time(conn, alice).talking);
+        assert!(talk_time(other, alice).talking);
+        assert_eq!(talk_time(conn, alice).spurts, 1);
+
+        server_disconnected(conn);
+        assert!(!talk_time(conn, bob).talking);
+        assert!(talk_time(other, alice).talking);
+        server_disconnected(other);
+        assert_eq!(talk_time(conn, m::UserIdT(3)), TalkTime::default());
+    }
+
+    #[test]
+    fn levels_are_metered_only_while_enabled() {
+        let _serial = SERIAL.lock();
+        let quiet = m::UserIdT(9303);
+        set_enabled(false);
+        meter_user(quiet, &[0.5; 4800], 1, 48000);
+        assert_eq!(user_levels(quiet), None);
+
+        set_enabled(true);
+        meter_user(quiet, &[0.5; 4800], 1, 48000);
+        set_enabled(false);
+        assert!(user_levels(quiet).unwrap().peak_db > -6.1);
+        let stats = snapshot()
+            .users
+            .into_iter()
+            .find(|stats| stats.user == quiet)
+            .unwrap();
+        assert_eq!(stats.connection, None);
+        assert_eq!(stats.levels, user_levels(quiet));
+    }
+}
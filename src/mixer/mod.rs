@@ -0,0 +1,453 @@
+//! Mixes plugin-generated audio into Mumble's audio stages.
+//!
+//! Sources can be queued from any thread; the audio callback adopts them without
+//! waiting, converts them to the stage's rate and channel layout, and mixes them in.
+
+mod source;
+
+pub use source::{stream, AudioSource, Clip, ClipSource, StreamSource, StreamWriter};
+
+use crate::dsp::{self, db_to_linear, Param, Resampler};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+
+const SOURCE_CHUNK_FRAMES: usize = 512;
+const CONVERTED_CHUNK_FRAMES: usize = 512;
+const MAX_VOICES: usize = 32;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlaybackId(u64);
+
+/// Controls a queued source; dropping the handle does not stop playback.
+#[derive(Clone, Debug)]
+pub struct Playback {
+    id: PlaybackId,
+    gain_db: Param,
+    finished: Arc<AtomicBool>,
+}
+
+impl Playback {
+    pub fn id(&self) -> PlaybackId {
+        self.id
+    }
+
+    pub fn gain_db(&self) -> Param {
+        self.gain_db.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+}
+
+/// A playing source along with what it needs to reach the stage's format.
+struct Voice {
+    id: PlaybackId,
+    source: Box<dyn AudioSource>,
+    gain_db: Param,
+    finished: Arc<AtomicBool>,
+    resampler: Resampler,
+    source_buffer: Box<[f32]>,
+    source_position: usize,
+    source_frames: usize,
+    exhausted: bool,
+    converted: Box<[f32]>,
+}
+
+impl Voice {
+    fn new(id: PlaybackId, source: Box<dyn AudioSource>, target_rate: u32) -> (Self, Playback) {
+        let channels = source.channels() as usize;
+        let playback = Playback {
+            id,
+            gain_db: Param::new(0.0),
+            finished: Arc::new(AtomicBool::new(false)),
+        };
+        let voice = Voice {
+            id,
+            resampler: Resampler::new(source.sample_rate(), target_rate, source.channels()),
+            gain_db: playback.gain_db.clone(),
+            finished: playback.finished.clone(),
+            source_buffer: vec![0f32; SOURCE_CHUNK_FRAMES * channels].into_boxed_slice(),
+            source_position: 0,
+            source_frames: 0,
+            exhausted: false,
+            converted: vec![0f32; CONVERTED_CHUNK_FRAMES * channels].into_boxed_slice(),
+            source,
+        };
+        (voice, playback)
+    }
+
+    /// Adds this voice onto `out`; returns false once the source has run dry.
+    fn mix(&mut self, out: &mut [f32], out_channels: usize, out_rate: u32) -> bool {
+        if self.resampler.to_rate() != out_rate {
+            self.resampler =
+                Resampler::new(self.source.sample_rate(), out_rate, self.source.channels());
+        }
+        let gain = db_to_linear(self.gain_db.get());
+        let channels = self.source.channels() as usize;
+        let frames_wanted = out.len() / out_channels;
+        let mut written = 0;
+        while written < frames_wanted {
+            if self.source_position == self.source_frames && !self.exhausted {
+                self.source_frames = self.source.read(&mut self.source_buffer);
+                self.source_position = 0;
+                self.exhausted = self.source_frames == 0;
+            }
+            let want = (frames_wanted - written).min(CONVERTED_CHUNK_FRAMES);
+            let pending =
+                &self.source_buffer[self.source_position * channels..self.source_frames * channels];
+            let converted = &mut self.converted[..want * channels];
+            let (consumed, produced) = if self.resampler.is_passthrough() {
+                let frames = want.min(pending.len() / channels);
+                converted[..frames * channels].copy_from_slice(&pending[..frames * channels]);
+                (frames, frames)
+            } else {
+                self.resampler.process(pending, converted)
+            };
+            self.source_position += consumed;
+            if produced == 0 {
+                if self.exhausted {
+                    return false;
+                }
+                continue;
+            }
+            let target = &mut out[written * out_channels..(written + produced) * out_channels];
+            mix_frames(
+                &self.converted[..produced * channels],
+                channels,
+                target,
+                out_channels,
+                gain,
+            );
+            written += produced;
+        }
+        true
+    }
+}
+
+/// Adds `input` onto `out`, duplicating mono, averaging down to mono, or matching channels by index.
+fn mix_frames(input: &[f32], in_channels: usize, out: &mut [f32], out_channels: usize, gain: f32) {
+    let frames = input.chunks(in_channels).zip(out.chunks_mut(out_channels));
+    if in_channels == out_channels {
+        for (sample, out) in input.iter().zip(out.iter_mut()) {
+            *out += sample * gain;
+        }
+    } else if in_channels == 1 {
+        for (frame, out) in frames {
+            for out in out.iter_mut() {
+                *out += frame[0] * gain;
+            }
+        }
+    } else if out_channels == 1 {
+        let gain = gain / in_channels as f32;
+        for (frame, out) in frames {
+            out[0] += frame.iter().sum::<f32>() * gain;
+        }
+    } else {
+        for (frame, out) in frames {
+            for (sample, out) in frame.iter().zip(out.iter_mut()) {
+                *out += sample * gain;
+            }
+        }
+    }
+}
+
+enum Command {
+    Play(Box<Voice>),
+    Stop(PlaybackId),
+    StopAll,
+}
+
+struct MixerState {
+    voices: Vec<Voice>,
+    mix_buffer: Vec<f32>,
+}
+
+/// One mix point, e.g. the outgoing microphone stream.
+pub struct Mixer {
+    stage: dsp::Stage,
+    next_id: AtomicU64,
+    pending: Mutex<Vec<Command>>,
+    state: Mutex<MixerState>,
+    ducking_db: AtomicU32,
+    force_transmission: AtomicBool,
+}
+
+static INPUT: Mixer = Mixer::new(dsp::Stage::Input);
+static OUTPUT: Mixer = Mixer::new(dsp::Stage::Output);
+
+/// Mixes into the local microphone, so everyone in the channel hears it.
+pub fn input() -> &'static Mixer {
+    &INPUT
+}
+
+/// Mixes into local playback, heard only by this client.
+pub fn output() -> &'static Mixer {
+    &OUTPUT
+}
+
+impl Mixer {
+    const fn new(stage: dsp::Stage) -> Self {
+        Mixer {
+            stage,
+            next_id: AtomicU64::new(0),
+            pending: Mutex::new(Vec::new()),
+            state: Mutex::new(MixerState {
+                voices: Vec::new(),
+                mix_buffer: Vec::new(),
+            }),
+            ducking_db: AtomicU32::new(0),
+            force_transmission: AtomicBool::new(false),
+        }
+    }
+
+    pub fn play<S: AudioSource + 'static>(&self, source: S) -> Playback {
+        let id = PlaybackId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let target_rate = dsp::stage_sample_rate(self.stage);
+        let (voice, playback) = Voice::new(id, Box::new(source), target_rate);
+        self.pending.lock().push(Command::Play(Box::new(voice)));
+        playback
+    }
+
+    pub fn play_clip(&self, clip: &Clip) -> Playback {
+        self.play(ClipSource::new(clip.clone()))
+    }
+
+    pub fn stop(&self, playback: PlaybackId) {
+        self.pending.lock().push(Command::Stop(playback));
+    }
+
+    pub fn stop_all(&self) {
+        self.pending.lock().push(Command::StopAll);
+    }
+
+    /// Lowers the stage's own audio by `db` while anything is playing; 0 disables ducking.
+    pub fn set_ducking_db(&self, db: f32) {
+        self.ducking_db
+            .store((-db.abs()).to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn ducking_db(&self) -> f32 {
+        f32::from_bits(self.ducking_db.load(Ordering::Relaxed))
+    }
+
+    /// Whether the microphone is forced on while anything is playing, regardless of
+    /// voice activity or push-to-talk. Only meaningful for the input mixer.
+    pub fn set_force_transmission(&self, force: bool) {
+        self.force_transmission.store(force, Ordering::Relaxed);
+    }
+
+    pub fn is_playing(&self) -> bool {
+        !self.state.lock().voices.is_empty()
+    }
+
+    fn adopt_pending(&self, state: &mut MixerState) {
+        // Never wait on producers from the audio thread; pick them up next time instead
+        let mut pending = match self.pending.try_lock() {
+            Some(pending) => pending,
+            None => return,
+        };
+        for command in pending.drain(..) {
+            match command {
+                Command::Play(voice) => {
+                    if state.voices.len() < MAX_VOICES {
+                        state.voices.push(*voice);
+                    } else {
+                        voice.finished.store(true, Ordering::Release);
+                    }
+                }
+                Command::Stop(id) => state.voices.retain(|voice| {
+                    let keep = voice.id != id;
+                    if !keep {
+                        voice.finished.store(true, Ordering::Release);
+                    }
+                    keep
+                }),
+                Command::StopAll => {
+                    for voice in state.voices.drain(..) {
+                        voice.finished.store(true, Ordering::Release);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Mixes all voices into `mix_buffer`, returning whether anything played.
+    fn render(
+        &self,
+        state: &mut MixerState,
+        len: usize,
+        channel_count: u16,
+        sample_rate: u32,
+    ) -> bool {
+        self.adopt_pending(state);
+        if state.voices.is_empty() {
+            return false;
+        }
+        if state.mix_buffer.len() < len {
+            state.mix_buffer.resize(len, 0.0);
+        }
+        let mix = &mut state.mix_buffer[..len];
+        for sample in mix.iter_mut() {
+            *sample = 0.0;
+        }
+        let channels = channel_count as usize;
+        for voice in state.voices.iter_mut() {
+            if !voice.mix(mix, channels, sample_rate) {
+                voice.finished.store(true, Ordering::Release);
+            }
+        }
+        state
+            .voices
+            .retain(|voice| !voice.finished.load(Ordering::Relaxed));
+        true
+    }
+
+    /// Mixes into 16-bit microphone audio.
+    ///
+    /// Returns whether `pcm` changed, and whether transmission should be forced on.
+    pub(crate) fn mix_i16(
+        &self,
+        pcm: &mut [i16],
+        channel_count: u16,
+        sample_rate: u32,
+    ) -> (bool, bool) {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        let playing = self.render(state, pcm.len(), channel_count, sample_rate);
+        if playing {
+            let duck = db_to_linear(self.ducking_db());
+            for (out, mixed) in pcm.iter_mut().zip(state.mix_buffer.iter()) {
+                let sample = *out as f32 * duck + mixed * 32768.0;
+                *out = sample.clamp(-32768.0, 32767.0) as i16;
+            }
+        }
+        let force = playing && self.force_transmission.load(Ordering::Relaxed);
+        (playing, force)
+    }
+
+    /// Mixes into floating-point playback audio, returning whether `pcm` changed.
+    pub(crate) fn mix_f32(&self, pcm: &mut [f32], channel_count: u16, sample_rate: u32) -> bool {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        if !self.render(state, pcm.len(), channel_count, sample_rate) {
+            return false;
+        }
+        let duck = db_to_linear(self.ducking_db());
+        for (out, mixed) in pcm.iter_mut().zip(state.mix_buffer.iter()) {
+            *out = *out * duck + mixed;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_frames_matches_channel_layouts() {
+        let mut out = vec![1.0; 4];
+        mix_frames(&[0.5, -0.5, 0.25, 0.0], 2, &mut out, 2, 2.0);
+        assert_eq!(out, vec![2.0, 0.0, 1.5, 1.0]);
+
+        let mut out = vec![0.0; 4];
+        mix_frames(&[0.5, -0.5], 1, &mut out, 2, 1.0);
+        assert_eq!(out, vec![0.5, 0.5, -0.5, -0.5]);
+
+        let mut out = vec![0.0; 2];
+        mix_frames(&[0.5, 0.25, 1.0, -1.0], 2, &mut out, 1, 1.0);
+        assert_eq!(out, vec![0.375, 0.0]);
+
+        // Extra source channels are dropped, missing ones are left alone
+        let mut out = vec![0.0; 4];
+        mix_frames(&[0.1, 0.2, 0.3], 3, &mut out, 2, 1.0);
+        assert_eq!(out, vec![0.1, 0.2, 0.0, 0.0]);
+        let mut out = vec![0.0; 3];
+        mix_frames(&[0.1, 0.2], 2, &mut out, 3, 1.0);
+        assert_eq!(out, vec![0.1, 0.2, 0.0]);
+    }
+
+    #[test]
+    fn voices_finish_when_their_source_runs_dry() {
+        let mixer = Mixer::new(dsp::Stage::Output);
+        let playback = mixer.play_clip(&Clip::new(vec![0.5; 4], 1, 48000));
+        playback.gain_db().set(-6.0);
+        assert!(!mixer.is_playing());
+
+        let mut pcm = vec![0.0; 16];
+        assert!(mixer.mix_f32(&mut pcm, 2, 48000));
+        let gain = db_to_linear(-6.0);
+        assert!(pcm[..8].iter().all(|sample| *sample == 0.5 * gain));
+        assert!(pcm[8..].iter().all(|sample| *sample == 0.0));
+        assert!(playback.is_finished());
+        assert!(!mixer.is_playing());
+        assert!(!mixer.mix_f32(&mut pcm, 2, 48000));
+    }
+
+    #[test]
+    fn stopping_ends_only_that_voice() {
+        let mixer = Mixer::new(dsp::Stage::Output);
+        let first = mixer.play(ClipSource::looping(Clip::new(vec![0.25; 2], 1, 48000)));
+        let second = mixer.play(ClipSource::looping(Clip::new(vec![0.5; 2], 1, 48000)));
+        let mut pcm = vec![0.0; 8];
+        assert!(mixer.mix_f32(&mut pcm, 1, 48000));
+        assert!(pcm.iter().all(|sample| *sample == 0.75));
+        assert!(!first.is_finished() && !second.is_finished());
+
+        mixer.stop(first.id());
+        let mut pcm = vec![0.0; 8];
+        assert!(mixer.mix_f32(&mut pcm, 1, 48000));
+        assert!(pcm.iter().all(|sample| *sample == 0.5));
+        assert!(first.is_finished() && !second.is_finished());
+
+        mixer.stop_all();
+        assert!(!mixer.mix_f32(&mut pcm, 1, 48000));
+        assert!(second.is_finished());
+    }
+
+    #[test]
+    fn voices_beyond_the_limit_are_refused() {
+        let mixer = Mixer::new(dsp::Stage::Output);
+        let clip = Clip::new(vec![0.0; 2], 1, 48000);
+        let playbacks: Vec<Playback> = (0..=MAX_VOICES)
+            .map(|_| mixer.play(ClipSource::looping(clip.clone())))
+            .collect();
+        mixer.mix_f32(&mut [0.0; 4], 1, 48000);
+        assert!(playbacks[..MAX_VOICES].iter().all(|p| !p.is_finished()));
+        assert!(playbacks[MAX_VOICES].is_finished());
+    }
+
+    #[test]
+    fn converts_to_the_callback_rate() {
+        let mixer = Mixer::new(dsp::Stage::Output);
+        let playback = mixer.play_clip(&Clip::new(vec![0.5; 480], 1, 24000));
+        let mut pcm = vec![0.0; 960];
+        assert!(mixer.mix_f32(&mut pcm, 1, 48000));
+        // Past the resampler's lead-in the level holds
+        assert!(pcm[80..900]
+            .iter()
+            .all(|sample| (sample - 0.5).abs() < 0.01));
+        let mut pcm = vec![0.0; 960];
+        mixer.mix_f32(&mut pcm, 1, 48000);
+        assert!(playback.is_finished());
+    }
+
+    #[test]
+    fn microphone_mixing_ducks_clips_and_forces_transmission() {
+        let mixer = Mixer::new(dsp::Stage::Input);
+        mixer.set_ducking_db(6.0);
+        assert_eq!(mixer.ducking_db(), -6.0);
+        mixer.set_force_transmission(true);
+        let mut pcm = [20000i16, -20000, 1000, 1000];
+        assert_eq!(mixer.mix_i16(&mut pcm, 1, 48000), (false, false));
+        assert_eq!(pcm, [20000, -20000, 1000, 1000]);
+
+        mixer.play_clip(&Clip::new(vec![0.75, -0.75, 0.0, 0.0], 1, 48000));
+        assert_eq!(mixer.mix_i16(&mut pcm, 1, 48000), (true, true));
+        let duck = db_to_linear(-6.0);
+        assert_eq!(pcm[..2], [32767, -32768]);
+        assert_eq!(pcm[2], (1000.0 * duck) as i16);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::dsp::Resampler;
+use crate::ring;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Decoded, interleaved PCM held in memory. Cloning shares the samples.
+#[derive(Clone, Debug)]
+pub struct Clip {
+    samples: Arc<[f32]>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl Clip {
+    pub fn new(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Self {
+        assert!(channels > 0, "Clips need at least one channel");
+        assert_eq!(
+            samples.len() % channels as usize,
+            0,
+            "Clip samples must contain whole frames"
+        );
+        Clip {
+            samples: samples.into(),
+            channels,
+            sample_rate,
+        }
+    }
+
+    pub fn from_i16(samples: &[i16], channels: u16, sample_rate: u32) -> Self {
+        let samples = samples.iter().map(|s| *s as f32 / 32768.0).collect();
+        Self::new(samples, channels, sample_rate)
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames() as f64 / self.sample_rate as f64)
+    }
+
+    /// Converts the whole clip to `sample_rate`; a no-op clone if it already matches.
+    pub fn resampled(&self, sample_rate: u32) -> Clip {
+        if sample_rate == self.sample_rate {
+            return self.clone();
+        }
+        let channels = self.channels as usize;
+        let mut resampler = Resampler::new(self.sample_rate, sample_rate, self.channels);
+        // Output trails the input once a whole buffer is converted; pad the tail and skip
+        // the lead-in
+        let latency = resampler.latency();
+        let mut input = Vec::with_capacity(self.samples.len() + latency * channels);
+        input.extend_from_slice(&self.samples);
+        input.extend(std::iter::repeat(0.0).take(latency * channels));
+        let mut output = vec![0f32; resampler.max_output_frames(input.len() / channels) * channels];
+        let (_, produced) = resampler.process(&input, &mut output);
+        let ratio = sample_rate as f64 / self.sample_rate as f64;
+        let skip = (latency as f64 * ratio).round() as usize;
+        let frames =
+            ((self.frames() as f64 * ratio).round() as usize).min(produced.saturating_sub(skip));
+        output.truncate((skip + frames) * channels);
+        output.drain(..(skip * channels).min(output.len()));
+        Clip::new(output, self.channels, sample_rate)
+    }
+
+    pub(crate) fn shares_samples(&self, other: &Clip) -> bool {
+        Arc::ptr_eq(&self.samples, &other.samples)
+    }
+}
+
+/// Anything which can be mixed into a stage, pulled from the audio thread.
+pub trait AudioSource: Send {
+    fn channels(&self) -> u16;
+    fn sample_rate(&self) -> u32;
+
+    /// Fills `out` with interleaved frames in the source's own format, returning
+    /// how many frames were written. Returning zero ends playback.
+    fn read(&mut self, out: &mut [f32]) -> usize;
+}
+
+pub struct ClipSource {
+    clip: Clip,
+    position: usize,
+    looping: bool,
+}
+
+impl ClipSource {
+    pub fn new(clip: Clip) -> Self {
+        ClipSource {
+            clip,
+            position: 0,
+            looping: false,
+        }
+    }
+
+    pub fn looping(clip: Clip) -> Self {
+        ClipSource {
+            looping: true,
+            ..Self::new(clip)
+        }
+    }
+}
+
+impl AudioSource for ClipSource {
+    fn channels(&self) -> u16 {
+        self.clip.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.clip.sample_rate
+    }
+
+    fn read(&mut self, out: &mut [f32]) -> usize {
+        let channels = self.clip.channels as usize;
+        if self.looping && self.position == self.clip.frames() && self.position > 0 {
+            self.position = 0;
+        }
+        let frames = (out.len() / channels).min(self.clip.frames() - self.position);
+        let start = self.position * channels;
+        out[..frames * channels]
+            .copy_from_slice(&self.clip.samples[start..start + frames * channels]);
+        self.position += frames;
+        frames
+    }
+}
+
+/// Feeds a `StreamSource` from another thread, e.g. a TTS engine producing audio as it goes.
+pub struct StreamWriter {
+    producer: ring::Producer,
+    channels: u16,
+    closed: Arc<AtomicBool>,
+}
+
+/// Plays whatever its `StreamWriter` queues, and silence while it waits for more.
+/// Playback ends once the writer is dropped and the queue has drained.
+pub struct StreamSource {
+    consumer: ring::Consumer,
+    channels: u16,
+    sample_rate: u32,
+    closed: Arc<AtomicBool>,
+}
+
+/// Creates a stream which can hold `buffer_frames` frames of not-yet-played audio.
+pub fn stream(
+    channels: u16,
+    sample_rate: u32,
+    buffer_frames: usize,
+) -> (StreamWriter, StreamSource) {
+    assert!(channels > 0, "Streams need at least one channel");
+    let (producer, consumer) = ring::channel(buffer_frames * channels as usize);
+    let closed = Arc::new(AtomicBool::new(false));
+    (
+        StreamWriter {
+            producer,
+            channels,
+            closed: closed.clone(),
+        },
+        StreamSource {
+            consumer,
+            channels,
+            sample_rate,
+            closed,
+        },
+    )
+}
+
+impl StreamWriter {
+    /// Queues whole frames; returns false without queueing anything if they don't fit yet.
+    pub fn write(&mut self, samples: &[f32]) -> bool {
+        assert_eq!(
+            samples.len() % self.channels as usize,
+            0,
+            "Streams accept whole frames only"
+        );
+        self.producer.push(samples.iter().cloned())
+    }
+
+    pub fn free_frames(&self) -> usize {
+        self.producer.free() / self.channels as usize
+    }
+}
+
+impl Drop for StreamWriter {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Release);
+    }
+}
+
+impl AudioSource for StreamSource {
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn read(&mut self, out: &mut [f32]) -> usize {
+        let closed = self.closed.load(Ordering::Acquire);
+        let frame_len = self.channels as usize;
+        let whole = out.len() / frame_len * frame_len;
+        let read = self.consumer.pop(&mut out[..whole]);
+        if read == 0 && closed {
+            return 0;
+        }
+        for sample in out[read..whole].iter_mut() {
+            *sample = 0.0;
+        }
+        whole / frame_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clip_sources_play_once_or_loop() {
+        let clip = Clip::new(vec![1.0, -1.0, 2.0, -2.0, 3.0, -3.0], 2, 8000);
+        let mut once = ClipSource::new(clip.clone());
+        let mut out = [0.0; 4];
+        assert_eq!(once.read(&mut out), 2);
+        assert_eq!(out, [1.0, -1.0, 2.0, -2.0]);
+        assert_eq!(once.read(&mut out), 1);
+        assert_eq!(out[..2], [3.0, -3.0]);
+        assert_eq!(once.read(&mut out), 0);
+
+        let mut looping = ClipSource::looping(clip);
+        let mut out = [0.0; 6];
+        assert_eq!(looping.read(&mut out), 3);
+        assert_eq!(looping.read(&mut out[..4]), 2);
+        assert_eq!(out[..4], [1.0, -1.0, 2.0, -2.0]);
+        // An empty clip ends instead of looping forever
+        assert_eq!(
+            ClipSource::looping(Clip::new(Vec::new(), 1, 8000)).read(&mut out),
+            0
+        );
+    }
+
+    #[test]
+    fn streams_pad_with_silence_until_the_writer_is_dropped() {
+        let (mut writer, mut source) = stream(2, 16000, 4);
+        assert_eq!(writer.free_frames(), 4);
+        assert!(writer.write(&[0.5, 0.5, 0.25, 0.25]));
+        assert!(!writer.write(&[1.0; 6]));
+
+        let mut out = [9.0; 7];
+        assert_eq!(source.read(&mut out), 3);
+        assert_eq!(out, [0.5, 0.5, 0.25, 0.25, 0.0, 0.0, 9.0]);
+        assert_eq!(source.read(&mut out), 3);
+        drop(writer);
+        assert_eq!(source.read(&mut out), 0);
+    }
+
+    #[test]
+    fn resampling_keeps_length_and_level() {
+        let clip = Clip::new(vec![0.5; 4410], 1, 44100);
+        let resampled = clip.resampled(48000);
+        assert_eq!(resampled.sample_rate(), 48000);
+        assert_eq!(resampled.frames(), 4800);
+        assert!(resampled.samples()[10..4790]
+            .iter()
+            .all(|sample| (sample - 0.5).abs() < 0.01));
+        assert!(clip.resampled(44100).shares_samples(&clip));
+    }
+}
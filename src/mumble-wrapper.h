@@ -1,3 +0,0 @@
-#include <string.h>
-#include "PluginComponents_v_1_0_x.h"
-#include "MumblePlugin_v_1_0_x.h"
@@ -0,0 +1,317 @@
+use super::SampleSink;
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_RICE_PARAMETER: u32 = 14;
+const FIXED_ORDER: usize = 2;
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    accumulator: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            bytes: Vec::new(),
+            accumulator: 0,
+            bits: 0,
+        }
+    }
+
+    fn write(&mut self, value: u64, bits: u32) {
+        for shift in (0..bits).rev() {
+            self.accumulator = (self.accumulator << 1) | ((value >> shift) & 1);
+            self.bits += 1;
+            if self.bits == 8 {
+                self.bytes.push(self.accumulator as u8);
+                self.accumulator = 0;
+                self.bits = 0;
+            }
+        }
+    }
+
+    fn write_signed(&mut self, value: i64, bits: u32) {
+        self.write(value as u64 & ((1u64 << bits) - 1), bits);
+    }
+
+    fn write_unary(&mut self, zeros: u64) {
+        for _ in 0..zeros {
+            self.write(0, 1);
+        }
+        self.write(1, 1);
+    }
+
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |mut crc, byte| {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+// Frame numbers use UTF-8's variable-length scheme
+fn write_coded_number(out: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        out.write(value, 8);
+        return;
+    }
+    let mut continuation_bytes = 1;
+    while value >> (6 * continuation_bytes + (6 - continuation_bytes)) != 0 {
+        continuation_bytes += 1;
+    }
+    let lead_marker = (0xFF00u64 >> (continuation_bytes + 1)) & 0xFF;
+    out.write(lead_marker | (value >> (6 * continuation_bytes)), 8);
+    for index in (0..continuation_bytes).rev() {
+        out.write(0x80 | ((value >> (6 * index)) & 0x3F), 8);
+    }
+}
+
+fn rice_cost(residuals: &[u32], parameter: u32) -> u64 {
+    residuals
+        .iter()
+        .map(|r| (r >> parameter) as u64 + 1 + parameter as u64)
+        .sum()
+}
+
+fn write_subframe(out: &mut BitWriter, samples: &[i32]) {
+    let verbatim_bits = samples.len() as u64 * BITS_PER_SAMPLE as u64;
+    let warmup_bits = FIXED_ORDER as u64 * BITS_PER_SAMPLE as u64;
+    if samples.len() > FIXED_ORDER {
+        let residuals: Vec<u32> = samples
+            .windows(3)
+            .map(|w| {
+                let residual = w[2] - 2 * w[1] + w[0];
+                ((residual << 1) ^ (residual >> 31)) as u32
+            })
+            .collect();
+        let (parameter, cost) = (0..=MAX_RICE_PARAMETER)
+            .map(|p| (p, rice_cost(&residuals, p)))
+            .min_by_key(|(_, cost)| *cost)
+            .unwrap();
+        if cost + warmup_bits + 10 < verbatim_bits {
+            out.write(0, 1);
+            out.write(0b001000 | FIXED_ORDER as u64, 6);
+            out.write(0, 1);
+            for warmup in &samples[..FIXED_ORDER] {
+                out.write_signed(*warmup as i64, BITS_PER_SAMPLE);
+            }
+            // Rice coding, a single partition
+            out.write(0b00, 2);
+            out.write(0, 4);
+            out.write(parameter as u64, 4);
+            for residual in residuals {
+                out.write_unary((residual >> parameter) as u64);
+                out.write(residual as u64 & ((1u64 << parameter) - 1), parameter);
+            }
+            return;
+        }
+    }
+    out.write(0, 1);
+    out.write(0b000001, 6);
+    out.write(0, 1);
+    for sample in samples {
+        out.write_signed(*sample as i64, BITS_PER_SAMPLE);
+    }
+}
+
+/// Minimal 16-bit FLAC encoder: fixed blocks, second-order fixed prediction, no MD5.
+pub struct FlacWriter {
+    out: BufWriter<File>,
+    channels: usize,
+    pending: Vec<i32>,
+    frame_number: u64,
+    total_frames: u64,
+    min_frame_bytes: u32,
+    max_frame_bytes: u32,
+    sample_rate: u32,
+}
+
+impl FlacWriter {
+    pub fn create(path: &Path, channels: u16, sample_rate: u32) -> io::Result<Self> {
+        if channels == 0 || channels > 8 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "FLAC supports between 1 and 8 channels",
+            ));
+        }
+        let mut writer = FlacWriter {
+            out: BufWriter::new(File::create(path)?),
+            channels: channels as usize,
+            pending: Vec::with_capacity(BLOCK_SIZE * channels as usize),
+            frame_number: 0,
+            total_frames: 0,
+            min_frame_bytes: u32::MAX,
+            max_frame_bytes: 0,
+            sample_rate,
+        };
+        writer.out.write_all(b"fLaC")?;
+        writer.write_stream_info()?;
+        Ok(writer)
+    }
+
+    fn write_stream_info(&mut self) -> io::Result<()> {
+        let mut info = BitWriter::new();
+        // Last-metadata-block flag, STREAMINFO type, 34 bytes long
+        info.write(1, 1);
+        info.write(0, 7);
+        info.write(34, 24);
+        info.write(BLOCK_SIZE as u64, 16);
+        info.write(BLOCK_SIZE as u64, 16);
+        let min_frame_bytes = if self.min_frame_bytes == u32::MAX {
+            0
+        } else {
+            self.min_frame_bytes
+        };
+        info.write(min_frame_bytes as u64, 24);
+        info.write(self.max_frame_bytes as u64, 24);
+        info.write(self.sample_rate as u64, 20);
+        info.write(self.channels as u64 - 1, 3);
+        info.write(BITS_PER_SAMPLE as u64 - 1, 5);
+        info.write(self.total_frames, 36);
+        // An all-zero MD5 signature means "not computed"
+        info.write(0, 64);
+        info.write(0, 64);
+        self.out.write_all(&info.bytes)
+    }
+
+    fn write_frame(&mut self, samples: &[i32]) -> io::Result<()> {
+        let frames = samples.len() / self.channels;
+        let mut frame = BitWriter::new();
+        frame.write(0b11111111111110, 14);
+        frame.write(0, 1);
+        // Fixed-blocksize stream
+        frame.write(0, 1);
+        // Block size stored as 16 bits after the header
+        frame.write(0b0111, 4);
+        // Sample rate taken from STREAMINFO
+        frame.write(0b0000, 4);
+        frame.write(self.channels as u64 - 1, 4);
+        // 16 bits per sample
+        frame.write(0b100, 3);
+        frame.write(0, 1);
+        write_coded_number(&mut frame, self.frame_number);
+        frame.write(frames as u64 - 1, 16);
+        let header_crc = crc8(&frame.bytes);
+        frame.write(header_crc as u64, 8);
+
+        let mut channel = Vec::with_capacity(frames);
+        for index in 0..self.channels {
+            channel.clear();
+            channel.extend(samples.iter().skip(index).step_by(self.channels));
+            write_subframe(&mut frame, &channel);
+        }
+        frame.align();
+        let frame_crc = crc16(&frame.bytes);
+        frame.write(frame_crc as u64, 16);
+
+        self.out.write_all(&frame.bytes)?;
+        let frame_bytes = frame.bytes.len() as u32;
+        self.min_frame_bytes = self.min_frame_bytes.min(frame_bytes);
+        self.max_frame_bytes = self.max_frame_bytes.max(frame_bytes);
+        self.frame_number += 1;
+        self.total_frames += frames as u64;
+        Ok(())
+    }
+}
+
+impl SampleSink for FlacWriter {
+    fn write(&mut self, samples: &[f32]) -> io::Result<()> {
+        let block_samples = BLOCK_SIZE * self.channels;
+        for sample in samples {
+            self.pending
+                .push((sample * 32768.0).clamp(-32768.0, 32767.0) as i32);
+            if self.pending.len() == block_samples {
+                let block = std::mem::take(&mut self.pending);
+                self.write_frame(&block)?;
+                self.pending = block;
+                self.pending.clear();
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> io::Result<()> {
+        let whole_frames = self.pending.len() / self.channels * self.channels;
+        if whole_frames > 0 {
+            let block = std::mem::take(&mut self.pending);
+            self.write_frame(&block[..whole_frames])?;
+        }
+        self.out.seek(SeekFrom::Start(4))?;
+        self.write_stream_info()?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+    use std::fs;
+
+    #[test]
+    fn decodes_to_what_was_written() {
+        let path =
+            std::env::temp_dir().join(format!("mumble-sys-flac-{}.flac", std::process::id()));
+        // More than one block, ending in a partial one, with a loud and a quiet channel
+        let frames = BLOCK_SIZE * 2 + 123;
+        let samples: Vec<f32> = (0..frames)
+            .flat_map(|i| {
+                let phase = 2.0 * PI * 440.0 * i as f32 / 48000.0;
+                vec![phase.sin() * 0.9, (phase * 3.0).cos() * 0.01]
+            })
+            .collect();
+        let mut writer = Box::new(FlacWriter::create(&path, 2, 48000).unwrap());
+        writer.write(&samples).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = claxon::FlacReader::open(&path).unwrap();
+        let info = reader.streaminfo();
+        assert_eq!((info.channels, info.sample_rate), (2, 48000));
+        assert_eq!(info.bits_per_sample, BITS_PER_SAMPLE);
+        assert_eq!(info.samples, Some(frames as u64));
+        let decoded: Vec<i32> = reader.samples().map(|sample| sample.unwrap()).collect();
+        let expected: Vec<i32> = samples
+            .iter()
+            .map(|sample| (sample * 32768.0).clamp(-32768.0, 32767.0) as i32)
+            .collect();
+        assert_eq!(decoded, expected);
+
+        let clip = crate::assets::load(&path).unwrap();
+        assert_eq!((clip.channels(), clip.sample_rate()), (2, 48000));
+        assert_eq!(clip.samples()[0], expected[0] as f32 / 32768.0);
+        assert_eq!(clip.samples().len(), expected.len());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,522 @@
+//! Records the audio passing through the plugin callbacks to disk.
+//!
+//! Callbacks only copy samples into per-track ring buffers; a background thread
+//! owns the files, so audio never waits on disk I/O. If the writer falls behind,
+//! whole buffers are dropped rather than blocking. Tracks are opened by the writer too: a
+//! callback with audio for a track that isn't open yet only asks for it, and its audio is
+//! dropped until the writer has caught up.
+//!
+//! Callbacks never wait for the recorder's lock either: a callback that finds it taken skips
+//! its buffer, and per-user tracks pad the skipped time with silence on their next buffer.
+//!
+//! Mumble only fetches a user's audio while they talk, so per-user tracks are padded with
+//! silence for the time since the recording started, keeping them in step with the others.
+
+#[cfg(feature = "flac")]
+mod flac;
+mod wav;
+
+#[cfg(feature = "flac")]
+pub use flac::FlacWriter;
+pub use wav::WavWriter;
+
+use crate::ids::Scoped;
+use crate::ring;
+use crate::types as m;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WRITER_INTERVAL: Duration = Duration::from_millis(20);
+const DRAIN_CHUNK_LEN: usize = 8192;
+/// Tracks that may wait for the writer to open them at once
+const PENDING_LIMIT: usize = 16;
+/// Silence is queued as a NaN carrying the number of silent frames in its payload, which is
+/// unambiguous since captured NaNs are zeroed
+const GAP_MARKER: u32 = 0x7FC0_0000;
+const MAX_GAP_FRAMES: u32 = (1 << 22) - 1;
+
+/// Destination for a track's interleaved samples, driven from the writer thread.
+pub trait SampleSink: Send {
+    fn write(&mut self, samples: &[f32]) -> io::Result<()>;
+    fn finish(self: Box<Self>) -> io::Result<()>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecordingFormat {
+    Wav16,
+    WavFloat,
+    #[cfg(feature = "flac")]
+    Flac,
+}
+
+impl RecordingFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            RecordingFormat::Wav16 | RecordingFormat::WavFloat => "wav",
+            #[cfg(feature = "flac")]
+            RecordingFormat::Flac => "flac",
+        }
+    }
+
+    fn create_sink(
+        self,
+        path: &Path,
+        channels: u16,
+        sample_rate: u32,
+    ) -> io::Result<Box<dyn SampleSink>> {
+        Ok(match self {
+            RecordingFormat::Wav16 => {
+                Box::new(WavWriter::create(path, channels, sample_rate, false)?)
+            }
+            RecordingFormat::WavFloat => {
+                Box::new(WavWriter::create(path, channels, sample_rate, true)?)
+            }
+            #[cfg(feature = "flac")]
+            RecordingFormat::Flac => Box::new(FlacWriter::create(path, channels, sample_rate)?),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Track {
+    /// Everything about to be played back (`on_audio_output_about_to_play`)
+    Mix,
+    /// The local microphone (`on_audio_input`)
+    Microphone,
+    /// A single speaking user (`on_audio_source_fetched`), on the server they are connected
+    /// through since user IDs repeat across servers
+    User(Scoped<m::UserIdT>),
+}
+
+impl Track {
+    fn file_stem(self) -> String {
+        match self {
+            Track::Mix => "mix".into(),
+            Track::Microphone => "mic".into(),
+            Track::User(user) => format!("user-{}-{}", user.connection().0, user.id().0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    pub directory: PathBuf,
+    /// Files are named `<prefix>-<track>.<ext>`
+    pub prefix: String,
+    pub format: RecordingFormat,
+    pub mix: bool,
+    pub microphone: bool,
+    pub per_user: bool,
+    /// How much audio each track may queue before buffers are dropped
+    pub buffer_seconds: f32,
+}
+
+impl RecorderConfig {
+    pub fn new<P: Into<PathBuf>>(directory: P, prefix: &str) -> Self {
+        RecorderConfig {
+            directory: directory.into(),
+            prefix: prefix.into(),
+            format: RecordingFormat::Wav16,
+            mix: true,
+            microphone: true,
+            per_user: false,
+            buffer_seconds: 2.0,
+        }
+    }
+
+    fn records(&self, track: Track) -> bool {
+        match track {
+            Track::Mix => self.mix,
+            Track::Microphone => self.microphone,
+            Track::User(_) => self.per_user,
+        }
+    }
+}
+
+struct TrackTap {
+    producer: ring::Producer,
+    channels: u16,
+    sample_rate: u32,
+    /// Frames queued so far, silence included
+    frames: u64,
+}
+
+/// A track with audio waiting for the writer to open it
+#[derive(Copy, Clone)]
+struct PendingTrack {
+    track: Track,
+    channels: u16,
+    sample_rate: u32,
+}
+
+struct Taps {
+    config: RecorderConfig,
+    started: Instant,
+    open: BTreeMap<Track, TrackTap>,
+    pending: [Option<PendingTrack>; PENDING_LIMIT],
+}
+
+impl Taps {
+    /// Asks the writer to open `track`, unless it is already waiting. Never allocates, since
+    /// it runs on the audio thread.
+    fn request(&mut self, track: Track, channels: u16, sample_rate: u32) {
+        if self
+            .pending
+            .iter()
+            .flatten()
+            .any(|pending| pending.track == track)
+        {
+            return;
+        }
+        if let Some(slot) = self.pending.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(PendingTrack {
+                track,
+                channels,
+                sample_rate,
+            });
+        }
+    }
+}
+
+struct Recording {
+    taps: Taps,
+    stop: Arc<AtomicBool>,
+    writer: thread::JoinHandle<io::Result<()>>,
+}
+
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+static IS_RECORDING: AtomicBool = AtomicBool::new(false);
+
+struct WriterTrack {
+    consumer: ring::Consumer,
+    sink: Box<dyn SampleSink>,
+    channels: u16,
+}
+
+/// Opens the tracks callbacks asked for, handing their producers back to the callbacks.
+fn open_pending(config: &RecorderConfig, tracks: &mut Vec<WriterTrack>) -> io::Result<()> {
+    let pending = match RECORDING.lock().as_mut() {
+        Some(recording) => std::mem::take(&mut recording.taps.pending),
+        None => return Ok(()),
+    };
+    let mut result = Ok(());
+    for pending in pending.iter().flatten() {
+        let path = config.directory.join(format!(
+            "{}-{}.{}",
+            config.prefix,
+            pending.track.file_stem(),
+            config.format.extension()
+        ));
+        let sink = match config
+            .format
+            .create_sink(&path, pending.channels, pending.sample_rate)
+        {
+            Ok(sink) => sink,
+            Err(e) => {
+                result = result.and(Err(e));
+                continue;
+            }
+        };
+        let capacity = (config.buffer_seconds.max(0.1) * pending.sample_rate as f32) as usize
+            * pending.channels as usize;
+        let (producer, consumer) = ring::channel(capacity);
+        tracks.push(WriterTrack {
+            consumer,
+            sink,
+            channels: pending.channels,
+        });
+        if let Some(recording) = RECORDING.lock().as_mut() {
+            recording.taps.open.insert(
+                pending.track,
+                TrackTap {
+                    producer,
+                    channels: pending.channels,
+                    sample_rate: pending.sample_rate,
+                    frames: 0,
+                },
+            );
+        }
+    }
+    result
+}
+
+/// Writes drained samples, expanding gap markers into silence.
+fn write_drained(track: &mut WriterTrack, samples: &[f32]) -> io::Result<()> {
+    const SILENCE: [f32; 1024] = [0.0; 1024];
+    let mut rest = samples;
+    while let Some(marker) = rest.iter().position(|sample| sample.is_nan()) {
+        track.sink.write(&rest[..marker])?;
+        let gap = (rest[marker].to_bits() & MAX_GAP_FRAMES) as usize;
+        let mut silent = gap * track.channels as usize;
+        while silent > 0 {
+            let len = silent.min(SILENCE.len());
+            track.sink.write(&SILENCE[..len])?;
+            silent -= len;
+        }
+        rest = &rest[marker + 1..];
+    }
+    track.sink.write(rest)
+}
+
+fn run_writer(config: RecorderConfig, stop: Arc<AtomicBool>) -> io::Result<()> {
+    let mut tracks: Vec<WriterTrack> = Vec::new();
+    let mut chunk = vec![0f32; DRAIN_CHUNK_LEN];
+    let mut result = Ok(());
+    loop {
+        let stopping = stop.load(Ordering::Acquire);
+        result = result.and(open_pending(&config, &mut tracks));
+        for track in tracks.iter_mut() {
+            loop {
+                let count = track.consumer.pop(&mut chunk);
+                if count == 0 {
+                    break;
+                }
+                if let Err(e) = write_drained(track, &chunk[..count]) {
+                    result = result.and(Err(e));
+                    break;
+                }
+            }
+        }
+        if stopping {
+            break;
+        }
+        thread::sleep(WRITER_INTERVAL);
+    }
+    for track in tracks {
+        result = result.and(track.sink.finish());
+    }
+    result
+}
+
+/// Starts writing the configured tracks; fails if a recording is already running.
+pub fn start_recording(config: RecorderConfig) -> io::Result<()> {
+    let mut recording = RECORDING.lock();
+    if recording.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "A recording is already running",
+        ));
+    }
+    std::fs::create_dir_all(&config.directory)?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let writer = {
+        let config = config.clone();
+        let stop = stop.clone();
+        thread::Builder::new()
+            .name("mumble-sys recorder".into())
+            .spawn(move || run_writer(config, stop))?
+    };
+    *recording = Some(Recording {
+        taps: Taps {
+            config,
+            started: Instant::now(),
+            open: BTreeMap::new(),
+            pending: [None; PENDING_LIMIT],
+        },
+        stop,
+        writer,
+    });
+    IS_RECORDING.store(true, Ordering::Release);
+    Ok(())
+}
+
+/// Flushes and closes all files, reporting the first I/O error the writer hit.
+pub fn stop_recording() -> io::Result<()> {
+    let recording = RECORDING.lock().take();
+    IS_RECORDING.store(false, Ordering::Release);
+    let recording = match recording {
+        Some(recording) => recording,
+        None => return Ok(()),
+    };
+    // Taps are gone by now, so everything queued is visible to the final drain
+    drop(recording.taps);
+    recording.stop.store(true, Ordering::Release);
+    recording.writer.join().unwrap_or_else(|_| {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "Recorder thread panicked",
+        ))
+    })
+}
+
+pub fn is_recording() -> bool {
+    IS_RECORDING.load(Ordering::Acquire)
+}
+
+fn capture_with<I, F>(track: Track, channel_count: u16, sample_rate: u32, samples: F)
+where
+    I: ExactSizeIterator<Item = f32>,
+    F: FnOnce() -> I,
+{
+    if !is_recording() || channel_count == 0 {
+        return;
+    }
+    // Skipping a buffer beats stalling the audio thread behind the writer or `stop_recording`
+    let mut recording = match RECORDING.try_lock() {
+        Some(recording) => recording,
+        None => return,
+    };
+    let taps = match recording.as_mut() {
+        Some(recording) => &mut recording.taps,
+        None => return,
+    };
+    if !taps.config.records(track) {
+        return;
+    }
+    let elapsed = taps.started.elapsed();
+    let tap = match taps.open.get_mut(&track) {
+        Some(tap) => tap,
+        None => {
+            taps.request(track, channel_count, sample_rate);
+            return;
+        }
+    };
+    // A track's layout is fixed once its file is open
+    if tap.channels != channel_count {
+        return;
+    }
+    let samples = samples();
+    let frames = (samples.len() / channel_count as usize) as u64;
+    if let Track::User(_) = track {
+        // Up to a buffer late is callback jitter, not silence
+        let due = (elapsed.as_secs_f64() * tap.sample_rate as f64) as u64;
+        let gap = due.saturating_sub(tap.frames);
+        if gap > frames {
+            let max = MAX_GAP_FRAMES as u64;
+            let markers = ((gap + max - 1) / max) as usize;
+            let queued = tap.producer.push((0..markers).map(|marker| {
+                let left = gap - marker as u64 * max;
+                f32::from_bits(GAP_MARKER | left.min(max) as u32)
+            }));
+            if !queued {
+                return;
+            }
+            tap.frames += gap;
+        }
+    }
+    let samples = samples.map(|sample| if sample.is_nan() { 0.0 } else { sample });
+    if tap.producer.push(samples) {
+        tap.frames += frames;
+    }
+}
+
+pub(crate) fn capture(track: Track, pcm: &[f32], channel_count: u16, sample_rate: u32) {
+    capture_with(track, channel_count, sample_rate, || pcm.iter().cloned());
+}
+
+pub(crate) fn capture_i16(track: Track, pcm: &[i16], channel_count: u16, sample_rate: u32) {
+    capture_with(track, channel_count, sample_rate, || {
+        pcm.iter().map(|sample| *sample as f32 / 32768.0)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::Arc;
+
+    /// The recording is global, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    struct Collected(Arc<Mutex<Vec<f32>>>);
+
+    impl SampleSink for Collected {
+        fn write(&mut self, samples: &[f32]) -> io::Result<()> {
+            self.0.lock().extend_from_slice(samples);
+            Ok(())
+        }
+
+        fn finish(self: Box<Self>) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn gap_markers_expand_to_silent_frames() {
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let mut track = WriterTrack {
+            consumer: ring::channel(4).1,
+            sink: Box::new(Collected(collected.clone())),
+            channels: 2,
+        };
+        let marker = f32::from_bits(GAP_MARKER | 2);
+        write_drained(&mut track, &[0.5, 0.5, marker, 0.25, 0.25, marker]).unwrap();
+        assert_eq!(
+            *collected.lock(),
+            vec![0.5, 0.5, 0.0, 0.0, 0.0, 0.0, 0.25, 0.25, 0.0, 0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn users_are_opened_by_the_writer_and_padded_to_the_recording() {
+        let _serial = SERIAL.lock();
+        let dir = std::env::temp_dir().join(format!("mumble-sys-record-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut config = RecorderConfig::new(&dir, "test");
+        config.format = RecordingFormat::WavFloat;
+        config.mix = false;
+        config.microphone = false;
+        config.per_user = true;
+        start_recording(config).unwrap();
+        let user = Track::User(m::UserIdT(3).on(m::ConnectionT(2)));
+
+        // Only asks for the track; the writer opens it
+        capture(user, &[0.5; 480], 1, 48000);
+        let opened = (0..100).any(|_| {
+            thread::sleep(Duration::from_millis(10));
+            RECORDING
+                .lock()
+                .as_ref()
+                .unwrap()
+                .taps
+                .open
+                .contains_key(&user)
+        });
+        assert!(opened);
+        thread::sleep(Duration::from_millis(200));
+        capture(user, &[0.5; 480], 1, 48000);
+        let elapsed = RECORDING.lock().as_ref().unwrap().taps.started.elapsed();
+        stop_recording().unwrap();
+
+        let clip = crate::assets::load(&dir.join("test-user-2-3.wav")).unwrap();
+        let (silence, talk) = clip.samples().split_at(clip.frames() - 480);
+        assert!(talk.iter().all(|sample| *sample == 0.5));
+        assert!(silence.iter().all(|sample| *sample == 0.0));
+        let padded = Duration::from_secs_f64(silence.len() as f64 / 48000.0);
+        assert!(padded >= Duration::from_millis(200) && padded <= elapsed);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn captures_skip_buffers_while_the_recording_is_busy() {
+        let _serial = SERIAL.lock();
+        let dir = std::env::temp_dir().join(format!("mumble-sys-busy-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut config = RecorderConfig::new(&dir, "test");
+        config.mix = false;
+        start_recording(config).unwrap();
+
+        // Would deadlock if capturing waited for the lock
+        let recording = RECORDING.lock();
+        capture(Track::Microphone, &[0.5; 480], 1, 48000);
+        let taps = &recording.as_ref().unwrap().taps;
+        assert!(taps.pending.iter().all(Option::is_none));
+        drop(recording);
+
+        capture(Track::Microphone, &[0.5; 480], 1, 48000);
+        // The writer may have opened it already
+        let recording = RECORDING.lock();
+        let taps = &recording.as_ref().unwrap().taps;
+        assert!(taps.pending[0].is_some() || taps.open.contains_key(&Track::Microphone));
+        drop(recording);
+        stop_recording().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+use super::SampleSink;
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// RIFF/WAVE writer; chunk sizes are patched in once the recording finishes.
+pub struct WavWriter {
+    out: BufWriter<File>,
+    float: bool,
+    channels: u16,
+    data_bytes: u64,
+}
+
+impl WavWriter {
+    pub fn create(path: &Path, channels: u16, sample_rate: u32, float: bool) -> io::Result<Self> {
+        Self::from_file(File::create(path)?, channels, sample_rate, float)
+    }
+
+    /// Writes into a file that has already been opened and is empty.
+    pub fn from_file(file: File, channels: u16, sample_rate: u32, float: bool) -> io::Result<Self> {
+        let mut writer = WavWriter {
+            out: BufWriter::new(file),
+            float,
+            channels,
+            data_bytes: 0,
+        };
+        writer.write_header(sample_rate)?;
+        Ok(writer)
+    }
+
+    fn bytes_per_sample(&self) -> u16 {
+        if self.float {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn header_len(&self) -> u64 {
+        // Float data needs the extended fmt chunk and a fact chunk
+        if self.float {
+            58
+        } else {
+            44
+        }
+    }
+
+    fn write_header(&mut self, sample_rate: u32) -> io::Result<()> {
+        let bytes_per_sample = self.bytes_per_sample();
+        let block_align = self.channels * bytes_per_sample;
+        let out = &mut self.out;
+        out.write_all(b"RIFF")?;
+        out.write_all(&0u32.to_le_bytes())?;
+        out.write_all(b"WAVE")?;
+        out.write_all(b"fmt ")?;
+        out.write_all(&(if self.float { 18u32 } else { 16u32 }).to_le_bytes())?;
+        let format = if self.float {
+            FORMAT_IEEE_FLOAT
+        } else {
+            FORMAT_PCM
+        };
+        out.write_all(&format.to_le_bytes())?;
+        out.write_all(&self.channels.to_le_bytes())?;
+        out.write_all(&sample_rate.to_le_bytes())?;
+        out.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+        out.write_all(&block_align.to_le_bytes())?;
+        out.write_all(&(bytes_per_sample * 8).to_le_bytes())?;
+        if self.float {
+            out.write_all(&0u16.to_le_bytes())?;
+            out.write_all(b"fact")?;
+            out.write_all(&4u32.to_le_bytes())?;
+            out.write_all(&0u32.to_le_bytes())?;
+        }
+        out.write_all(b"data")?;
+        out.write_all(&0u32.to_le_bytes())?;
+        Ok(())
+    }
+}
+
+impl SampleSink for WavWriter {
+    fn write(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            if self.float {
+                self.out.write_all(&sample.to_le_bytes())?;
+            } else {
+                let sample = (sample * 32768.0).clamp(-32768.0, 32767.0) as i16;
+                self.out.write_all(&sample.to_le_bytes())?;
+            }
+        }
+        self.data_bytes += samples.len() as u64 * self.bytes_per_sample() as u64;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> io::Result<()> {
+        // RIFF sizes are 32-bit; saturate rather than wrap for over-long recordings
+        let header_len = self.header_len();
+        let data_bytes = self.data_bytes.min(u32::MAX as u64 - header_len) as u32;
+        let riff_bytes = data_bytes + header_len as u32 - 8;
+        let out = &mut self.out;
+        out.seek(SeekFrom::Start(4))?;
+        out.write_all(&riff_bytes.to_le_bytes())?;
+        if self.float {
+            let frames = data_bytes / (self.channels as u32 * 4);
+            out.seek(SeekFrom::Start(46))?;
+            out.write_all(&frames.to_le_bytes())?;
+        }
+        out.seek(SeekFrom::Start(header_len - 4))?;
+        out.write_all(&data_bytes.to_le_bytes())?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn written(test: &str, float: bool, samples: &[f32]) -> Vec<u8> {
+        let path =
+            std::env::temp_dir().join(format!("mumble-sys-{}-{}.wav", test, std::process::id()));
+        let mut writer = Box::new(WavWriter::create(&path, 2, 44100, float).unwrap());
+        writer.write(samples).unwrap();
+        writer.finish().unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        bytes
+    }
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    }
+
+    #[test]
+    fn pcm_header_describes_the_data() {
+        let bytes = written("wav-pcm", false, &[0.5, -0.5, 2.0, -2.0]);
+        assert_eq!(bytes.len(), 44 + 8);
+        assert_eq!(&bytes[..4], b"RIFF");
+        assert_eq!(u32_at(&bytes, 4), bytes.len() as u32 - 8);
+        assert_eq!(&bytes[8..16], b"WAVEfmt ");
+        assert_eq!(u32_at(&bytes, 16), 16);
+        assert_eq!(u16_at(&bytes, 20), FORMAT_PCM);
+        assert_eq!(u16_at(&bytes, 22), 2);
+        assert_eq!(u32_at(&bytes, 24), 44100);
+        assert_eq!(u32_at(&bytes, 28), 44100 * 4);
+        assert_eq!(u16_at(&bytes, 32), 4);
+        assert_eq!(u16_at(&bytes, 34), 16);
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(u32_at(&bytes, 40), 8);
+        // Out-of-range samples clip instead of wrapping
+        let samples: Vec<i16> = bytes[44..]
+            .chunks(2)
+            .map(|s| i16::from_le_bytes([s[0], s[1]]))
+            .collect();
+        assert_eq!(samples, vec![16384, -16384, 32767, -32768]);
+    }
+
+    #[test]
+    fn float_header_has_a_fact_chunk() {
+        let samples = [0.25, -0.75, 1.5, 0.0, 0.125, -0.125];
+        let bytes = written("wav-float", true, &samples);
+        assert_eq!(bytes.len(), 58 + 24);
+        assert_eq!(u32_at(&bytes, 4), bytes.len() as u32 - 8);
+        assert_eq!(u32_at(&bytes, 16), 18);
+        assert_eq!(u16_at(&bytes, 20), FORMAT_IEEE_FLOAT);
+        assert_eq!(u16_at(&bytes, 34), 32);
+        assert_eq!(&bytes[38..42], b"fact");
+        assert_eq!(u32_at(&bytes, 46), 3);
+        assert_eq!(&bytes[50..54], b"data");
+        assert_eq!(u32_at(&bytes, 54), 24);
+
+        let clip = crate::assets::decode(&bytes).unwrap();
+        assert_eq!((clip.channels(), clip.sample_rate()), (2, 44100));
+        assert_eq!(clip.samples(), &samples);
+    }
+}
@@ -0,0 +1,148 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Single-producer single-consumer sample queue shared between an audio
+/// callback and another thread. Neither side ever blocks.
+struct Ring {
+    buffer: Box<[UnsafeCell<f32>]>,
+    mask: usize,
+    // Both indices grow forever and wrap; slots are `index & mask`
+    write: AtomicUsize,
+    read: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+unsafe impl Sync for Ring {}
+unsafe impl Send for Ring {}
+
+pub(crate) struct Producer {
+    ring: Arc<Ring>,
+}
+
+pub(crate) struct Consumer {
+    ring: Arc<Ring>,
+}
+
+pub(crate) fn channel(min_capacity: usize) -> (Producer, Consumer) {
+    let capacity = min_capacity.max(2).next_power_of_two();
+    let ring = Arc::new(Ring {
+        buffer: (0..capacity).map(|_| UnsafeCell::new(0f32)).collect(),
+        mask: capacity - 1,
+        write: AtomicUsize::new(0),
+        read: AtomicUsize::new(0),
+        dropped: AtomicUsize::new(0),
+    });
+    (Producer { ring: ring.clone() }, Consumer { ring })
+}
+
+impl Producer {
+    /// Samples which can currently be pushed without being dropped
+    pub fn free(&self) -> usize {
+        let ring = &*self.ring;
+        let write = ring.write.load(Ordering::Relaxed);
+        let read = ring.read.load(Ordering::Acquire);
+        ring.buffer.len() - write.wrapping_sub(read)
+    }
+
+    /// Queues all of `samples`, or none of them if they don't fit, so frames never tear.
+    pub fn push<I>(&mut self, samples: I) -> bool
+    where
+        I: ExactSizeIterator<Item = f32>,
+    {
+        let ring = &*self.ring;
+        let len = samples.len();
+        let write = ring.write.load(Ordering::Relaxed);
+        let read = ring.read.load(Ordering::Acquire);
+        let free = ring.buffer.len() - write.wrapping_sub(read);
+        if len > free {
+            ring.dropped.fetch_add(len, Ordering::Relaxed);
+            return false;
+        }
+        // Never trust the length so far as to write past it, into slots not yet read
+        for (offset, sample) in samples.take(len).enumerate() {
+            let slot = &ring.buffer[write.wrapping_add(offset) & ring.mask];
+            unsafe { *slot.get() = sample };
+        }
+        ring.write.store(write.wrapping_add(len), Ordering::Release);
+        true
+    }
+}
+
+impl Consumer {
+    /// Moves as many queued samples as fit into `out`, returning how many were moved.
+    pub fn pop(&mut self, out: &mut [f32]) -> usize {
+        let ring = &*self.ring;
+        let read = ring.read.load(Ordering::Relaxed);
+        let write = ring.write.load(Ordering::Acquire);
+        let available = write.wrapping_sub(read).min(out.len());
+        for (offset, sample) in out[..available].iter_mut().enumerate() {
+            let slot = &ring.buffer[read.wrapping_add(offset) & ring.mask];
+            *sample = unsafe { *slot.get() };
+        }
+        ring.read
+            .store(read.wrapping_add(available), Ordering::Release);
+        available
+    }
+
+    /// Samples discarded because the writer fell behind
+    pub fn dropped(&self) -> usize {
+        self.ring.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn pushes_whole_batches_or_nothing() {
+        let (mut producer, mut consumer) = channel(5);
+        assert_eq!(producer.free(), 8);
+        assert!(producer.push([1.0, 2.0, 3.0, 4.0, 5.0].iter().cloned()));
+        assert!(!producer.push([6.0, 7.0, 8.0, 9.0].iter().cloned()));
+        assert_eq!(consumer.dropped(), 4);
+        assert!(producer.push([6.0, 7.0, 8.0].iter().cloned()));
+        assert_eq!(producer.free(), 0);
+
+        let mut out = [0.0; 3];
+        assert_eq!(consumer.pop(&mut out), 3);
+        assert_eq!(out, [1.0, 2.0, 3.0]);
+        // Wraps around the end of the buffer
+        assert!(producer.push([9.0, 10.0].iter().cloned()));
+        let mut out = [0.0; 16];
+        assert_eq!(consumer.pop(&mut out), 7);
+        assert_eq!(out[..7], [4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+        assert_eq!(consumer.pop(&mut out), 0);
+    }
+
+    #[test]
+    fn keeps_order_across_threads() {
+        let (mut producer, mut consumer) = channel(64);
+        let writer = thread::spawn(move || {
+            let mut next = 0;
+            while next < 10_000 {
+                let batch = (next..next + 10).map(|i| i as f32);
+                if producer.push(batch) {
+                    next += 10;
+                } else {
+                    thread::yield_now();
+                }
+            }
+        });
+        let mut expected = 0;
+        let mut out = [0.0; 17];
+        while expected < 10_000 {
+            let read = consumer.pop(&mut out);
+            for sample in &out[..read] {
+                assert_eq!(*sample, expected as f32);
+                expected += 1;
+            }
+            if read == 0 {
+                thread::yield_now();
+            }
+        }
+        writer.join().unwrap();
+    }
+}
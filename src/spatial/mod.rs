@@ -0,0 +1,292 @@
+//! Stereo placement of speakers on servers without positional audio.
+//!
+//! Each speaking user is given an azimuth and distance, either from a layout or
+//! set by hand, and their audio is panned with level, delay and head-shadow cues.
+//! Mono output is left untouched.
+
+mod pan;
+
+pub use pan::Placement;
+
+use crate::ids::Scoped;
+use crate::types as m;
+use pan::Panner;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Layout {
+    /// Users spread evenly across `spread` degrees, in the order they appeared
+    UserOrder { spread: f32 },
+    /// Each channel gets its own direction across `spread` degrees, with its users
+    /// clustered around it
+    ByChannel { spread: f32 },
+}
+
+/// Users are scoped to their connection, and each server is laid out on its own
+struct Spatial {
+    layout: Layout,
+    order: Vec<Scoped<m::UserIdT>>,
+    channels: BTreeMap<Scoped<m::UserIdT>, m::ChannelIdT>,
+    manual: BTreeMap<Scoped<m::UserIdT>, Placement>,
+    panners: BTreeMap<Scoped<m::UserIdT>, Panner>,
+}
+
+static SPATIAL: Mutex<Spatial> = Mutex::new(Spatial {
+    layout: Layout::UserOrder { spread: 120.0 },
+    order: Vec::new(),
+    channels: BTreeMap::new(),
+    manual: BTreeMap::new(),
+    panners: BTreeMap::new(),
+});
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+fn spread_position(index: usize, count: usize, spread: f32) -> f32 {
+    if count <= 1 {
+        0.0
+    } else {
+        -spread / 2.0 + spread * index as f32 / (count - 1) as f32
+    }
+}
+
+impl Spatial {
+    fn placement(&self, user: Scoped<m::UserIdT>) -> Option<Placement> {
+        if let Some(placement) = self.manual.get(&user) {
+            return Some(*placement);
+        }
+        // Counted in place rather than collected; this runs on the audio thread
+        let server = || {
+            self.order
+                .iter()
+                .filter(move |known| known.same_connection(&user))
+        };
+        let index = server().position(|known| *known == user)?;
+        let azimuth = match self.layout {
+            Layout::UserOrder { spread } => spread_position(index, server().count(), spread),
+            Layout::ByChannel { spread } => {
+                let channel_of = |user: &Scoped<m::UserIdT>| self.channels.get(user).cloned();
+                let channel = channel_of(&user);
+                let first_in_channel = |(index, other): (usize, &Scoped<m::UserIdT>)| {
+                    let other = channel_of(other);
+                    !server().take(index).any(|prior| channel_of(prior) == other)
+                };
+                let channel_count = server()
+                    .enumerate()
+                    .filter(|e| first_in_channel(*e))
+                    .count();
+                let slot = server()
+                    .enumerate()
+                    .filter(|e| first_in_channel(*e) && channel_of(e.1) < channel)
+                    .count();
+                let members = server().filter(|other| channel_of(other) == channel);
+                let member = members
+                    .clone()
+                    .position(|other| *other == user)
+                    .unwrap_or(0);
+                let slot_width = spread / channel_count as f32;
+                spread_position(slot, channel_count, spread)
+                    + spread_position(member, members.count(), slot_width / 2.0)
+            }
+        };
+        Some(Placement::new(azimuth, 1.0))
+    }
+
+    fn note_user(&mut self, user: Scoped<m::UserIdT>) {
+        if !self.order.contains(&user) {
+            self.order.push(user);
+        }
+    }
+}
+
+pub fn enable(layout: Layout) {
+    SPATIAL.lock().layout = layout;
+    ENABLED.store(true, Ordering::Release);
+}
+
+pub fn disable() {
+    ENABLED.store(false, Ordering::Release);
+    SPATIAL.lock().panners.clear();
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+/// Changes the layout; speakers glide to their new positions.
+pub fn set_layout(layout: Layout) {
+    SPATIAL.lock().layout = layout;
+}
+
+/// Pins `user` to a position, overriding the layout.
+pub fn place_user(user: Scoped<m::UserIdT>, placement: Placement) {
+    SPATIAL.lock().manual.insert(user, placement);
+}
+
+/// Returns `user` to their layout position.
+pub fn unplace_user(user: Scoped<m::UserIdT>) {
+    SPATIAL.lock().manual.remove(&user);
+}
+
+/// Where `user` is currently placed, if they are pinned or known to the layout.
+pub fn placement_of(user: Scoped<m::UserIdT>) -> Option<Placement> {
+    SPATIAL.lock().placement(user)
+}
+
+pub(crate) fn process_user(
+    user: Scoped<m::UserIdT>,
+    pcm: &mut [f32],
+    channel_count: u16,
+    sample_rate: u32,
+) -> bool {
+    if !is_enabled() || channel_count < 2 {
+        return false;
+    }
+    let mut spatial = SPATIAL.lock();
+    spatial.note_user(user);
+    let placement = match spatial.placement(user) {
+        Some(placement) => placement,
+        None => return false,
+    };
+    spatial
+        .panners
+        .entry(user)
+        .or_insert_with(Panner::new)
+        .process(pcm, channel_count, sample_rate, placement);
+    true
+}
+
+pub(crate) fn user_added(user: Scoped<m::UserIdT>) {
+    SPATIAL.lock().note_user(user);
+}
+
+pub(crate) fn user_removed(user: Scoped<m::UserIdT>) {
+    let mut spatial = SPATIAL.lock();
+    spatial.order.retain(|known| *known != user);
+    spatial.channels.remove(&user);
+    spatial.panners.remove(&user);
+}
+
+pub(crate) fn channel_entered(user: Scoped<m::UserIdT>, channel: m::ChannelIdT) {
+    SPATIAL.lock().channels.insert(user, channel);
+}
+
+/// Forgets the users of a server that disconnected, keeping the layout, pinned placements
+/// and the users of any other server.
+pub(crate) fn server_disconnected(connection: m::ConnectionT) {
+    let mut spatial = SPATIAL.lock();
+    spatial.order.retain(|user| user.connection() != connection);
+    spatial
+        .channels
+        .retain(|user, _| user.connection() != connection);
+    spatial
+        .panners
+        .retain(|user, _| user.connection() != connection);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Placement is global, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    const SERVER: m::ConnectionT = m::ConnectionT(1);
+
+    fn user(id: u32) -> Scoped<m::UserIdT> {
+        m::UserIdT(id).on(SERVER)
+    }
+
+    fn azimuth(id: u32) -> Option<f32> {
+        placement_of(user(id)).map(|placement| placement.azimuth)
+    }
+
+    #[test]
+    fn users_spread_in_the_order_they_appeared() {
+        let _serial = SERIAL.lock();
+        server_disconnected(SERVER);
+        set_layout(Layout::UserOrder { spread: 120.0 });
+        for user in &[3, 1, 2] {
+            user_added(self::user(*user));
+        }
+        user_added(user(1));
+        assert_eq!(azimuth(3), Some(-60.0));
+        assert_eq!(azimuth(1), Some(0.0));
+        assert_eq!(azimuth(2), Some(60.0));
+        assert_eq!(azimuth(4), None);
+
+        place_user(user(4), Placement::new(180.0, 2.0));
+        assert_eq!(azimuth(4), Some(180.0));
+        user_removed(user(1));
+        assert_eq!(azimuth(2), Some(60.0));
+        assert_eq!(azimuth(3), Some(-60.0));
+        server_disconnected(SERVER);
+        assert_eq!(azimuth(3), None);
+        assert_eq!(azimuth(4), Some(180.0));
+        unplace_user(user(4));
+        assert_eq!(azimuth(4), None);
+    }
+
+    #[test]
+    fn channels_get_a_direction_each() {
+        let _serial = SERIAL.lock();
+        server_disconnected(SERVER);
+        set_layout(Layout::ByChannel { spread: 120.0 });
+        for (user, channel) in &[(1, 10), (2, 10), (3, 5)] {
+            user_added(self::user(*user));
+            channel_entered(self::user(*user), m::ChannelIdT(*channel));
+        }
+        assert_eq!(azimuth(3), Some(-60.0));
+        assert_eq!(azimuth(1), Some(45.0));
+        assert_eq!(azimuth(2), Some(75.0));
+
+        channel_entered(user(2), m::ChannelIdT(5));
+        assert_eq!(azimuth(1), Some(60.0));
+        assert_eq!(azimuth(2), Some(-75.0));
+        assert_eq!(azimuth(3), Some(-45.0));
+        server_disconnected(SERVER);
+    }
+
+    #[test]
+    fn only_stereo_is_processed_while_enabled() {
+        let _serial = SERIAL.lock();
+        server_disconnected(SERVER);
+        set_layout(Layout::UserOrder { spread: 90.0 });
+        let singer = user(9);
+        let mut pcm = vec![0.25; 96];
+        assert!(!process_user(singer, &mut pcm, 2, 48000));
+        assert_eq!(azimuth(9), None);
+
+        enable(Layout::UserOrder { spread: 90.0 });
+        assert!(!process_user(singer, &mut pcm, 1, 48000));
+        assert!(process_user(singer, &mut pcm, 2, 48000));
+        assert!(is_enabled());
+        assert_eq!(azimuth(9), Some(0.0));
+        disable();
+        assert!(!is_enabled());
+        server_disconnected(SERVER);
+    }
+
+    #[test]
+    fn each_server_is_laid_out_on_its_own() {
+        let _serial = SERIAL.lock();
+        server_disconnected(SERVER);
+        set_layout(Layout::UserOrder { spread: 120.0 });
+        let elsewhere = |id| m::UserIdT(id).on(m::ConnectionT(2));
+        user_added(user(1));
+        user_added(user(2));
+        user_added(elsewhere(1));
+        assert_eq!(azimuth(1), Some(-60.0));
+        assert_eq!(azimuth(2), Some(60.0));
+        assert_eq!(
+            placement_of(elsewhere(1)).map(|placement| placement.azimuth),
+            Some(0.0)
+        );
+
+        server_disconnected(SERVER);
+        assert_eq!(azimuth(1), None);
+        assert!(placement_of(elsewhere(1)).is_some());
+        server_disconnected(m::ConnectionT(2));
+        assert!(placement_of(elsewhere(1)).is_none());
+    }
+}
@@ -0,0 +1,222 @@
+use std::f32::consts::{PI, SQRT_2};
+
+const HEAD_RADIUS_M: f32 = 0.0875;
+const SPEED_OF_SOUND_M_S: f32 = 343.0;
+const MAX_DELAY_SAMPLES: usize = 64;
+const SHADOW_CUTOFF_HZ: f32 = 2500.0;
+const REAR_CUTOFF_HZ: f32 = 6000.0;
+const MIN_DISTANCE: f32 = 0.25;
+
+/// Where a speaker sits relative to the listener.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Placement {
+    /// Degrees clockwise from straight ahead: -90 is hard left, 90 hard right, 180 behind
+    pub azimuth: f32,
+    /// Relative to the reference distance of 1.0; level falls off as 1/distance
+    pub distance: f32,
+}
+
+impl Placement {
+    pub fn new(azimuth: f32, distance: f32) -> Self {
+        Placement { azimuth, distance }
+    }
+
+    pub fn ahead() -> Self {
+        Self::new(0.0, 1.0)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+struct Target {
+    left_gain: f32,
+    right_gain: f32,
+    // Positive delays the left ear, negative the right
+    delay_samples: f32,
+    far_ear_coefficient: f32,
+    rear_coefficient: f32,
+}
+
+fn one_pole_coefficient(cutoff_hz: f32, sample_rate: u32) -> f32 {
+    1.0 - (-2.0 * PI * cutoff_hz.min(sample_rate as f32 * 0.45) / sample_rate as f32).exp()
+}
+
+fn target(placement: Placement, sample_rate: u32) -> Target {
+    // Wrap to -180..=180, then fold rear positions onto the front arc
+    let azimuth = (placement.azimuth + 180.0).rem_euclid(360.0) - 180.0;
+    let (lateral, behind) = if azimuth > 90.0 {
+        (180.0 - azimuth, true)
+    } else if azimuth < -90.0 {
+        (-180.0 - azimuth, true)
+    } else {
+        (azimuth, false)
+    };
+    let lateral = lateral.to_radians();
+    // Constant-power pan law, normalised so a centred speaker keeps its original level
+    let theta = (lateral + PI / 2.0) / 2.0;
+    let distance_gain = SQRT_2 / placement.distance.max(MIN_DISTANCE);
+    // Woodworth's spherical-head interaural time difference
+    let itd = HEAD_RADIUS_M / SPEED_OF_SOUND_M_S * (lateral.abs().sin() + lateral.abs());
+    let delay = (itd * sample_rate as f32).min(MAX_DELAY_SAMPLES as f32 - 2.0);
+    // Fade from an unfiltered far ear straight ahead to full head shadow at the side
+    let shadow = lateral.abs() / (PI / 2.0);
+    let shadowed = one_pole_coefficient(SHADOW_CUTOFF_HZ, sample_rate);
+    Target {
+        left_gain: theta.cos() * distance_gain,
+        right_gain: theta.sin() * distance_gain,
+        delay_samples: if lateral > 0.0 { delay } else { -delay },
+        far_ear_coefficient: 1.0 + (shadowed - 1.0) * shadow,
+        rear_coefficient: if behind {
+            one_pole_coefficient(REAR_CUTOFF_HZ, sample_rate)
+        } else {
+            1.0
+        },
+    }
+}
+
+/// Places a mono source in stereo with level, time and head-shadow cues.
+/// Parameter changes are ramped across a buffer so moving speakers don't click.
+pub(super) struct Panner {
+    current: Option<Target>,
+    history: [f32; MAX_DELAY_SAMPLES],
+    write: usize,
+    far_ear_state: f32,
+    rear_state: f32,
+}
+
+impl Panner {
+    pub fn new() -> Self {
+        Panner {
+            current: None,
+            history: [0.0; MAX_DELAY_SAMPLES],
+            write: 0,
+            far_ear_state: 0.0,
+            rear_state: 0.0,
+        }
+    }
+
+    fn delayed(&self, delay: f32) -> f32 {
+        let whole = delay.floor();
+        let fraction = delay - whole;
+        let newest = self.write + MAX_DELAY_SAMPLES - 1;
+        let a = self.history[(newest - whole as usize) % MAX_DELAY_SAMPLES];
+        let b = self.history[(newest - whole as usize - 1) % MAX_DELAY_SAMPLES];
+        a + (b - a) * fraction
+    }
+
+    /// Replaces interleaved `pcm` (at least two channels) with the panned downmix.
+    /// Channels beyond the first two are silenced.
+    pub fn process(
+        &mut self,
+        pcm: &mut [f32],
+        channel_count: u16,
+        sample_rate: u32,
+        placement: Placement,
+    ) {
+        let channels = channel_count as usize;
+        if channels < 2 || pcm.len() < channels {
+            return;
+        }
+        let frames = pcm.len() / channels;
+        let end = target(placement, sample_rate);
+        let start = *self.current.get_or_insert(end);
+        let scale = 1.0 / channels as f32;
+        for (index, frame) in pcm.chunks_exact_mut(channels).enumerate() {
+            let t = (index + 1) as f32 / frames as f32;
+            let lerp = |from: f32, to: f32| from + (to - from) * t;
+            let mono = frame.iter().sum::<f32>() * scale;
+            self.rear_state +=
+                lerp(start.rear_coefficient, end.rear_coefficient) * (mono - self.rear_state);
+            self.history[self.write] = self.rear_state;
+            self.write = (self.write + 1) % MAX_DELAY_SAMPLES;
+
+            let delay = lerp(start.delay_samples, end.delay_samples);
+            let near = self.delayed(0.0);
+            let far = self.delayed(delay.abs());
+            self.far_ear_state += lerp(start.far_ear_coefficient, end.far_ear_coefficient)
+                * (far - self.far_ear_state);
+            let (left, right) = if delay >= 0.0 {
+                (self.far_ear_state, near)
+            } else {
+                (near, self.far_ear_state)
+            };
+            frame[0] = left * lerp(start.left_gain, end.left_gain);
+            frame[1] = right * lerp(start.right_gain, end.right_gain);
+            for other in frame[2..].iter_mut() {
+                *other = 0.0;
+            }
+        }
+        self.current = Some(end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f32, expected: f32) -> bool {
+        (actual - expected).abs() < 1e-4
+    }
+
+    #[test]
+    fn targets_follow_the_placement() {
+        let ahead = target(Placement::ahead(), 48000);
+        assert!(close(ahead.left_gain, 1.0) && close(ahead.right_gain, 1.0));
+        assert_eq!(ahead.delay_samples, 0.0);
+        assert_eq!(ahead.far_ear_coefficient, 1.0);
+        assert_eq!(ahead.rear_coefficient, 1.0);
+
+        let right = target(Placement::new(90.0, 1.0), 48000);
+        assert!(close(right.left_gain, 0.0) && close(right.right_gain, SQRT_2));
+        assert!(right.delay_samples > 30.0 && right.delay_samples < 32.0);
+        assert!(right.far_ear_coefficient < 0.5);
+        assert!(target(Placement::new(-90.0, 1.0), 48000).delay_samples < -30.0);
+        assert!(target(Placement::new(450.0, 1.0), 48000) == right);
+
+        let behind = target(Placement::new(180.0, 1.0), 48000);
+        assert!(close(behind.left_gain, 1.0));
+        assert!(behind.rear_coefficient < 1.0);
+        let far = target(Placement::new(0.0, 2.0), 48000);
+        assert!(close(far.left_gain, 0.5));
+        let near = target(Placement::new(0.0, 0.0), 48000);
+        assert!(close(near.left_gain, 1.0 / MIN_DISTANCE));
+    }
+
+    #[test]
+    fn centred_speakers_keep_their_level() {
+        let mut panner = Panner::new();
+        let mut pcm = vec![0.5; 960];
+        panner.process(&mut pcm, 2, 48000, Placement::ahead());
+        assert!(pcm[200..].iter().all(|sample| close(*sample, 0.5)));
+
+        // Mono is left alone; extra channels are silenced
+        let mut mono = vec![0.5; 480];
+        panner.process(&mut mono, 1, 48000, Placement::ahead());
+        assert!(mono.iter().all(|sample| *sample == 0.5));
+        let mut surround = vec![0.5; 1440];
+        panner.process(&mut surround, 3, 48000, Placement::ahead());
+        assert!(surround.chunks(3).all(|frame| frame[2] == 0.0));
+    }
+
+    #[test]
+    fn the_near_ear_hears_first_and_loudest() {
+        let mut panner = Panner::new();
+        let mut pcm = vec![0.0; 2 * 128];
+        pcm[0] = 1.0;
+        pcm[1] = 1.0;
+        panner.process(&mut pcm, 2, 48000, Placement::new(60.0, 1.0));
+        let loudest = |channel: usize| {
+            (0..128)
+                .max_by(|&a, &b| {
+                    pcm[2 * a + channel]
+                        .abs()
+                        .partial_cmp(&pcm[2 * b + channel].abs())
+                        .unwrap()
+                })
+                .unwrap()
+        };
+        let (left, right) = (loudest(0), loudest(1));
+        assert_eq!(right, 0);
+        assert!(left > 20, "{}", left);
+        assert!(pcm[1] > pcm[2 * left].abs());
+    }
+}
@@ -0,0 +1,576 @@
+//! Key-value storage for plugins, namespaced by server and by user.
+//!
+//! Servers are identified by `get_server_hash` and users by their certificate hash
+//! (`get_user_hash`), which unlike connection and user IDs stay the same between sessions.
+//! Each server's values, and its users', are kept in one JSON file in the storage
+//! directory. Files are read when first needed, or as soon as a server finishes
+//! synchronizing, and rewritten on every change.
+
+use crate::config::{plugin_dir, write_atomically};
+use crate::traits::MumblePluginDescriptor;
+use crate::types as m;
+use crate::MumbleAPI;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+/// Written into exports, so later versions can read older ones
+const EXPORT_VERSION: u64 = 1;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(io::Error),
+    Malformed(String),
+    /// `open` hasn't been called, or `close` has
+    NotOpen,
+    /// No configuration directory could be determined for this platform
+    NoStorageDir,
+    /// The user has no certificate, so there is no hash to store their values under
+    Unidentified,
+    Mumble(m::ErrorT),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "I/O error: {}", e),
+            StorageError::Malformed(reason) => write!(f, "Malformed storage: {}", reason),
+            StorageError::NotOpen => write!(f, "Storage is not open"),
+            StorageError::NoStorageDir => write!(f, "No storage directory available"),
+            StorageError::Unidentified => write!(f, "User has no certificate"),
+            StorageError::Mumble(e) => write!(f, "Mumble error: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+impl From<m::ErrorT> for StorageError {
+    fn from(e: m::ErrorT) -> Self {
+        StorageError::Mumble(e)
+    }
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+fn malformed(e: impl fmt::Display) -> StorageError {
+    StorageError::Malformed(e.to_string())
+}
+
+type Values = BTreeMap<String, Value>;
+
+/// Removes and deserializes `name`, defaulting if it's absent
+fn field<T: DeserializeOwned + Default>(
+    object: &mut Map<String, Value>,
+    name: &str,
+) -> StorageResult<T> {
+    object
+        .remove(name)
+        .map_or(Ok(T::default()), serde_json::from_value)
+        .map_err(malformed)
+}
+
+#[derive(Debug, Default, Clone)]
+struct ServerData {
+    values: Values,
+    /// By certificate hash
+    users: BTreeMap<String, Values>,
+}
+
+impl ServerData {
+    fn to_json(&self, server_hash: &str) -> Value {
+        json!({
+            "server_hash": server_hash,
+            "values": self.values,
+            "users": self.users,
+        })
+    }
+
+    /// The server hash and data from `to_json`'s output
+    fn from_json(value: Value) -> StorageResult<(String, ServerData)> {
+        let mut object = match value {
+            Value::Object(object) => object,
+            _ => return Err(malformed("expected an object")),
+        };
+        let server_hash = match object.remove("server_hash") {
+            Some(Value::String(hash)) => hash,
+            _ => return Err(malformed("missing `server_hash`")),
+        };
+        let data = ServerData {
+            values: field(&mut object, "values")?,
+            users: field(&mut object, "users")?,
+        };
+        Ok((server_hash, data))
+    }
+
+    fn merge(&mut self, other: ServerData) {
+        self.values.extend(other.values);
+        for (user, values) in other.users {
+            self.users.entry(user).or_default().extend(values);
+        }
+    }
+}
+
+struct Storage {
+    dir: PathBuf,
+    /// Loaded servers, by hash
+    servers: BTreeMap<String, ServerData>,
+    connections: BTreeMap<m::ConnectionT, String>,
+}
+
+impl Storage {
+    fn path(&self, server_hash: &str) -> PathBuf {
+        let name: String = server_hash
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+
+    fn server(&mut self, server_hash: &str) -> StorageResult<&mut ServerData> {
+        if !self.servers.contains_key(server_hash) {
+            let data = match fs::read_to_string(self.path(server_hash)) {
+                Ok(text) => {
+                    let value = serde_json::from_str(&text).map_err(malformed)?;
+                    ServerData::from_json(value)?.1
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => ServerData::default(),
+                Err(e) => return Err(e.into()),
+            };
+            self.servers.insert(server_hash.to_string(), data);
+        }
+        Ok(self.servers.get_mut(server_hash).unwrap())
+    }
+
+    fn save(&self, server_hash: &str) -> StorageResult<()> {
+        let data = match self.servers.get(server_hash) {
+            Some(data) => data,
+            None => return Ok(()),
+        };
+        let text = serde_json::to_string_pretty(&data.to_json(server_hash)).map_err(malformed)?;
+        write_atomically(&self.path(server_hash), text.as_bytes())?;
+        Ok(())
+    }
+
+    /// Every server with stored values, from disk
+    fn stored_servers(&self) -> StorageResult<Vec<(String, ServerData)>> {
+        let mut servers = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "json")
+            {
+                continue;
+            }
+            let value = serde_json::from_str(&fs::read_to_string(&path)?).map_err(malformed)?;
+            servers.push(ServerData::from_json(value)?);
+        }
+        Ok(servers)
+    }
+}
+
+static STORAGE: Mutex<Option<Storage>> = Mutex::new(None);
+
+fn with_storage<T>(f: impl FnOnce(&mut Storage) -> StorageResult<T>) -> StorageResult<T> {
+    match &mut *STORAGE.lock() {
+        Some(storage) => f(storage),
+        None => Err(StorageError::NotOpen),
+    }
+}
+
+/// Keeps values in `dir`, creating it if needed.
+pub fn open(dir: impl Into<PathBuf>) -> StorageResult<()> {
+    let dir = dir.into();
+    fs::create_dir_all(&dir)?;
+    *STORAGE.lock() = Some(Storage {
+        dir,
+        servers: BTreeMap::new(),
+        connections: BTreeMap::new(),
+    });
+    Ok(())
+}
+
+/// Keeps values in `storage` under `config::plugin_dir::<P>()`.
+pub fn open_for_plugin<P: MumblePluginDescriptor>() -> StorageResult<()> {
+    let dir = plugin_dir::<P>().ok_or(StorageError::NoStorageDir)?;
+    open(dir.join("storage"))
+}
+
+pub fn close() {
+    *STORAGE.lock() = None;
+}
+
+pub fn is_open() -> bool {
+    STORAGE.lock().is_some()
+}
+
+pub fn dir() -> Option<PathBuf> {
+    STORAGE.lock().as_ref().map(|storage| storage.dir.clone())
+}
+
+/// Where a value is stored: a server, or a user on a server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Namespace {
+    server: String,
+    user: Option<String>,
+}
+
+impl Namespace {
+    pub fn server(server_hash: impl Into<String>) -> Self {
+        Namespace {
+            server: server_hash.into(),
+            user: None,
+        }
+    }
+
+    pub fn user(server_hash: impl Into<String>, user_hash: impl Into<String>) -> Self {
+        Namespace {
+            server: server_hash.into(),
+            user: Some(user_hash.into()),
+        }
+    }
+
+    /// The namespace of the server on `connection`.
+    pub fn of_server(api: &mut MumbleAPI, connection: m::ConnectionT) -> StorageResult<Self> {
+        let known = STORAGE
+            .lock()
+            .as_ref()
+            .and_then(|storage| storage.connections.get(&connection).cloned());
+        match known {
+            Some(server_hash) => Ok(Namespace::server(server_hash)),
+            None => Ok(Namespace::server(api.get_server_hash(connection)?)),
+        }
+    }
+
+    /// The namespace of `user` on the server on `connection`.
+    pub fn of_user(
+        api: &mut MumbleAPI,
+        connection: m::ConnectionT,
+        user: m::UserIdT,
+    ) -> StorageResult<Self> {
+        let server = Namespace::of_server(api, connection)?;
+        let user_hash = api.get_user_hash(connection, user)?;
+        if user_hash.is_empty() {
+            return Err(StorageError::Unidentified);
+        }
+        Ok(Namespace::user(server.server, user_hash))
+    }
+
+    pub fn server_hash(&self) -> &str {
+        &self.server
+    }
+
+    pub fn user_hash(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// The server's own namespace, for a user's
+    pub fn server_namespace(&self) -> Namespace {
+        Namespace::server(self.server.clone())
+    }
+
+    fn read<T>(&self, f: impl FnOnce(Option<&Values>) -> T) -> StorageResult<T> {
+        with_storage(|storage| {
+            let data = storage.server(&self.server)?;
+            Ok(match &self.user {
+                Some(user) => f(data.users.get(user)),
+                None => f(Some(&data.values)),
+            })
+        })
+    }
+
+    /// Changes the values and saves them if `f` returns true.
+    fn modify<T>(&self, f: impl FnOnce(&mut Values) -> (T, bool)) -> StorageResult<T> {
+        with_storage(|storage| {
+            let data = storage.server(&self.server)?;
+            let values = match &self.user {
+                Some(user) => data.users.entry(user.clone()).or_default(),
+                None => &mut data.values,
+            };
+            let (result, changed) = f(values);
+            if let Some(user) = &self.user {
+                if data.users.get(user).map_or(false, BTreeMap::is_empty) {
+                    data.users.remove(user);
+                }
+            }
+            if changed {
+                storage.save(&self.server)?;
+            }
+            Ok(result)
+        })
+    }
+
+    /// The value under `key`, or `None` if there is none. Fails if it doesn't deserialize as `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> StorageResult<Option<T>> {
+        let value = self.read(|values| values.and_then(|values| values.get(key)).cloned())?;
+        value
+            .map(|value| serde_json::from_value(value).map_err(malformed))
+            .transpose()
+    }
+
+    pub fn set<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> StorageResult<()> {
+        let value = serde_json::to_value(value).map_err(malformed)?;
+        self.modify(|values| {
+            let changed = values.get(key) != Some(&value);
+            values.insert(key.to_string(), value);
+            ((), changed)
+        })
+    }
+
+    /// Whether there was a value to remove
+    pub fn remove(&self, key: &str) -> StorageResult<bool> {
+        self.modify(|values| {
+            let removed = values.remove(key).is_some();
+            (removed, removed)
+        })
+    }
+
+    pub fn keys(&self) -> StorageResult<Vec<String>> {
+        self.read(|values| values.map_or_else(Vec::new, |values| values.keys().cloned().collect()))
+    }
+}
+
+/// Writes every stored value as one JSON document.
+pub fn export(mut writer: impl Write) -> StorageResult<()> {
+    let servers = with_storage(|storage| storage.stored_servers())?;
+    let servers: Vec<Value> = servers
+        .iter()
+        .map(|(server_hash, data)| data.to_json(server_hash))
+        .collect();
+    let document = json!({ "version": EXPORT_VERSION, "servers": servers });
+    serde_json::to_writer_pretty(&mut writer, &document).map_err(malformed)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a document written by `export`. Imported values replace stored ones under the
+/// same key; with `replace`, the servers in the document lose all their other values too.
+pub fn import(reader: impl Read, replace: bool) -> StorageResult<()> {
+    let document: Value = serde_json::from_reader(reader).map_err(malformed)?;
+    match document.get("version").and_then(Value::as_u64) {
+        Some(version) if version <= EXPORT_VERSION => {}
+        _ => return Err(malformed("unsupported export version")),
+    }
+    let servers = match document.get("servers") {
+        Some(Value::Array(servers)) => servers.clone(),
+        _ => return Err(malformed("missing `servers`")),
+    };
+    let servers = servers
+        .into_iter()
+        .map(ServerData::from_json)
+        .collect::<StorageResult<Vec<_>>>()?;
+    with_storage(|storage| {
+        for (server_hash, imported) in servers {
+            let data = storage.server(&server_hash)?;
+            if replace {
+                *data = imported;
+            } else {
+                data.merge(imported);
+            }
+            storage.save(&server_hash)?;
+        }
+        Ok(())
+    })
+}
+
+/// Loads the server's values and remembers its hash for `connection`
+pub(crate) fn server_synchronized(api: &mut MumbleAPI, connection: m::ConnectionT) {
+    if !is_open() {
+        return;
+    }
+    let server_hash = match api.get_server_hash(connection) {
+        Ok(server_hash) => server_hash,
+        Err(e) => {
+            log::warn!("Could not identify the server for storage: {:?}", e);
+            return;
+        }
+    };
+    let loaded = with_storage(|storage| {
+        storage.connections.insert(connection, server_hash.clone());
+        storage.server(&server_hash).map(|_| ())
+    });
+    if let Err(e) = loaded {
+        log::warn!("Could not load stored values for {}: {}", server_hash, e);
+    }
+}
+
+/// Forgets the connection, and the server's values unless another connection uses them
+pub(crate) fn server_disconnected(connection: m::ConnectionT) {
+    if let Some(storage) = &mut *STORAGE.lock() {
+        if let Some(server_hash) = storage.connections.remove(&connection) {
+            if !storage
+                .connections
+                .values()
+                .any(|hash| *hash == server_hash)
+            {
+                storage.servers.remove(&server_hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_tests::fake::{self, check, sample};
+    use crate::api_tests::unexpected_v1_2;
+    use crate::storage::{self, Namespace, StorageError};
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use parking_lot::Mutex;
+    use std::cell::Cell;
+    use std::fs;
+    use std::os::raw;
+    use std::path::PathBuf;
+
+    /// Storage is global, so its tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    thread_local! {
+        static HASH_LOOKUPS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mumble-sys-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn values_persist_per_server_and_user() {
+        let _serial = SERIAL.lock();
+        let dir = temp_dir("storage-persist");
+        storage::open(&dir).unwrap();
+        let server = Namespace::server("server/1");
+        let alice = Namespace::user("server/1", "alice");
+        server.set("volume", &3).unwrap();
+        alice.set("nickname", "Al").unwrap();
+        alice.set("muted", &true).unwrap();
+        assert_eq!(
+            Namespace::server("server/2").keys().unwrap(),
+            Vec::<String>::new()
+        );
+        assert_eq!(server.get::<bool>("nickname").unwrap(), None);
+
+        storage::close();
+        assert!(matches!(
+            server.get::<i32>("volume"),
+            Err(StorageError::NotOpen)
+        ));
+        storage::open(&dir).unwrap();
+        assert_eq!(server.get::<i32>("volume").unwrap(), Some(3));
+        assert_eq!(
+            alice.get::<String>("nickname").unwrap().as_deref(),
+            Some("Al")
+        );
+        assert_eq!(alice.keys().unwrap(), vec!["muted", "nickname"]);
+        assert!(matches!(
+            alice.get::<i32>("nickname"),
+            Err(StorageError::Malformed(_))
+        ));
+        assert!(alice.remove("muted").unwrap());
+        assert!(!alice.remove("muted").unwrap());
+        assert_eq!(
+            Namespace::user("server/1", "bob").keys().unwrap(),
+            Vec::<String>::new()
+        );
+        storage::close();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn exports_import_into_other_storage() {
+        let _serial = SERIAL.lock();
+        let from = temp_dir("storage-export");
+        let to = temp_dir("storage-import");
+        storage::open(&from).unwrap();
+        Namespace::server("a").set("volume", &3).unwrap();
+        Namespace::user("a", "alice").set("muted", &true).unwrap();
+        let mut backup = Vec::new();
+        storage::export(&mut backup).unwrap();
+
+        storage::open(&to).unwrap();
+        Namespace::server("a").set("kept", &1).unwrap();
+        Namespace::server("a").set("volume", &10).unwrap();
+        storage::import(&backup[..], false).unwrap();
+        assert_eq!(
+            Namespace::server("a").get::<i32>("volume").unwrap(),
+            Some(3)
+        );
+        assert_eq!(Namespace::server("a").get::<i32>("kept").unwrap(), Some(1));
+        assert_eq!(
+            Namespace::user("a", "alice").get::<bool>("muted").unwrap(),
+            Some(true)
+        );
+        storage::import(&backup[..], true).unwrap();
+        assert_eq!(Namespace::server("a").get::<i32>("kept").unwrap(), None);
+        assert!(matches!(
+            storage::import(&b"{}"[..], true),
+            Err(StorageError::Malformed(_))
+        ));
+        storage::close();
+        fs::remove_dir_all(&from).unwrap();
+        fs::remove_dir_all(&to).unwrap();
+    }
+
+    #[test]
+    fn synchronized_servers_are_remembered() {
+        unsafe extern "C" fn server_hash(
+            _: m::PluginId,
+            connection: m::ConnectionT,
+            hash: *mut *const raw::c_char,
+        ) -> m::ErrorT {
+            check("connection", connection, sample());
+            HASH_LOOKUPS.with(|lookups| lookups.set(lookups.get() + 1));
+            *hash = fake::alloc_string("server-hash");
+            fake::ok()
+        }
+        unsafe extern "C" fn user_hash(
+            _: m::PluginId,
+            _: m::ConnectionT,
+            user_id: m::UserIdT,
+            hash: *mut *const raw::c_char,
+        ) -> m::ErrorT {
+            // Users without a certificate have no hash
+            let text = if user_id == sample() { "user-hash" } else { "" };
+            *hash = fake::alloc_string(text);
+            fake::ok()
+        }
+        let _serial = SERIAL.lock();
+        let dir = temp_dir("storage-synchronized");
+        storage::open(&dir).unwrap();
+        let mut table = unexpected_v1_2();
+        table.getServerHash = server_hash;
+        table.getUserHash = user_hash;
+        let mut api = fake::api(RawMumbleAPI::V1_2(table));
+
+        storage::server_synchronized(&mut api, sample());
+        let user = Namespace::of_user(&mut api, sample(), sample()).unwrap();
+        assert_eq!(user, Namespace::user("server-hash", "user-hash"));
+        assert_eq!(HASH_LOOKUPS.with(Cell::get), 1);
+        assert!(matches!(
+            Namespace::of_user(&mut api, sample(), m::UserIdT(1)),
+            Err(StorageError::Unidentified)
+        ));
+
+        storage::server_disconnected(sample());
+        let server = Namespace::of_server(&mut api, sample()).unwrap();
+        assert_eq!(server, user.server_namespace());
+        assert_eq!(HASH_LOOKUPS.with(Cell::get), 2);
+        storage::close();
+        fs::remove_dir_all(&dir).unwrap();
+        fake::finish("synchronized_servers_are_remembered");
+    }
+}
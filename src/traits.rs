@@ -1,10 +1,14 @@
+use crate::auto_move::{AutoMove, MoveError};
+use crate::hotkeys::HotkeyAction;
+use crate::local_user::{LocalUserChange, LocalUserState};
 use crate::mumble::m;
+use crate::versions::RawMumbleAPI;
 
 #[allow(unused_variables)]
 pub trait MumblePlugin: Send {
     fn shutdown(&self);
 
-    //fn register_api_functions(api: m::MumbleAPI); // To be handled internally
+    //fn register_api_functions(api: RawMumbleAPI); // To be handled internally
 
     fn on_server_connected(&mut self, conn: m::ConnectionT) {}
     fn on_server_disconnected(&mut self, conn: m::ConnectionT) {}
@@ -40,6 +44,7 @@ pub trait MumblePlugin: Send {
         pulse_code_modulation: &mut [i16], // Length is sample_count * channel_count
         sample_count: u32,
         channel_count: u16,
+        sample_rate: u32,
         is_speech: bool,
     ) -> bool /* true if mutated */ {
         false
@@ -62,6 +67,7 @@ pub trait MumblePlugin: Send {
         pcm: &mut [f32],
         sample_count: u32,
         channel_count: u16,
+        sample_rate: u32,
     ) -> bool {
         false
     }
@@ -87,6 +93,15 @@ pub trait MumblePlugin: Send {
     fn on_channel_renamed(&mut self, conn: m::ConnectionT, channel: m::ChannelIdT) {}
 
     fn on_key_event(&mut self, key_code: u32, pressed: bool) {}
+
+    /// Called for each change once `local_user::set_tracking(true)` is on; not a Mumble callback.
+    fn on_local_user_changed(&mut self, change: LocalUserChange, state: &LocalUserState) {}
+
+    /// Called when a `hotkeys` binding fires; not a Mumble callback.
+    fn on_hotkey(&mut self, binding: &str, action: HotkeyAction) {}
+
+    /// Called with the outcome of each `auto_move` move; not a Mumble callback.
+    fn on_auto_move(&mut self, movement: AutoMove, result: Result<(), MoveError>) {}
 }
 
 pub trait MumblePluginUpdater {
@@ -125,11 +140,18 @@ pub trait MumblePluginDescriptor: MumblePlugin {
             patch: 1,
         }
     }
+    /// One of `versions::SUPPORTED`; decides which functions `MumbleAPI` can call
     fn api_version() -> m::Version {
-        unsafe { m::mumble_plugin_api_version.0 }
+        crate::versions::DEFAULT
     }
 
-    fn init(id: m::PluginId, api: m::MumbleAPI) -> Result<Self, m::ErrorT>
+    fn init(id: m::PluginId, api: RawMumbleAPI) -> Result<Self, m::ErrorT>
     where
         Self: Sized;
 }
+
+/// Constructs the plugin for `#[mumble_plugin]`, which derives the rest of
+/// `MumblePluginDescriptor` from its attribute.
+pub trait MumblePluginInit: MumblePlugin + Sized {
+    fn init(id: m::PluginId, api: RawMumbleAPI) -> Result<Self, m::ErrorT>;
+}
@@ -0,0 +1,507 @@
+//! Rules that switch the local user's transmission mode or force the microphone on.
+//!
+//! Each `Rule` has a `Condition` and the state it wants while the condition holds. Among the
+//! rules that apply, the one with the highest priority picks the mode, and separately whether
+//! the microphone is forced on; ties go to the rule added first. The mode the user had
+//! before any rule changed it is restored once no rule wants one, and when the plugin shuts
+//! down.
+//!
+//! Rules are re-evaluated whenever something they may depend on changes: rules or flags
+//! being set, hotkeys firing, and the local user changing channel or server. Forcing the
+//! microphone is combined with the crate's own forcing for `vad` and `mixer`, so either can
+//! keep it on; the audio callback only records the latter, and it is passed on to Mumble from
+//! the next callback Mumble makes on its main thread.
+
+use crate::hotkeys;
+use crate::types as m;
+use crate::{MumbleAPI, MumbleResult};
+use parking_lot::Mutex;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Always,
+    /// A `hotkeys` binding is held (`Mode::Hold`) or toggled on (`Mode::Toggle`)
+    Hotkey(String),
+    /// A flag set with `set_flag`, e.g. for game state
+    Flag(String),
+    /// The local user is in this channel on the server they last synchronized with, or on
+    /// another server still connected once that one disconnects
+    InChannel(m::ChannelIdT),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub name: String,
+    pub condition: Condition,
+    /// Higher wins
+    pub priority: i32,
+    pub mode: Option<m::TransmissionMode>,
+    /// `Some(false)` keeps lower-priority rules from forcing the microphone on
+    pub force_microphone: Option<bool>,
+}
+
+impl Rule {
+    /// A rule that wants nothing yet, at priority 0
+    pub fn new(name: impl Into<String>, condition: Condition) -> Self {
+        Rule {
+            name: name.into(),
+            condition,
+            priority: 0,
+            mode: None,
+            force_microphone: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: m::TransmissionMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    pub fn with_microphone(mut self, forced: bool) -> Self {
+        self.force_microphone = Some(forced);
+        self
+    }
+}
+
+/// What the applying rules want, as of the last evaluation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Desired {
+    pub mode: Option<m::TransmissionMode>,
+    pub force_microphone: bool,
+}
+
+struct Engine {
+    /// In the order they were added
+    rules: Vec<Rule>,
+    flags: BTreeSet<String>,
+    /// The local user's channel on each server, as channel IDs repeat across servers
+    channels: BTreeMap<m::ConnectionT, m::ChannelIdT>,
+    /// The server `Condition::InChannel` looks at
+    active: Option<m::ConnectionT>,
+    desired: Desired,
+    /// The user's own mode, while a rule has replaced it
+    original_mode: Option<m::TransmissionMode>,
+    applied_mode: Option<m::TransmissionMode>,
+    /// Whether we last asked Mumble to force the microphone on
+    microphone_forced: bool,
+}
+
+/// Whether `vad` or `mixer` want the microphone on; written from the audio callback, which
+/// must not wait on `ENGINE` or call Mumble
+static AUDIO_FORCED: AtomicBool = AtomicBool::new(false);
+
+static ENGINE: Mutex<Engine> = Mutex::new(Engine {
+    rules: Vec::new(),
+    flags: BTreeSet::new(),
+    channels: BTreeMap::new(),
+    active: None,
+    desired: Desired {
+        mode: None,
+        force_microphone: false,
+    },
+    original_mode: None,
+    applied_mode: None,
+    microphone_forced: false,
+});
+
+impl Engine {
+    fn holds(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Always => true,
+            Condition::Hotkey(name) => hotkeys::is_active(name),
+            Condition::Flag(flag) => self.flags.contains(flag),
+            Condition::InChannel(channel) => {
+                self.active.and_then(|active| self.channels.get(&active)) == Some(channel)
+            }
+            Condition::All(conditions) => conditions.iter().all(|c| self.holds(c)),
+            Condition::Any(conditions) => conditions.iter().any(|c| self.holds(c)),
+            Condition::Not(condition) => !self.holds(condition),
+        }
+    }
+
+    fn resolve(&self) -> Desired {
+        let mut applying: Vec<&Rule> = self
+            .rules
+            .iter()
+            .filter(|rule| self.holds(&rule.condition))
+            .collect();
+        // Stable, so ties keep the order rules were added in
+        applying.sort_by_key(|rule| Reverse(rule.priority));
+        Desired {
+            mode: applying.iter().find_map(|rule| rule.mode),
+            force_microphone: applying
+                .iter()
+                .find_map(|rule| rule.force_microphone)
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Adds `rule`, replacing any rule with the same name, and applies the result.
+pub fn add_rule(api: &mut MumbleAPI, rule: Rule) -> MumbleResult<()> {
+    {
+        let mut engine = ENGINE.lock();
+        match engine
+            .rules
+            .iter_mut()
+            .find(|added| added.name == rule.name)
+        {
+            Some(added) => *added = rule,
+            None => engine.rules.push(rule),
+        }
+    }
+    evaluate(api)
+}
+
+/// Whether there was a rule to remove
+pub fn remove_rule(api: &mut MumbleAPI, name: &str) -> MumbleResult<bool> {
+    let removed = {
+        let mut engine = ENGINE.lock();
+        let count = engine.rules.len();
+        engine.rules.retain(|rule| rule.name != name);
+        engine.rules.len() != count
+    };
+    evaluate(api)?;
+    Ok(removed)
+}
+
+/// Removes every rule and flag, restoring the user's mode.
+pub fn clear(api: &mut MumbleAPI) -> MumbleResult<()> {
+    {
+        let mut engine = ENGINE.lock();
+        engine.rules.clear();
+        engine.flags.clear();
+    }
+    evaluate(api)
+}
+
+pub fn rules() -> Vec<Rule> {
+    ENGINE.lock().rules.clone()
+}
+
+pub fn set_flag(api: &mut MumbleAPI, flag: &str, set: bool) -> MumbleResult<()> {
+    let changed = {
+        let mut engine = ENGINE.lock();
+        if set {
+            engine.flags.insert(flag.to_string())
+        } else {
+            engine.flags.remove(flag)
+        }
+    };
+    if changed {
+        evaluate(api)?;
+    }
+    Ok(())
+}
+
+pub fn flag(flag: &str) -> bool {
+    ENGINE.lock().flags.contains(flag)
+}
+
+pub fn desired() -> Desired {
+    ENGINE.lock().desired
+}
+
+/// Applies the rules now, e.g. after state a `Condition` can't see has changed.
+///
+/// Mumble is called without the rules locked, since its API may wait on a thread that is
+/// itself waiting to evaluate them.
+pub fn evaluate(api: &mut MumbleAPI) -> MumbleResult<()> {
+    let (wanted, applied, saved) = {
+        let mut engine = ENGINE.lock();
+        engine.desired = engine.resolve();
+        (
+            engine.desired.mode,
+            engine.applied_mode,
+            engine.original_mode.is_some(),
+        )
+    };
+    if wanted != applied {
+        match wanted {
+            Some(mode) => {
+                if !saved {
+                    let original = api.get_local_user_transmission_mode()?.into();
+                    ENGINE.lock().original_mode.get_or_insert(original);
+                }
+                api.request_local_user_transmission_mode(mode.into())?;
+                ENGINE.lock().applied_mode = Some(mode);
+            }
+            None => {
+                let original = {
+                    let mut engine = ENGINE.lock();
+                    engine.applied_mode = None;
+                    engine.original_mode.take()
+                };
+                if let Some(mode) = original {
+                    api.request_local_user_transmission_mode(mode.into())?;
+                }
+            }
+        }
+    }
+    update_microphone(api)
+}
+
+fn update_microphone(api: &mut MumbleAPI) -> MumbleResult<()> {
+    let forced = {
+        let mut engine = ENGINE.lock();
+        let forced = AUDIO_FORCED.load(Ordering::Relaxed) || engine.desired.force_microphone;
+        if forced == engine.microphone_forced {
+            return Ok(());
+        }
+        engine.microphone_forced = forced;
+        forced
+    };
+    api.request_microphone_activation_overwrite(forced)
+}
+
+/// Evaluates after an event, if there is anything to evaluate
+pub(crate) fn reevaluate(api: &mut MumbleAPI) {
+    let idle = {
+        let engine = ENGINE.lock();
+        engine.rules.is_empty() && engine.applied_mode.is_none() && !engine.microphone_forced
+    };
+    if idle {
+        return;
+    }
+    if let Err(e) = evaluate(api) {
+        log::warn!("Could not apply transmission rules: {:?}", e);
+    }
+}
+
+/// Whether `vad` or `mixer` want the microphone forced on, from the audio callback.
+/// Only records it, as the audio thread must not call Mumble.
+pub(crate) fn set_audio_forced(forced: bool) {
+    AUDIO_FORCED.store(forced, Ordering::Relaxed);
+}
+
+/// Passes on what the audio callback last asked for, from Mumble's main thread
+pub(crate) fn apply_audio_forced(api: &mut MumbleAPI) {
+    if let Err(e) = update_microphone(api) {
+        log::warn!("Could not toggle forced transmission: {:?}", e);
+    }
+}
+
+pub(crate) fn server_synchronized(api: &mut MumbleAPI, connection: m::ConnectionT) {
+    if ENGINE.lock().rules.is_empty() {
+        return;
+    }
+    let channel = api
+        .get_local_user_id(connection)
+        .and_then(|user| api.get_channel_of_user(connection, user));
+    match channel {
+        Ok(channel) => {
+            {
+                let mut engine = ENGINE.lock();
+                engine.channels.insert(connection, channel);
+                engine.active = Some(connection);
+            }
+            reevaluate(api);
+        }
+        Err(e) => log::warn!("Could not find the local user's channel: {:?}", e),
+    }
+}
+
+pub(crate) fn channel_entered(
+    api: &mut MumbleAPI,
+    connection: m::ConnectionT,
+    user: m::UserIdT,
+    channel: m::ChannelIdT,
+) {
+    if ENGINE.lock().rules.is_empty() {
+        return;
+    }
+    if api.get_local_user_id(connection) == Ok(user) {
+        {
+            let mut engine = ENGINE.lock();
+            engine.channels.insert(connection, channel);
+            engine.active.get_or_insert(connection);
+        }
+        reevaluate(api);
+    }
+}
+
+pub(crate) fn server_disconnected(api: &mut MumbleAPI, connection: m::ConnectionT) {
+    {
+        let mut engine = ENGINE.lock();
+        engine.channels.remove(&connection);
+        if engine.active == Some(connection) {
+            engine.active = engine.channels.keys().next().cloned();
+        }
+    }
+    reevaluate(api);
+}
+
+/// Drops every rule and releases the microphone, restoring the user's mode
+pub(crate) fn shutdown(api: &mut MumbleAPI) {
+    {
+        let mut engine = ENGINE.lock();
+        engine.rules.clear();
+        engine.flags.clear();
+        engine.channels.clear();
+        engine.active = None;
+    }
+    AUDIO_FORCED.store(false, Ordering::Relaxed);
+    if let Err(e) = evaluate(api) {
+        log::warn!("Could not restore the transmission mode: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_tests::fake::{self, sample};
+    use crate::api_tests::unexpected_v1_2;
+    use crate::transmission::{self, Condition, Rule};
+    use crate::types as m;
+    use crate::versions::RawMumbleAPI;
+    use crate::MumbleAPI;
+    use m::TransmissionMode::*;
+    use parking_lot::Mutex;
+
+    /// Rules are global, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+    static REQUESTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn transmission_mode(
+        _: m::PluginId,
+        mode: *mut m::TransmissionModeT,
+    ) -> m::ErrorT {
+        *mode = TM_VOICE_ACTIVATION.into();
+        fake::ok()
+    }
+
+    unsafe extern "C" fn request_mode(_: m::PluginId, mode: m::TransmissionModeT) -> m::ErrorT {
+        let mode: m::TransmissionMode = mode.into();
+        REQUESTS.lock().push(format!("{:?}", mode));
+        fake::ok()
+    }
+
+    unsafe extern "C" fn request_overwrite(_: m::PluginId, activate: bool) -> m::ErrorT {
+        REQUESTS.lock().push(format!("microphone {}", activate));
+        fake::ok()
+    }
+
+    unsafe extern "C" fn local_user(
+        _: m::PluginId,
+        _: m::ConnectionT,
+        user_id: *mut m::UserIdT,
+    ) -> m::ErrorT {
+        *user_id = sample();
+        fake::ok()
+    }
+
+    fn api() -> MumbleAPI {
+        let mut table = unexpected_v1_2();
+        table.getLocalUserTransmissionMode = transmission_mode;
+        table.requestLocalUserTransmissionMode = request_mode;
+        table.requestMicrophoneActivationOvewrite = request_overwrite;
+        table.getLocalUserID = local_user;
+        fake::api(RawMumbleAPI::V1_2(table))
+    }
+
+    fn requests() -> Vec<String> {
+        std::mem::take(&mut *REQUESTS.lock())
+    }
+
+    #[test]
+    fn highest_priority_rule_wins_and_the_mode_is_restored() {
+        let _serial = SERIAL.lock();
+        let mut api = api();
+        let game = Rule::new("game", Condition::Flag("in-game".into())).with_mode(TM_PUSH_TO_TALK);
+        let meeting = Rule::new("meeting", Condition::Flag("meeting".into()))
+            .with_priority(10)
+            .with_mode(TM_CONTINOUS);
+        transmission::add_rule(&mut api, meeting).unwrap();
+        transmission::add_rule(&mut api, game).unwrap();
+        assert!(requests().is_empty());
+
+        transmission::set_flag(&mut api, "in-game", true).unwrap();
+        transmission::set_flag(&mut api, "meeting", true).unwrap();
+        assert_eq!(transmission::desired().mode, Some(TM_CONTINOUS));
+        transmission::set_flag(&mut api, "meeting", false).unwrap();
+        transmission::set_flag(&mut api, "in-game", false).unwrap();
+        assert_eq!(
+            requests(),
+            vec![
+                "TM_PUSH_TO_TALK",
+                "TM_CONTINOUS",
+                "TM_PUSH_TO_TALK",
+                "TM_VOICE_ACTIVATION"
+            ]
+        );
+
+        transmission::set_flag(&mut api, "in-game", true).unwrap();
+        transmission::shutdown(&mut api);
+        assert_eq!(requests(), vec!["TM_PUSH_TO_TALK", "TM_VOICE_ACTIVATION"]);
+        assert!(transmission::rules().is_empty());
+        fake::finish("highest_priority_rule_wins_and_the_mode_is_restored");
+    }
+
+    #[test]
+    fn microphone_forcing_combines_rules_and_audio() {
+        let _serial = SERIAL.lock();
+        let mut api = api();
+        let stage =
+            Rule::new("stage", Condition::InChannel(m::ChannelIdT(3))).with_microphone(true);
+        let quiet = Rule::new("quiet", Condition::Flag("quiet".into()))
+            .with_priority(1)
+            .with_microphone(false);
+        transmission::add_rule(&mut api, stage).unwrap();
+        transmission::add_rule(&mut api, quiet).unwrap();
+
+        transmission::channel_entered(&mut api, sample(), m::UserIdT(1), m::ChannelIdT(3));
+        assert!(requests().is_empty());
+        transmission::channel_entered(&mut api, sample(), sample(), m::ChannelIdT(3));
+        assert_eq!(requests(), vec!["microphone true"]);
+        transmission::set_flag(&mut api, "quiet", true).unwrap();
+        assert_eq!(requests(), vec!["microphone false"]);
+
+        transmission::set_audio_forced(true);
+        assert!(requests().is_empty());
+        transmission::apply_audio_forced(&mut api);
+        assert_eq!(requests(), vec!["microphone true"]);
+        transmission::set_flag(&mut api, "quiet", false).unwrap();
+        transmission::server_disconnected(&mut api, sample());
+        assert!(requests().is_empty());
+        transmission::shutdown(&mut api);
+        assert_eq!(requests(), vec!["microphone false"]);
+        fake::finish("microphone_forcing_combines_rules_and_audio");
+    }
+
+    #[test]
+    fn channels_are_tracked_per_server() {
+        let _serial = SERIAL.lock();
+        let mut api = api();
+        let other = m::ConnectionT(8);
+        let stage =
+            Rule::new("stage", Condition::InChannel(m::ChannelIdT(3))).with_microphone(true);
+        transmission::add_rule(&mut api, stage).unwrap();
+
+        transmission::channel_entered(&mut api, sample(), sample(), m::ChannelIdT(3));
+        transmission::channel_entered(&mut api, other, sample(), m::ChannelIdT(4));
+        assert_eq!(requests(), vec!["microphone true"]);
+        // Another server going away leaves the active one's channel alone
+        transmission::server_disconnected(&mut api, other);
+        assert!(transmission::desired().force_microphone);
+        assert!(requests().is_empty());
+
+        transmission::channel_entered(&mut api, other, sample(), m::ChannelIdT(4));
+        transmission::server_disconnected(&mut api, sample());
+        assert_eq!(requests(), vec!["microphone false"]);
+        transmission::channel_entered(&mut api, other, sample(), m::ChannelIdT(3));
+        assert_eq!(requests(), vec!["microphone true"]);
+        transmission::shutdown(&mut api);
+        assert_eq!(requests(), vec!["microphone false"]);
+        fake::finish("channels_are_tracked_per_server");
+    }
+}
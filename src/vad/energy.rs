@@ -0,0 +1,91 @@
+use super::spectrum::{flatness, Spectrum};
+use super::{VadConfig, VoiceDetector};
+use crate::dsp::linear_to_db;
+
+const VOICE_BAND_HZ: (f32, f32) = (250.0, 4000.0);
+
+/// Level gate with hysteresis; onsets must also look voiced (low spectral flatness),
+/// so clicks and broadband noise above the threshold don't open it.
+pub struct EnergyDetector {
+    speech_threshold_db: f32,
+    silence_threshold_db: f32,
+    max_flatness: f32,
+    spectrum: Option<Spectrum>,
+}
+
+impl EnergyDetector {
+    pub fn new(config: &VadConfig) -> Self {
+        EnergyDetector {
+            speech_threshold_db: config.speech_threshold_db,
+            silence_threshold_db: config.silence_threshold_db,
+            max_flatness: config.max_flatness,
+            spectrum: None,
+        }
+    }
+}
+
+impl VoiceDetector for EnergyDetector {
+    fn detect(&mut self, frame: &[f32], sample_rate: u32, active: bool) -> bool {
+        let peak = frame
+            .iter()
+            .fold(0f32, |peak, sample| peak.max(sample.abs()));
+        let level_db = linear_to_db(peak);
+        if active {
+            return level_db > self.silence_threshold_db;
+        }
+        if level_db <= self.speech_threshold_db {
+            return false;
+        }
+        let spectrum = match &mut self.spectrum {
+            Some(spectrum) if spectrum.sample_rate() == sample_rate => spectrum,
+            spectrum => {
+                *spectrum = Some(Spectrum::new(frame.len(), sample_rate));
+                spectrum.as_mut().unwrap()
+            }
+        };
+        spectrum.analyze(frame);
+        flatness(spectrum.band(VOICE_BAND_HZ.0, VOICE_BAND_HZ.1)) < self.max_flatness
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..480)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn voiced_onsets_open_and_quiet_closes() {
+        let mut detector = EnergyDetector::new(&VadConfig::default());
+        // -40dBFS: between the silence and speech thresholds
+        let between = tone(0.01);
+        assert!(!detector.detect(&between, 48000, false));
+        assert!(detector.detect(&between, 48000, true));
+        assert!(!detector.detect(&tone(0.001), 48000, true));
+        assert!(detector.detect(&tone(0.5), 48000, false));
+        assert!(detector.detect(&tone(0.5), 16000, false));
+    }
+
+    #[test]
+    fn noise_and_clicks_do_not_open() {
+        let mut detector = EnergyDetector::new(&VadConfig::default());
+        let mut seed = 7u32;
+        let noise: Vec<f32> = (0..480)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                0.5 * ((seed >> 8) as f32 / (1 << 23) as f32 - 1.0)
+            })
+            .collect();
+        assert!(!detector.detect(&noise, 48000, false));
+        // Once open, level alone keeps it open
+        assert!(detector.detect(&noise, 48000, true));
+
+        let mut click = vec![0.0; 480];
+        click[240] = 0.9;
+        assert!(!detector.detect(&click, 48000, false));
+    }
+}
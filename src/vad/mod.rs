@@ -0,0 +1,382 @@
+//! Plugin-side voice activity detection for the microphone.
+//!
+//! Audio is judged in 10ms mono frames, matching Mumble's own VAD. Decisions are
+//! held for `VadConfig::hold` after speech stops, and can drive Mumble's microphone
+//! activation overwrite. The overwrite can only force transmission on, so for our
+//! detector to also keep the microphone closed, set Mumble to push-to-talk.
+
+mod energy;
+#[cfg(feature = "vad-model")]
+mod model;
+mod spectrum;
+
+pub use energy::EnergyDetector;
+#[cfg(feature = "vad-model")]
+pub use model::{Aggressiveness, SubbandDetector};
+
+use crate::types as m;
+use crate::{MumbleAPI, MumbleResult};
+use parking_lot::Mutex;
+use std::time::Duration;
+
+pub const FRAME_DURATION: Duration = Duration::from_millis(10);
+
+/// Decides whether a single 10ms mono frame contains speech.
+pub trait VoiceDetector: Send {
+    /// `active` is the previous frame's decision, before hold, for hysteresis.
+    fn detect(&mut self, frame: &[f32], sample_rate: u32, active: bool) -> bool;
+    fn reset(&mut self) {}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VadConfig {
+    /// Peak level (dBFS) a frame must exceed for speech to start
+    pub speech_threshold_db: f32,
+    /// Peak level (dBFS) below which ongoing speech stops
+    pub silence_threshold_db: f32,
+    /// Spectral flatness (0 tonal ..= 1 noise) above which onsets are ignored
+    pub max_flatness: f32,
+    pub hold: Duration,
+    /// Whether detected speech forces transmission via the microphone activation overwrite
+    pub drive_transmission: bool,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        VadConfig {
+            speech_threshold_db: -35.0,
+            silence_threshold_db: -45.0,
+            max_flatness: 0.3,
+            hold: Duration::from_millis(200),
+            drive_transmission: false,
+        }
+    }
+}
+
+// Mumble's amplitude thresholds map -96..0 dBFS onto 0..1
+fn mumble_threshold_to_db(threshold: f64) -> f32 {
+    (threshold * 96.0 - 96.0) as f32
+}
+
+impl VadConfig {
+    /// Takes hold time and thresholds from Mumble's own voice activity settings.
+    pub fn from_mumble_settings(api: &mut MumbleAPI) -> MumbleResult<Self> {
+        let hold_frames =
+            api.get_mumble_setting_int(m::SettingsKey::MSK_AUDIO_INPUT_VOICE_HOLD.into())?;
+        let silence = api.get_mumble_setting_double(
+            m::SettingsKey::MSK_AUDIO_INPUT_VAD_SILENCE_THRESHOLD.into(),
+        )?;
+        let speech = api.get_mumble_setting_double(
+            m::SettingsKey::MSK_AUDIO_INPUT_VAD_SPEECH_THRESHOLD.into(),
+        )?;
+        Ok(VadConfig {
+            speech_threshold_db: mumble_threshold_to_db(speech),
+            silence_threshold_db: mumble_threshold_to_db(silence),
+            hold: FRAME_DURATION * hold_frames.max(0) as u32,
+            ..Self::default()
+        })
+    }
+
+    fn hold_frames(&self) -> u32 {
+        (self.hold.as_micros() / FRAME_DURATION.as_micros()) as u32
+    }
+}
+
+/// Frames a microphone stream and applies a detector's decisions with hold time.
+pub struct VoiceActivity {
+    config: VadConfig,
+    detector: Box<dyn VoiceDetector>,
+    frame: Vec<f32>,
+    frame_len: usize,
+    sample_rate: u32,
+    detected: bool,
+    hold_remaining: u32,
+}
+
+impl VoiceActivity {
+    pub fn new(config: VadConfig) -> Self {
+        Self::with_detector(config, EnergyDetector::new(&config))
+    }
+
+    /// Uses a custom detector; `config`'s thresholds are then left to the detector.
+    pub fn with_detector<D: VoiceDetector + 'static>(config: VadConfig, detector: D) -> Self {
+        VoiceActivity {
+            config,
+            detector: Box::new(detector),
+            frame: Vec::new(),
+            frame_len: 0,
+            sample_rate: 0,
+            detected: false,
+            hold_remaining: 0,
+        }
+    }
+
+    pub fn config(&self) -> &VadConfig {
+        &self.config
+    }
+
+    /// Whether speech was detected recently enough to still be held open.
+    pub fn is_active(&self) -> bool {
+        self.detected || self.hold_remaining > 0
+    }
+
+    pub fn process(&mut self, pcm: &[f32], channel_count: u16, sample_rate: u32) -> bool {
+        self.process_with(pcm, channel_count, sample_rate, |sample| sample)
+    }
+
+    pub fn process_i16(&mut self, pcm: &[i16], channel_count: u16, sample_rate: u32) -> bool {
+        self.process_with(pcm, channel_count, sample_rate, |sample| {
+            sample as f32 / 32768.0
+        })
+    }
+
+    fn process_with<T: Copy, F: Fn(T) -> f32>(
+        &mut self,
+        pcm: &[T],
+        channel_count: u16,
+        sample_rate: u32,
+        to_f32: F,
+    ) -> bool {
+        if channel_count == 0 || sample_rate == 0 {
+            return self.is_active();
+        }
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.frame_len = (sample_rate / 100).max(1) as usize;
+            self.frame = Vec::with_capacity(self.frame_len);
+            self.detector.reset();
+        }
+        let scale = 1.0 / channel_count as f32;
+        for frame in pcm.chunks_exact(channel_count as usize) {
+            let mono = frame.iter().map(|sample| to_f32(*sample)).sum::<f32>() * scale;
+            self.frame.push(mono);
+            if self.frame.len() == self.frame_len {
+                let was_detected = self.detected;
+                self.detected = self
+                    .detector
+                    .detect(&self.frame, sample_rate, self.detected);
+                if self.detected {
+                    self.hold_remaining = self.config.hold_frames();
+                } else if !was_detected {
+                    // The first silent frame starts the hold rather than using it up
+                    self.hold_remaining = self.hold_remaining.saturating_sub(1);
+                }
+                self.frame.clear();
+            }
+        }
+        self.is_active()
+    }
+
+    pub fn reset(&mut self) {
+        self.frame.clear();
+        self.detected = false;
+        self.hold_remaining = 0;
+        self.detector.reset();
+    }
+}
+
+/// Callback-by-callback agreement between our decision and Mumble's `is_speech`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct VadComparison {
+    pub both: u64,
+    pub only_plugin: u64,
+    pub only_mumble: u64,
+    pub neither: u64,
+}
+
+impl VadComparison {
+    pub fn total(&self) -> u64 {
+        self.both + self.only_plugin + self.only_mumble + self.neither
+    }
+
+    /// Fraction of callbacks on which both detectors agreed.
+    pub fn agreement(&self) -> f32 {
+        match self.total() {
+            0 => 1.0,
+            total => (self.both + self.neither) as f32 / total as f32,
+        }
+    }
+
+    fn record(&mut self, plugin: bool, mumble: bool) {
+        match (plugin, mumble) {
+            (true, true) => self.both += 1,
+            (true, false) => self.only_plugin += 1,
+            (false, true) => self.only_mumble += 1,
+            (false, false) => self.neither += 1,
+        }
+    }
+}
+
+struct Vad {
+    activity: VoiceActivity,
+    comparison: VadComparison,
+}
+
+static VAD: Mutex<Option<Vad>> = Mutex::new(None);
+
+/// Starts detecting on the microphone with the built-in energy detector.
+pub fn enable(config: VadConfig) {
+    enable_with_detector(config, EnergyDetector::new(&config));
+}
+
+pub fn enable_with_detector<D: VoiceDetector + 'static>(config: VadConfig, detector: D) {
+    *VAD.lock() = Some(Vad {
+        activity: VoiceActivity::with_detector(config, detector),
+        comparison: VadComparison::default(),
+    });
+}
+
+/// Stops detecting; transmission is released on the next audio callback if we forced it.
+pub fn disable() {
+    *VAD.lock() = None;
+}
+
+pub fn is_enabled() -> bool {
+    VAD.lock().is_some()
+}
+
+pub fn is_voice_active() -> bool {
+    VAD.lock()
+        .as_ref()
+        .map_or(false, |vad| vad.activity.is_active())
+}
+
+pub fn comparison() -> VadComparison {
+    VAD.lock()
+        .as_ref()
+        .map_or_else(VadComparison::default, |vad| vad.comparison)
+}
+
+pub fn reset_comparison() {
+    if let Some(vad) = VAD.lock().as_mut() {
+        vad.comparison = VadComparison::default();
+    }
+}
+
+/// Runs detection on microphone audio, returning whether transmission should be forced on.
+pub(crate) fn process_input(
+    pcm: &[i16],
+    channel_count: u16,
+    sample_rate: u32,
+    is_speech: bool,
+) -> bool {
+    let mut vad = VAD.lock();
+    let vad = match vad.as_mut() {
+        Some(vad) => vad,
+        None => return false,
+    };
+    let active = vad.activity.process_i16(pcm, channel_count, sample_rate);
+    vad.comparison.record(active, is_speech);
+    active && vad.activity.config().drive_transmission
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Enabling detection is global, so these tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// Reports speech whenever a frame's first sample is positive, and records frame sizes
+    struct Scripted(std::sync::Arc<Mutex<Vec<usize>>>);
+
+    impl VoiceDetector for Scripted {
+        fn detect(&mut self, frame: &[f32], _sample_rate: u32, _active: bool) -> bool {
+            self.0.lock().push(frame.len());
+            frame[0] > 0.0
+        }
+    }
+
+    fn scripted(hold_ms: u64) -> (VoiceActivity, std::sync::Arc<Mutex<Vec<usize>>>) {
+        let frames = std::sync::Arc::default();
+        let config = VadConfig {
+            hold: Duration::from_millis(hold_ms),
+            ..VadConfig::default()
+        };
+        (
+            VoiceActivity::with_detector(config, Scripted(std::sync::Arc::clone(&frames))),
+            frames,
+        )
+    }
+
+    #[test]
+    fn audio_is_framed_in_ten_milliseconds_of_mono() {
+        let (mut activity, frames) = scripted(0);
+        // 25ms of stereo at 16kHz; the left and right cancel out into silence
+        let pcm: Vec<f32> = (0..400).flat_map(|_| vec![1.0, -1.0]).collect();
+        assert!(!activity.process(&pcm, 2, 16000));
+        assert_eq!(*frames.lock(), vec![160, 160]);
+
+        // A new sample rate drops the partial frame
+        assert!(activity.process_i16(&[1000; 480], 1, 48000));
+        assert_eq!(*frames.lock(), vec![160, 160, 480]);
+        assert!(activity.process(&[], 0, 48000));
+    }
+
+    #[test]
+    fn speech_is_held_after_it_stops() {
+        let (mut activity, _) = scripted(30);
+        assert!(activity.process(&[0.5; 160], 1, 16000));
+        for _ in 0..3 {
+            assert!(activity.process(&[0.0; 160], 1, 16000));
+        }
+        assert!(!activity.process(&[0.0; 160], 1, 16000));
+
+        activity.process(&[0.5; 160], 1, 16000);
+        activity.reset();
+        assert!(!activity.is_active());
+    }
+
+    #[test]
+    fn mumble_settings_are_converted() {
+        assert_eq!(mumble_threshold_to_db(1.0), 0.0);
+        assert_eq!(mumble_threshold_to_db(0.5), -48.0);
+        let config = VadConfig {
+            hold: Duration::from_millis(255),
+            ..VadConfig::default()
+        };
+        assert_eq!(config.hold_frames(), 25);
+    }
+
+    #[test]
+    fn comparison_counts_agreement() {
+        let mut comparison = VadComparison::default();
+        assert_eq!(comparison.agreement(), 1.0);
+        comparison.record(true, true);
+        comparison.record(true, false);
+        comparison.record(false, true);
+        comparison.record(false, false);
+        assert_eq!(comparison.total(), 4);
+        assert_eq!(comparison.agreement(), 0.5);
+    }
+
+    #[test]
+    fn input_drives_transmission_only_when_asked() {
+        let _serial = SERIAL.lock();
+        let speech: Vec<i16> = (0..480)
+            .map(|i| {
+                (8000.0 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48000.0).sin()) as i16
+            })
+            .collect();
+        disable();
+        assert!(!process_input(&speech, 1, 48000, true));
+        assert_eq!(comparison(), VadComparison::default());
+
+        enable(VadConfig::default());
+        assert!(!process_input(&speech, 1, 48000, true));
+        assert!(is_voice_active());
+        assert_eq!(comparison().both, 1);
+        reset_comparison();
+        assert_eq!(comparison().total(), 0);
+
+        enable(VadConfig {
+            drive_transmission: true,
+            ..VadConfig::default()
+        });
+        assert!(process_input(&speech, 1, 48000, false));
+        assert_eq!(comparison().only_plugin, 1);
+        disable();
+        assert!(!is_enabled());
+        assert!(!is_voice_active());
+    }
+}
@@ -0,0 +1,155 @@
+use super::spectrum::Spectrum;
+use super::VoiceDetector;
+
+const BANDS_HZ: [(f32, f32); 6] = [
+    (80.0, 250.0),
+    (250.0, 500.0),
+    (500.0, 1000.0),
+    (1000.0, 2000.0),
+    (2000.0, 3000.0),
+    (3000.0, 4000.0),
+];
+// Speech energy concentrates in the middle bands
+const BAND_WEIGHTS: [f32; 6] = [0.8, 1.0, 1.2, 1.2, 0.9, 0.7];
+const SPEECH_OFFSET_DB: f32 = 15.0;
+const SPEECH_DEVIATION_DB: f32 = 8.0;
+const NOISE_DEVIATION_DB: f32 = 4.0;
+const HANGOVER_BONUS: f32 = 2.0;
+const NOISE_RISE: f32 = 0.05;
+const NOISE_FALL: f32 = 0.3;
+const NOISE_CREEP: f32 = 0.002;
+
+/// How readily the model reports speech, as in WebRTC's VAD modes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Aggressiveness {
+    Quality,
+    LowBitrate,
+    Aggressive,
+    VeryAggressive,
+}
+
+impl Aggressiveness {
+    fn threshold(self) -> f32 {
+        match self {
+            Aggressiveness::Quality => 3.0,
+            Aggressiveness::LowBitrate => 6.0,
+            Aggressiveness::Aggressive => 9.0,
+            Aggressiveness::VeryAggressive => 12.0,
+        }
+    }
+}
+
+fn log_gaussian(x: f32, mean: f32, deviation: f32) -> f32 {
+    let z = (x - mean) / deviation;
+    -0.5 * z * z - deviation.ln()
+}
+
+/// WebRTC-style detector: per-band log-likelihood of speech against an adaptive
+/// noise model, summed over six sub-bands.
+pub struct SubbandDetector {
+    aggressiveness: Aggressiveness,
+    spectrum: Option<Spectrum>,
+    noise_db: Option<[f32; 6]>,
+}
+
+impl SubbandDetector {
+    pub fn new(aggressiveness: Aggressiveness) -> Self {
+        SubbandDetector {
+            aggressiveness,
+            spectrum: None,
+            noise_db: None,
+        }
+    }
+}
+
+impl VoiceDetector for SubbandDetector {
+    fn detect(&mut self, frame: &[f32], sample_rate: u32, active: bool) -> bool {
+        let spectrum = match &mut self.spectrum {
+            Some(spectrum) if spectrum.sample_rate() == sample_rate => spectrum,
+            spectrum => {
+                *spectrum = Some(Spectrum::new(frame.len(), sample_rate));
+                spectrum.as_mut().unwrap()
+            }
+        };
+        spectrum.analyze(frame);
+        let mut energy_db = [0f32; 6];
+        for (energy, (low, high)) in energy_db.iter_mut().zip(BANDS_HZ.iter()) {
+            let power: f32 = spectrum.band(*low, *high).iter().sum();
+            *energy = 10.0 * (power + 1e-10).log10();
+        }
+        let noise_db = self.noise_db.get_or_insert(energy_db);
+        let likelihood: f32 = energy_db
+            .iter()
+            .zip(noise_db.iter())
+            .zip(BAND_WEIGHTS.iter())
+            .map(|((energy, noise), weight)| {
+                let speech = log_gaussian(*energy, noise + SPEECH_OFFSET_DB, SPEECH_DEVIATION_DB);
+                let noise = log_gaussian(*energy, *noise, NOISE_DEVIATION_DB);
+                weight * (speech - noise)
+            })
+            .sum();
+        let threshold = self.aggressiveness.threshold() - if active { HANGOVER_BONUS } else { 0.0 };
+        let speech = likelihood > threshold;
+        // Track the noise floor from non-speech frames; creep upwards during speech so a
+        // rising floor can't lock the detector open
+        for (noise, energy) in noise_db.iter_mut().zip(energy_db.iter()) {
+            let rate = match (speech, *energy > *noise) {
+                (true, _) => NOISE_CREEP,
+                (false, true) => NOISE_RISE,
+                (false, false) => NOISE_FALL,
+            };
+            *noise += rate * (energy - *noise);
+        }
+        speech
+    }
+
+    fn reset(&mut self) {
+        self.noise_db = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(amplitude: f32, seed: &mut u32) -> Vec<f32> {
+        (0..480)
+            .map(|i| {
+                *seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = 0.001 * ((*seed >> 8) as f32 / (1 << 23) as f32 - 1.0);
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / 48000.0;
+                noise + amplitude * ((220.0 * phase).sin() + (880.0 * phase).sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn speech_stands_out_from_the_learned_noise_floor() {
+        let mut seed = 3;
+        let mut detector = SubbandDetector::new(Aggressiveness::Aggressive);
+        for _ in 0..50 {
+            assert!(!detector.detect(&frame(0.0, &mut seed), 48000, false));
+        }
+        assert!(detector.detect(&frame(0.2, &mut seed), 48000, false));
+        assert!(detector.detect(&frame(0.2, &mut seed), 48000, true));
+        assert!(!detector.detect(&frame(0.0, &mut seed), 48000, true));
+
+        // Without a learned floor, the first frame becomes the noise
+        detector.reset();
+        assert!(!detector.detect(&frame(0.2, &mut seed), 48000, false));
+    }
+
+    #[test]
+    fn aggressiveness_raises_the_bar() {
+        let thresholds: Vec<f32> = [
+            Aggressiveness::Quality,
+            Aggressiveness::LowBitrate,
+            Aggressiveness::Aggressive,
+            Aggressiveness::VeryAggressive,
+        ]
+        .iter()
+        .map(|mode| mode.threshold())
+        .collect();
+        assert!(thresholds.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
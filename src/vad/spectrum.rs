@@ -0,0 +1,147 @@
+use std::f32::consts::PI;
+
+/// Power spectrum of one analysis frame, using a Hann window and a radix-2 FFT.
+pub(super) struct Spectrum {
+    size: usize,
+    window: Box<[f32]>,
+    real: Box<[f32]>,
+    imag: Box<[f32]>,
+    power: Box<[f32]>,
+    sample_rate: u32,
+}
+
+impl Spectrum {
+    /// Sized for `frame_len` samples, zero-padded to the next power of two.
+    pub fn new(frame_len: usize, sample_rate: u32) -> Self {
+        let size = frame_len.next_power_of_two().max(2);
+        let window = (0..frame_len)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / frame_len as f32).cos())
+            .collect();
+        Spectrum {
+            size,
+            window,
+            real: vec![0.0; size].into_boxed_slice(),
+            imag: vec![0.0; size].into_boxed_slice(),
+            power: vec![0.0; size / 2 + 1].into_boxed_slice(),
+            sample_rate,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn analyze(&mut self, frame: &[f32]) {
+        for (index, real) in self.real.iter_mut().enumerate() {
+            *real = match (frame.get(index), self.window.get(index)) {
+                (Some(sample), Some(window)) => sample * window,
+                _ => 0.0,
+            };
+        }
+        for imag in self.imag.iter_mut() {
+            *imag = 0.0;
+        }
+        self.fft();
+        for (bin, power) in self.power.iter_mut().enumerate() {
+            *power = self.real[bin] * self.real[bin] + self.imag[bin] * self.imag[bin];
+        }
+    }
+
+    fn fft(&mut self) {
+        let n = self.size;
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                self.real.swap(i, j);
+                self.imag.swap(i, j);
+            }
+        }
+        let mut len = 2;
+        while len <= n {
+            let angle = -2.0 * PI / len as f32;
+            for start in (0..n).step_by(len) {
+                for k in 0..len / 2 {
+                    let (sin, cos) = (angle * k as f32).sin_cos();
+                    let (a, b) = (start + k, start + k + len / 2);
+                    let re = self.real[b] * cos - self.imag[b] * sin;
+                    let im = self.real[b] * sin + self.imag[b] * cos;
+                    self.real[b] = self.real[a] - re;
+                    self.imag[b] = self.imag[a] - im;
+                    self.real[a] += re;
+                    self.imag[a] += im;
+                }
+            }
+            len <<= 1;
+        }
+    }
+
+    /// Power bins covering `low..high` Hz, clamped to the spectrum.
+    pub fn band(&self, low: f32, high: f32) -> &[f32] {
+        let bin_hz = self.sample_rate as f32 / self.size as f32;
+        let last = self.power.len();
+        let start = ((low / bin_hz).ceil() as usize).min(last);
+        let end = ((high / bin_hz).floor() as usize + 1).min(last).max(start);
+        &self.power[start..end]
+    }
+}
+
+/// Geometric over arithmetic mean: near 1 for noise, near 0 for tonal, voiced sound.
+pub(super) fn flatness(power: &[f32]) -> f32 {
+    if power.is_empty() {
+        return 1.0;
+    }
+    let floor = 1e-12;
+    let log_mean = power.iter().map(|p| (p + floor).ln()).sum::<f32>() / power.len() as f32;
+    let mean = power.iter().map(|p| p + floor).sum::<f32>() / power.len() as f32;
+    log_mean.exp() / mean
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tones_land_in_their_bin() {
+        let mut spectrum = Spectrum::new(480, 48000);
+        // 48000 / 512 Hz per bin, so bin 32 is 3000Hz
+        let tone: Vec<f32> = (0..480)
+            .map(|i| (2.0 * PI * 3000.0 * i as f32 / 48000.0).sin())
+            .collect();
+        spectrum.analyze(&tone);
+        let loudest = (0..spectrum.power.len())
+            .max_by(|&a, &b| spectrum.power[a].partial_cmp(&spectrum.power[b]).unwrap())
+            .unwrap();
+        assert_eq!(loudest, 32);
+        assert_eq!(spectrum.band(2950.0, 2990.0).len(), 0);
+        assert_eq!(spectrum.band(2900.0, 3100.0).len(), 3);
+        assert_eq!(spectrum.band(20000.0, 96000.0).len(), 43);
+        assert_eq!(spectrum.band(30000.0, 40000.0).len(), 0);
+    }
+
+    #[test]
+    fn flatness_separates_tones_from_noise() {
+        let mut spectrum = Spectrum::new(480, 48000);
+        let tone: Vec<f32> = (0..480)
+            .map(|i| (2.0 * PI * 440.0 * i as f32 / 48000.0).sin())
+            .collect();
+        spectrum.analyze(&tone);
+        assert!(flatness(spectrum.band(250.0, 4000.0)) < 0.1);
+
+        let mut seed = 1u32;
+        let noise: Vec<f32> = (0..480)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (seed >> 8) as f32 / (1 << 23) as f32 - 1.0
+            })
+            .collect();
+        spectrum.analyze(&noise);
+        assert!(flatness(spectrum.band(250.0, 4000.0)) > 0.3);
+        assert_eq!(flatness(&[]), 1.0);
+    }
+}
@@ -0,0 +1,79 @@
+//! The plugin API versions this crate has bindings for.
+//!
+//! A plugin picks its version through `MumblePluginDescriptor::api_version`, and Mumble
+//! hands over the function table laid out for that version. Functions missing from the
+//! chosen version fail with `EC_INVALID_API_VERSION` instead of being called.
+
+use crate::mumble::{v1_0_x, v1_2_x};
+use crate::types as m;
+use std::os::raw;
+
+pub const V1_0_X: m::Version = m::Version {
+    major: 1,
+    minor: 0,
+    patch: 0,
+};
+/// Adds a volume to `playSample`
+pub const V1_2_X: m::Version = m::Version {
+    major: 1,
+    minor: 2,
+    patch: 0,
+};
+
+pub const SUPPORTED: &[m::Version] = &[V1_0_X, V1_2_X];
+/// Understood by every Mumble release with the plugin framework
+pub const DEFAULT: m::Version = V1_0_X;
+
+pub fn is_supported(version: m::Version) -> bool {
+    SUPPORTED
+        .iter()
+        .any(|known| (known.major, known.minor) == (version.major, version.minor))
+}
+
+/// Mumble's function table, in the layout of the API version the plugin requested.
+#[derive(Debug, Copy, Clone)]
+pub enum RawMumbleAPI {
+    V1_0(v1_0_x::m::MumbleAPI),
+    V1_2(v1_2_x::m::MumbleAPI),
+}
+
+impl RawMumbleAPI {
+    /// Copies the struct given to `mumble_registerAPIFunctions` for a plugin that requested
+    /// `version`. Patch releases never change the layout, so only major and minor are matched.
+    ///
+    /// # Safety
+    ///
+    /// `api` must point to a readable function table in the layout of `version`, as Mumble
+    /// passes it.
+    pub unsafe fn from_ptr(version: m::Version, api: *const raw::c_void) -> Option<Self> {
+        match (version.major, version.minor) {
+            (1, 0) => Some(RawMumbleAPI::V1_0(*api.cast::<v1_0_x::m::MumbleAPI>())),
+            (1, 2) => Some(RawMumbleAPI::V1_2(*api.cast::<v1_2_x::m::MumbleAPI>())),
+            _ => None,
+        }
+    }
+
+    pub fn version(&self) -> m::Version {
+        match self {
+            RawMumbleAPI::V1_0(_) => V1_0_X,
+            RawMumbleAPI::V1_2(_) => V1_2_X,
+        }
+    }
+}
+
+unsafe impl Send for v1_0_x::m::MumbleAPI {}
+unsafe impl Send for v1_2_x::m::MumbleAPI {}
+
+pub(crate) fn unsupported() -> m::ErrorT {
+    m::ErrorT(m::ErrorCode::EC_INVALID_API_VERSION)
+}
+
+/// Reads a function that every supported version provides with the same signature.
+macro_rules! api_fn {
+    ($api: expr, $name: ident) => {
+        match $api {
+            $crate::versions::RawMumbleAPI::V1_0(api) => api.$name,
+            $crate::versions::RawMumbleAPI::V1_2(api) => api.$name,
+        }
+    };
+}
@@ -0,0 +1,14 @@
+//! Misuses of `#[mumble_plugin]` that must fail to compile with a pointed error, and the
+//! callbacks a well-formed one exports.
+
+#[test]
+fn rejects_misuse() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
+
+#[test]
+fn exports_only_overrides_and_used_subsystems() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/pass/*.rs");
+}
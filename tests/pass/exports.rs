@@ -0,0 +1,64 @@
+use mumble_sys::mumble_plugin;
+use mumble_sys::traits::{MumblePlugin, MumblePluginInit};
+use mumble_sys::types as m;
+use mumble_sys::versions::RawMumbleAPI;
+
+struct Plugin;
+
+impl MumblePluginInit for Plugin {
+    fn init(_id: m::PluginId, _api: RawMumbleAPI) -> Result<Self, m::ErrorT> {
+        Ok(Plugin)
+    }
+}
+
+#[mumble_plugin(
+    name = "Plugin",
+    author = "Someone",
+    api_version = "1.2.0",
+    uses(hotkeys)
+)]
+impl MumblePlugin for Plugin {
+    fn shutdown(&self) {}
+
+    fn on_channel_exited(
+        &mut self,
+        _conn: m::ConnectionT,
+        _user: m::UserIdT,
+        _channel: Option<m::ChannelIdT>,
+    ) {
+    }
+}
+
+// Each of these would clash with an export of the same name, had it been made
+#[allow(non_snake_case, dead_code)]
+fn mumble_onServerConnected() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onServerDisconnected() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onAudioInput() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onAudioSourceFetched() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onAudioOutputAboutToPlay() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onReceiveData() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onChannelAdded() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onChannelRemoved() {}
+#[allow(non_snake_case, dead_code)]
+fn mumble_onChannelRenamed() {}
+
+fn main() {
+    // The override
+    let _: extern "C" fn(m::ConnectionT, m::UserIdT, m::ChannelIdT) = mumble_onChannelExited;
+    // What `hotkeys` hooks, and the main-thread callbacks its long presses fire from
+    let _: extern "C" fn(u32, bool) = mumble_onKeyEvent;
+    let _: extern "C" fn(m::ConnectionT) = mumble_onServerSynchronized;
+    let _: extern "C" fn(m::ConnectionT, m::UserIdT, m::ChannelIdT, m::ChannelIdT) =
+        mumble_onChannelEntered;
+    let _: extern "C" fn(m::ConnectionT, m::UserIdT, m::TalkingStateT) =
+        mumble_onUserTalkingStateChanged;
+    let _: extern "C" fn(m::ConnectionT, m::UserIdT) = mumble_onUserAdded;
+    let _: extern "C" fn(m::ConnectionT, m::UserIdT) = mumble_onUserRemoved;
+}
@@ -0,0 +1,10 @@
+use mumble_sys::mumble_plugin;
+
+struct Plugin;
+
+#[mumble_plugin(name = "Plugin", author = "Someone", version = "1.2")]
+impl mumble_sys::traits::MumblePlugin for Plugin {
+    fn shutdown(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use mumble_sys::mumble_plugin;
+
+struct Plugin<T>(T);
+
+#[mumble_plugin(name = "Plugin", author = "Someone")]
+impl<T: Send + 'static> mumble_sys::traits::MumblePlugin for Plugin<T> {
+    fn shutdown(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use mumble_sys::mumble_plugin;
+
+struct Plugin;
+
+#[mumble_plugin(author = "Someone")]
+impl mumble_sys::traits::MumblePlugin for Plugin {
+    fn shutdown(&self) {}
+}
+
+fn main() {}
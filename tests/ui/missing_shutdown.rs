@@ -0,0 +1,10 @@
+use mumble_sys::mumble_plugin;
+
+struct Plugin;
+
+#[mumble_plugin(name = "Plugin", author = "Someone")]
+impl mumble_sys::traits::MumblePlugin for Plugin {
+    fn on_key_event(&mut self, _key_code: u32, _pressed: bool) {}
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use mumble_sys::mumble_plugin;
+
+struct Plugin;
+
+#[mumble_plugin(name = "Plugin", author = "Someone", licence = "MIT")]
+impl mumble_sys::traits::MumblePlugin for Plugin {
+    fn shutdown(&self) {}
+}
+
+fn main() {}
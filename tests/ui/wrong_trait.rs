@@ -0,0 +1,12 @@
+use mumble_sys::mumble_plugin;
+
+struct Plugin;
+
+#[mumble_plugin(name = "Plugin", author = "Someone")]
+impl Clone for Plugin {
+    fn clone(&self) -> Self {
+        Plugin
+    }
+}
+
+fn main() {}